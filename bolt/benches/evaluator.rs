@@ -0,0 +1,47 @@
+use bolt::{evaluator::evaluator::Evaluator, CompilerBackend};
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+//Programs that spend their time walking the AST rather than in builtins
+const PROGRAMS: [(&str, &str); 3] = [
+    (
+        "fibonacci",
+        "let fib = fn(n) { if (n < 2) { return n; } fib(n - 1) + fib(n - 2) }; fib(20)",
+    ),
+    (
+        "loop",
+        "let i = 0; let total = 0; while (i < 20000) { total = total + i * 2; i = i + 1; } total",
+    ),
+    (
+        "classes",
+        "class Counter { init() { this.count = 0; } add(n) { this.count = this.count + n; } };
+let c = Counter(); let i = 0; while (i < 5000) { c.add(i); i = i + 1; } c.count",
+    ),
+];
+
+fn evaluate(c: &mut Criterion) {
+    for (name, source) in PROGRAMS {
+        c.bench_function(name, |b| {
+            b.iter(|| {
+                let evaluator = Evaluator::new(black_box(source), "bench", None, false, None, "");
+                evaluator.eval()
+            })
+        });
+    }
+}
+
+//The same programs compiled to bytecode and run on the virtual machine
+fn evaluate_vm(c: &mut Criterion) {
+    for (name, source) in PROGRAMS {
+        c.bench_function(&format!("{} vm", name), |b| {
+            b.iter(|| {
+                let backend = Some(CompilerBackend::VM);
+                let evaluator =
+                    Evaluator::new(black_box(source), "bench", None, false, backend, "");
+                evaluator.eval()
+            })
+        });
+    }
+}
+
+criterion_group!(benches, evaluate, evaluate_vm);
+criterion_main!(benches);
@@ -5,9 +5,9 @@ use std::process::Command;
 use std::rc::Rc;
 use std::{env, fs, io, ptr};
 
+use crate::error::EvaluatorError;
 use crate::parser::ast::{
-    BinaryExpression, BlockStatement, Boolean, Expression, ExpressionStatement, Identifier,
-    IfExpression, IntegerLiteral, LetStatement, Program,
+    BlockStatement, Expression, Identifier, IfExpression, LetStatement, Program,
 };
 use crate::{compiler::Compiler, parser::ast::Statement};
 use llvm_sys::bit_writer::LLVMWriteBitcodeToFile;
@@ -142,14 +142,22 @@ impl LLVM {
         }
     }
 
-    unsafe fn compile_statement(&mut self, statement: &Box<dyn Statement>) {
-        let value_any = statement.as_any();
-        if let Some(stmt) = value_any.downcast_ref::<LetStatement>() {
-            self.compile_let_statement(stmt);
-        } else if let Some(expr) = value_any.downcast_ref::<ExpressionStatement>() {
-            self.compile_expression(&expr.value);
-        } else {
-            panic!("Error compiling statment")
+    unsafe fn compile_statement(&mut self, statement: &Statement) {
+        match statement {
+            Statement::Let(stmt) => self.compile_let_statement(stmt),
+            Statement::Expression(expr) => {
+                self.compile_expression(&expr.value);
+            }
+            Statement::Return(_)
+            | Statement::While(_)
+            | Statement::Class(_)
+            | Statement::Import(_)
+            | Statement::Export(_)
+            | Statement::Throw(_)
+            | Statement::Try(_)
+            | Statement::Break(_)
+            | Statement::Continue(_)
+            | Statement::Block(_) => panic!("Error compiling statment"),
         }
     }
 
@@ -310,38 +318,63 @@ impl LLVM {
 
     unsafe fn compile_expression(
         &mut self,
-        expr: &Box<dyn Expression>,
+        expr: &Expression,
     ) -> Option<(LLVMValueRef, Option<LLVMValueRef>, *mut LLVMType, bool)> {
-        let value_any = expr.as_any();
-        if let Some(int) = value_any.downcast_ref::<IntegerLiteral>() {
-            let f64_type = LLVMDoubleTypeInContext(self.context());
-            let reference = LLVMConstReal(f64_type, int.value);
-            return Some((reference, None, f64_type, false));
-        } else if let Some(binary) = value_any.downcast_ref::<BinaryExpression>() {
-            let left = self.compile_expression(&binary.left);
-            let right = self.compile_expression(&binary.right);
-            return Some(self.compile_binary_expression(left, right, binary.operator.clone()));
-        } else if let Some(boolean) = value_any.downcast_ref::<Boolean>() {
-            if boolean.value == true {
-                let true_value = LLVMConstInt(LLVMInt1Type(), 1, 0); // Represents `true`
-                return Some((true_value, None, LLVMInt1Type(), false));
-            } else {
-                let false_value = LLVMConstInt(LLVMInt1Type(), 0, 0);
-                return Some((false_value, None, LLVMInt1Type(), false));
+        match expr {
+            Expression::Integer(int) => {
+                let f64_type = LLVMDoubleTypeInContext(self.context());
+                let reference = LLVMConstReal(f64_type, int.value as f64);
+                return Some((reference, None, f64_type, false));
             }
-        } else if let Some(ident) = value_any.downcast_ref::<Identifier>() {
-            let map_val = self.compile_identifier(ident);
-            return Some((
-                map_val.ident_pointer,
-                Some(map_val.value_pointer),
-                map_val.ident_type,
-                true,
-            ));
-        } else if let Some(if_expression) = value_any.downcast_ref::<IfExpression>() {
-            self.compile_if_expression(if_expression);
-            return None;
-        } else {
-            panic!("Error compiling epxression")
+            Expression::Float(float) => {
+                let f64_type = LLVMDoubleTypeInContext(self.context());
+                let reference = LLVMConstReal(f64_type, float.value);
+                return Some((reference, None, f64_type, false));
+            }
+            Expression::Binary(binary) => {
+                let left = self.compile_expression(&binary.left);
+                let right = self.compile_expression(&binary.right);
+                return Some(self.compile_binary_expression(left, right, binary.operator.clone()));
+            }
+            Expression::Boolean(boolean) => {
+                if boolean.value == true {
+                    let true_value = LLVMConstInt(LLVMInt1Type(), 1, 0); // Represents `true`
+                    return Some((true_value, None, LLVMInt1Type(), false));
+                } else {
+                    let false_value = LLVMConstInt(LLVMInt1Type(), 0, 0);
+                    return Some((false_value, None, LLVMInt1Type(), false));
+                }
+            }
+            Expression::Identifier(ident) => {
+                let map_val = self.compile_identifier(ident);
+                return Some((
+                    map_val.ident_pointer,
+                    Some(map_val.value_pointer),
+                    map_val.ident_type,
+                    true,
+                ));
+            }
+            Expression::If(if_expression) => {
+                self.compile_if_expression(if_expression);
+                return None;
+            }
+            Expression::String(_)
+            | Expression::InterpolatedString(_)
+            | Expression::Null(_)
+            | Expression::Prefix(_)
+            | Expression::Logical(_)
+            | Expression::Match(_)
+            | Expression::Function(_)
+            | Expression::Call(_)
+            | Expression::Array(_)
+            | Expression::Hash(_)
+            | Expression::Index(_)
+            | Expression::Assign(_)
+            | Expression::Get(_)
+            | Expression::This(_)
+            | Expression::Super(_)
+            | Expression::Yield(_)
+            | Expression::Await(_) => panic!("Error compiling epxression"),
         }
     }
 
@@ -390,13 +423,14 @@ impl LLVM {
 }
 
 impl Compiler for LLVM {
-    fn compile(&mut self) {
+    fn compile(&mut self) -> Result<(), EvaluatorError> {
         let program = self.program.clone();
         unsafe { self.set_main_func() };
         for stmt in &program.stmts {
             unsafe { self.compile_statement(stmt) };
         }
         unsafe { self.set_return_main_func() };
+        return Ok(());
     }
 
     fn generate_ir(&mut self) -> String {
@@ -418,10 +452,10 @@ impl Compiler for LLVM {
     }
 
     //TODO: Fix this bad JIT
-    fn bytecode_to_jit(&mut self, _target: &String) {
+    fn bytecode_to_jit(&mut self, _target: &String) -> Result<(), EvaluatorError> {
         // Step 1: Generate assembly from LLVM IR
         let output_dir = setup_output_directory().unwrap();
-        self.compile();
+        self.compile()?;
 
         // Construct the absolute path for the output file
         let output_path = output_dir
@@ -480,6 +514,7 @@ impl Compiler for LLVM {
         let status = Command::new(&executable_file).output().unwrap();
 
         print_output("example", &status);
+        return Ok(());
     }
 
     fn clean(&mut self) {
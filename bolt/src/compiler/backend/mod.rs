@@ -1 +1,2 @@
 pub(crate) mod llvm;
+pub(crate) mod vm;
@@ -0,0 +1,81 @@
+use std::rc::Rc;
+
+use crate::object::object::Value;
+
+use super::instruction::Instruction;
+
+//Where a closure gets a captured variable from when it is created
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Capture {
+    Cell(usize),    //A cell of the frame creating the closure
+    Upvalue(usize), //An upvalue of the closure creating the closure
+}
+
+/*
+Bytecode of one function literal, method or module body. The locals that no
+nested function captures live in stack slots of the frame, captured ones live
+in cells that closures share
+*/
+#[derive(Debug)]
+pub struct CompiledFunction {
+    pub name: String,
+    pub parameters: Vec<String>,
+    pub code: Vec<Instruction>,
+    pub lines: Vec<usize>, //Line of the statement each instruction was compiled from
+    pub constants: Vec<Value>,
+    pub functions: Vec<Rc<CompiledFunction>>,
+    pub captures: Vec<(String, Capture)>, //Name of each upvalue and where it is captured from
+    pub slots: Vec<String>,               //Name of the local in each slot
+    pub cells: Vec<String>,               //Name of the local in each cell
+    pub this_slot: Option<usize>,         //Methods receive the instance in this slot
+    pub is_generator: bool,
+    pub is_async: bool,
+}
+
+impl CompiledFunction {
+    pub fn new(name: &str, parameters: Vec<String>) -> Self {
+        return Self {
+            name: String::from(name),
+            parameters: parameters,
+            code: vec![],
+            lines: vec![],
+            constants: vec![],
+            functions: vec![],
+            captures: vec![],
+            slots: vec![],
+            cells: vec![],
+            this_slot: None,
+            is_generator: false,
+            is_async: false,
+        };
+    }
+
+    pub fn inspect(&self) -> String {
+        return format!("fn({})", self.parameters.join(","));
+    }
+
+    //Listing of the instructions of the function followed by the functions nested in it
+    pub fn disassemble(&self) -> String {
+        let mut listing = format!(
+            "== {} ({} slots, {} cells) ==\n",
+            self.name,
+            self.slots.len(),
+            self.cells.len()
+        );
+        for (position, instruction) in self.code.iter().enumerate() {
+            listing.push_str(&format!(
+                "{:04} {:>4} {:?}",
+                position, self.lines[position], instruction
+            ));
+            if let Instruction::Constant(index) = instruction {
+                listing.push_str(&format!(" ; {}", self.constants[*index].inspect()));
+            }
+            listing.push('\n');
+        }
+        for function in &self.functions {
+            listing.push('\n');
+            listing.push_str(&function.disassemble());
+        }
+        return listing;
+    }
+}
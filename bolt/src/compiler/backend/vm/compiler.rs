@@ -0,0 +1,1001 @@
+use std::{
+    collections::{HashMap, HashSet},
+    rc::Rc,
+};
+
+use crate::{
+    object::object::Value,
+    parser::ast::{
+        Binding, BlockStatement, ClassStatement, Expression, FunctionLiteral, Identifier,
+        LetStatement, MatchExpression, Pattern, Program, Statement, StringPart, TryStatement,
+        WhileStatement,
+    },
+    resolver::scan::{captured_names, declared_names, pattern_names},
+};
+
+use super::{
+    chunk::{Capture, CompiledFunction},
+    instruction::{BinaryOperator, Instruction, PrefixOperator},
+};
+
+#[derive(Debug, Clone, Copy)]
+enum Storage {
+    Slot(usize),
+    Cell(usize),
+}
+
+struct Local {
+    name: String,
+    storage: Storage,
+}
+
+enum Resolved {
+    Local(Storage),
+    Upvalue(usize),
+    Global,
+}
+
+struct Loop {
+    start: usize,
+    breaks: Vec<usize>,
+    depth: usize, //Temporaries on the stack when the loop started
+    tries: usize, //Try statements the loop is inside of
+}
+
+//A try statement whose handler is installed while its body or catch runs
+struct TryContext {
+    finally_body: Option<Rc<Box<BlockStatement>>>,
+}
+
+//Top level statement being compiled, a return ends it with the returned value
+struct TopLevelStatement {
+    exits: Vec<usize>,
+    depth: usize,
+}
+
+struct FunctionState {
+    function: CompiledFunction,
+    scopes: Vec<Vec<Local>>,
+    captured: HashSet<String>,
+    upvalues: Vec<(String, Capture)>,
+    loops: Vec<Loop>,
+    tries: Vec<TryContext>,
+    names: HashMap<String, usize>, //Constants holding names, shared by every use
+    depth: usize,                  //Temporaries on the stack above the slots
+    line: usize,
+    top_level: bool, //Body of a program or module, its outermost lets are globals
+    statement: Option<TopLevelStatement>,
+    return_slot: Option<usize>,
+}
+
+/*
+Compiles a program to bytecode for the virtual machine. Functions nested in
+the program are compiled with it, each one keeps track of the scopes, loops
+and try statements it is inside of while its body is compiled. The scopes
+are the ones the resolver made, in the same order and with the locals in
+the same order, so a binding it found is the local at that position
+*/
+pub struct BytecodeCompiler {
+    states: Vec<FunctionState>,
+}
+
+//The program has to be resolved first
+pub fn compile_program(program: &Program, name: &str) -> CompiledFunction {
+    let mut compiler = BytecodeCompiler { states: vec![] };
+    //The outermost lets of a program are globals, so it starts without a scope
+    compiler.push_state(name, &[], &program.stmts, 0);
+    compiler.state_mut().top_level = true;
+    if program.stmts.is_empty() {
+        compiler.emit(Instruction::Null);
+    }
+    for (index, statement) in program.stmts.iter().enumerate() {
+        if index > 0 {
+            compiler.emit(Instruction::Pop);
+        }
+        let depth = compiler.state().depth;
+        compiler.state_mut().statement = Some(TopLevelStatement {
+            exits: vec![],
+            depth: depth,
+        });
+        compiler.compile_statement(statement);
+        if let Some(statement) = compiler.state_mut().statement.take() {
+            for exit in statement.exits {
+                compiler.patch_jump(exit);
+            }
+        }
+    }
+    compiler.emit(Instruction::Return);
+    return compiler.end_function();
+}
+
+impl BytecodeCompiler {
+    fn state(&self) -> &FunctionState {
+        return self.states.last().unwrap();
+    }
+
+    fn state_mut(&mut self) -> &mut FunctionState {
+        return self.states.last_mut().unwrap();
+    }
+
+    fn emit(&mut self, instruction: Instruction) -> usize {
+        let state = self.state_mut();
+        state.function.code.push(instruction);
+        state.function.lines.push(state.line);
+        state.depth = (state.depth as isize + instruction.stack_effect()) as usize;
+        return state.function.code.len() - 1;
+    }
+
+    //Code after a jump, return or throw is only reached through a jump, which sets the count it starts with
+    fn set_depth(&mut self, depth: usize) {
+        self.state_mut().depth = depth;
+    }
+
+    fn pop_to(&mut self, depth: usize) {
+        if self.state().depth != depth {
+            self.emit(Instruction::PopTo(depth));
+            self.set_depth(depth);
+        }
+    }
+
+    //Jumps from failed patterns arrive with different counts, so the count is always set at runtime
+    fn drop_to(&mut self, depth: usize) {
+        self.emit(Instruction::PopTo(depth));
+        self.set_depth(depth);
+    }
+
+    fn position(&self) -> usize {
+        return self.state().function.code.len();
+    }
+
+    //Point the jump at the next instruction to be emitted
+    fn patch_jump(&mut self, position: usize) {
+        let target = self.position();
+        let code = &mut self.state_mut().function.code;
+        code[position] = match code[position] {
+            Instruction::Jump(_) => Instruction::Jump(target),
+            Instruction::JumpIfFalse(_) => Instruction::JumpIfFalse(target),
+            Instruction::And(_) => Instruction::And(target),
+            Instruction::Or(_) => Instruction::Or(target),
+            Instruction::PushHandler(_) => Instruction::PushHandler(target),
+            instruction => instruction,
+        };
+    }
+
+    fn constant(&mut self, value: Value) -> usize {
+        let constants = &mut self.state_mut().function.constants;
+        constants.push(value);
+        return constants.len() - 1;
+    }
+
+    fn name(&mut self, name: &str) -> usize {
+        if let Some(index) = self.state().names.get(name) {
+            return *index;
+        }
+        let index = self.constant(Value::string(String::from(name)));
+        self.state_mut().names.insert(String::from(name), index);
+        return index;
+    }
+
+    fn fail(&mut self, message: &str) {
+        let message = self.constant(Value::string(String::from(message)));
+        self.emit(Instruction::Fail(message));
+    }
+
+    fn push_state(
+        &mut self,
+        name: &str,
+        parameters: &[Identifier],
+        body: &[Statement],
+        line: usize,
+    ) {
+        self.states.push(FunctionState {
+            function: CompiledFunction::new(name, names_of(parameters)),
+            scopes: vec![],
+            captured: captured_names(body),
+            upvalues: vec![],
+            loops: vec![],
+            tries: vec![],
+            names: HashMap::new(),
+            depth: 0,
+            line: line,
+            top_level: false,
+            statement: None,
+            return_slot: None,
+        });
+    }
+
+    fn begin_function(
+        &mut self,
+        name: &str,
+        parameters: &[Identifier],
+        body: &[Statement],
+        method: bool,
+        line: usize,
+    ) {
+        self.push_state(name, parameters, body, line);
+        self.state_mut().scopes.push(vec![]);
+        //Arguments are placed in the first slots, captured ones are moved to their cells
+        let mut receivers: Vec<String> = names_of(parameters);
+        if method {
+            receivers.push(String::from("this"));
+            self.state_mut().function.this_slot = Some(parameters.len());
+        }
+        for (slot, name) in receivers.into_iter().enumerate() {
+            self.new_slot(&name);
+            let storage = match self.state().captured.contains(&name) {
+                true => {
+                    let cell = self.new_cell(&name);
+                    self.emit(Instruction::MoveToCell(slot, cell));
+                    Storage::Cell(cell)
+                }
+                false => Storage::Slot(slot),
+            };
+            self.add_local(name, storage);
+        }
+        //Locals declared anywhere in the body exist from the start, so closures can refer to later ones
+        for name in declared_names(body) {
+            if self.find_in_scope(&name).is_none() {
+                let storage = self.allocate(&name);
+                self.add_local(name, storage);
+            }
+        }
+    }
+
+    fn end_function(&mut self) -> CompiledFunction {
+        let state = self.states.pop().unwrap();
+        let mut function = state.function;
+        function.captures = state.upvalues;
+        return function;
+    }
+
+    fn new_slot(&mut self, name: &str) -> usize {
+        let slots = &mut self.state_mut().function.slots;
+        slots.push(String::from(name));
+        return slots.len() - 1;
+    }
+
+    fn new_cell(&mut self, name: &str) -> usize {
+        let cells = &mut self.state_mut().function.cells;
+        cells.push(String::from(name));
+        return cells.len() - 1;
+    }
+
+    fn allocate(&mut self, name: &str) -> Storage {
+        if self.state().captured.contains(name) {
+            return Storage::Cell(self.new_cell(name));
+        }
+        return Storage::Slot(self.new_slot(name));
+    }
+
+    fn add_local(&mut self, name: String, storage: Storage) {
+        self.state_mut().scopes.last_mut().unwrap().push(Local {
+            name: name,
+            storage: storage,
+        });
+    }
+
+    fn find_in_scope(&self, name: &str) -> Option<Storage> {
+        let scope = self.state().scopes.last()?;
+        return scope
+            .iter()
+            .find(|local| local.name == name)
+            .map(|local| local.storage);
+    }
+
+    /*
+    Start a scope of a catch block, match arm or class. Bound names hold a
+    value from the start, the others once their declaration runs. Captured
+    locals get fresh cells each time the scope is entered and the slots of
+    the others are cleared, a scope run again starts without its locals
+    */
+    fn begin_scope(&mut self, bound: Vec<String>, declared: Vec<String>) {
+        self.state_mut().scopes.push(vec![]);
+        let names = bound.iter().map(|name| (name, true));
+        for (name, is_bound) in names.chain(declared.iter().map(|name| (name, false))) {
+            if self.find_in_scope(name).is_some() {
+                continue;
+            }
+            let storage = self.allocate(name);
+            match storage {
+                Storage::Cell(cell) => {
+                    self.emit(Instruction::NewCell(cell));
+                }
+                Storage::Slot(slot) if !is_bound => {
+                    self.emit(Instruction::ClearLocal(slot));
+                }
+                Storage::Slot(_) => {}
+            }
+            self.add_local(name.clone(), storage);
+        }
+    }
+
+    fn end_scope(&mut self) {
+        self.state_mut().scopes.pop();
+    }
+
+    fn resolve(&mut self, name: &str, binding: Binding) -> Resolved {
+        let (function, storage) = match binding {
+            Binding::Local { depth, slot } => match self.find_local(depth, slot) {
+                Some(local) => local,
+                None => return Resolved::Global,
+            },
+            Binding::Global => return Resolved::Global,
+        };
+        let current = self.states.len() - 1;
+        if function == current {
+            return Resolved::Local(storage);
+        }
+        match self.resolve_upvalue(current, function, storage, name) {
+            Some(upvalue) => return Resolved::Upvalue(upvalue),
+            None => return Resolved::Global,
+        }
+    }
+
+    //Function and storage of the local depth scopes out, the top level has no scope of its own
+    fn find_local(&self, depth: usize, slot: usize) -> Option<(usize, Storage)> {
+        let mut scopes = self
+            .states
+            .iter()
+            .enumerate()
+            .rev()
+            .flat_map(|(function, state)| {
+                state
+                    .scopes
+                    .iter()
+                    .rev()
+                    .map(move |scope| (function, scope))
+            });
+        let (function, scope) = scopes.nth(depth)?;
+        return scope.get(slot).map(|local| (function, local.storage));
+    }
+
+    //Capture a local of the enclosing function owner, every function in between captures it too
+    fn resolve_upvalue(
+        &mut self,
+        function: usize,
+        owner: usize,
+        storage: Storage,
+        name: &str,
+    ) -> Option<usize> {
+        let capture = match (function - 1 == owner, storage) {
+            (true, Storage::Cell(cell)) => Capture::Cell(cell),
+            (true, Storage::Slot(_)) => return None,
+            (false, _) => {
+                Capture::Upvalue(self.resolve_upvalue(function - 1, owner, storage, name)?)
+            }
+        };
+        let upvalues = &mut self.states[function].upvalues;
+        if let Some(index) = upvalues.iter().position(|upvalue| upvalue.1 == capture) {
+            return Some(index);
+        }
+        upvalues.push((String::from(name), capture));
+        return Some(upvalues.len() - 1);
+    }
+
+    fn load(&mut self, name: &str, binding: Binding) {
+        match self.resolve(name, binding) {
+            Resolved::Local(Storage::Slot(slot)) => self.emit(Instruction::GetLocal(slot)),
+            Resolved::Local(Storage::Cell(cell)) => self.emit(Instruction::GetCell(cell)),
+            Resolved::Upvalue(upvalue) => self.emit(Instruction::GetUpvalue(upvalue)),
+            Resolved::Global => {
+                let name = self.name(name);
+                self.emit(Instruction::GetGlobal(name))
+            }
+        };
+    }
+
+    //Assign the value on top of the stack to a variable, which fails when it is not declared yet
+    fn store(&mut self, name: &str, binding: Binding) {
+        match self.resolve(name, binding) {
+            Resolved::Local(Storage::Slot(slot)) => {
+                self.emit(Instruction::SetLocal(slot));
+            }
+            Resolved::Local(Storage::Cell(cell)) => {
+                self.emit(Instruction::SetCell(cell));
+            }
+            Resolved::Upvalue(upvalue) => {
+                self.emit(Instruction::SetUpvalue(upvalue));
+            }
+            Resolved::Global => {
+                let name = self.name(name);
+                self.emit(Instruction::SetGlobal(name));
+            }
+        }
+    }
+
+    //Initialize a local with the value on top of the stack
+    fn store_local(&mut self, storage: Storage) {
+        match storage {
+            Storage::Slot(slot) => self.emit(Instruction::DefineLocal(slot)),
+            Storage::Cell(cell) => self.emit(Instruction::DefineCell(cell)),
+        };
+    }
+
+    /*
+    Bind the value on top of the stack to a name declared by a let, class,
+    import, catch or pattern. The resolver adds a name it did not find ahead of
+    time at the end of the scope, so it gets the next local here as well
+    */
+    fn define(&mut self, identifier: &Identifier) {
+        let slot = match identifier.binding.get() {
+            Binding::Local { slot, .. } => slot,
+            Binding::Global => {
+                let name = self.name(&identifier.value);
+                self.emit(Instruction::DefineGlobal(name));
+                return;
+            }
+        };
+        let scope = self.state().scopes.last().unwrap();
+        let storage = match scope.get(slot) {
+            Some(local) => local.storage,
+            None => {
+                let storage = self.allocate(&identifier.value);
+                self.add_local(identifier.value.clone(), storage);
+                storage
+            }
+        };
+        self.store_local(storage);
+    }
+
+    fn compile_block(&mut self, statements: &[Statement]) {
+        //An empty block evaluates to null
+        if statements.is_empty() {
+            self.emit(Instruction::Null);
+        }
+        for (index, statement) in statements.iter().enumerate() {
+            if index > 0 {
+                self.emit(Instruction::Pop);
+            }
+            self.compile_statement(statement);
+        }
+    }
+
+    //Every statement leaves its value on the stack
+    fn compile_statement(&mut self, statement: &Statement) {
+        let line = self.state().line;
+        let depth = self.state().depth;
+        self.state_mut().line = statement.line();
+        match statement {
+            Statement::Expression(expression) => self.compile_expression(&expression.value),
+            Statement::Block(block) => self.compile_block(&block.statements),
+            Statement::Let(let_statement) => self.compile_let(let_statement),
+            Statement::Return(return_statement) => {
+                self.compile_expression(&return_statement.value);
+                self.compile_return();
+                self.set_depth(depth + 1);
+            }
+            Statement::While(while_statement) => self.compile_while(while_statement),
+            Statement::Class(class_statement) => self.compile_class(class_statement),
+            Statement::Throw(throw) => {
+                self.compile_expression(&throw.value);
+                self.emit(Instruction::Throw);
+                self.set_depth(depth + 1);
+            }
+            Statement::Try(try_statement) => self.compile_try(try_statement),
+            Statement::Import(import) => {
+                let path = self.constant(Value::string(import.path.clone()));
+                self.emit(Instruction::Import {
+                    path: path,
+                    alias: import.alias.is_some(),
+                });
+                if let Some(alias) = import.alias.as_ref() {
+                    self.define(alias);
+                }
+            }
+            Statement::Export(export) => match export.statement.as_ref() {
+                Statement::Let(let_statement) => {
+                    self.compile_let(let_statement);
+                    let name = self.name(&let_statement.identifier.value);
+                    self.emit(Instruction::Export(name));
+                }
+                _ => {
+                    self.fail("Only let statements can be exported");
+                    self.set_depth(depth + 1);
+                }
+            },
+            Statement::Break(_) => {
+                let target = self.state().loops.len() - 1;
+                let (depth, tries) = (
+                    self.state().loops[target].depth,
+                    self.state().loops[target].tries,
+                );
+                self.exit_tries(tries);
+                self.pop_to(depth);
+                let jump = self.emit(Instruction::Jump(0));
+                self.state_mut().loops[target].breaks.push(jump);
+                self.set_depth(depth + 1);
+            }
+            Statement::Continue(_) => {
+                let target = self.state().loops.len() - 1;
+                let (depth, tries) = (
+                    self.state().loops[target].depth,
+                    self.state().loops[target].tries,
+                );
+                let start = self.state().loops[target].start;
+                self.exit_tries(tries);
+                self.pop_to(depth);
+                self.emit(Instruction::Jump(start));
+                self.set_depth(depth + 1);
+            }
+        }
+        self.state_mut().line = line;
+    }
+
+    fn compile_let(&mut self, let_statement: &LetStatement) {
+        let name = &let_statement.identifier.value;
+        match let_statement.value.as_ref() {
+            Expression::Function(function) => self.compile_function(function, name, false),
+            value => self.compile_expression(value),
+        }
+        self.define(&let_statement.identifier);
+    }
+
+    /*
+    A return inside a function leaves its frame. Outside of any function it only
+    ends the top level statement it is in, which then has the returned value
+    */
+    fn compile_return(&mut self) {
+        self.exit_tries(0);
+        if !self.state().top_level {
+            self.emit(Instruction::Return);
+            return;
+        }
+        self.emit(Instruction::WrapReturn);
+        let slot = match self.state().return_slot {
+            Some(slot) => slot,
+            None => {
+                let slot = self.new_slot("return");
+                self.state_mut().return_slot = Some(slot);
+                slot
+            }
+        };
+        self.emit(Instruction::DefineLocal(slot));
+        let depth = self.state().statement.as_ref().unwrap().depth;
+        self.pop_to(depth);
+        self.emit(Instruction::GetLocal(slot));
+        let exit = self.emit(Instruction::Jump(0));
+        self.state_mut()
+            .statement
+            .as_mut()
+            .unwrap()
+            .exits
+            .push(exit);
+    }
+
+    //Leave the try statements entered since the given number, running their finally blocks
+    fn exit_tries(&mut self, keep: usize) {
+        let mut index = self.state().tries.len();
+        while index > keep {
+            index -= 1;
+            let finally_body = self.state().tries[index].finally_body.clone();
+            //The finally block runs outside of its own try statement
+            let inner = self.state_mut().tries.split_off(index);
+            self.emit(Instruction::PopHandler);
+            if let Some(finally_body) = finally_body {
+                self.compile_block(&finally_body.statements);
+                self.emit(Instruction::Pop);
+            }
+            self.state_mut().tries.extend(inner);
+        }
+    }
+
+    fn compile_while(&mut self, while_statement: &WhileStatement) {
+        let start = self.position();
+        let depth = self.state().depth;
+        let tries = self.state().tries.len();
+        self.state_mut().loops.push(Loop {
+            start: start,
+            breaks: vec![],
+            depth: depth,
+            tries: tries,
+        });
+        self.compile_expression(&while_statement.condition);
+        let exit = self.emit(Instruction::JumpIfFalse(0));
+        self.compile_block(&while_statement.body.statements);
+        self.emit(Instruction::Pop);
+        self.emit(Instruction::Jump(start));
+        self.patch_jump(exit);
+        let compiled = self.state_mut().loops.pop().unwrap();
+        for jump in compiled.breaks {
+            self.patch_jump(jump);
+        }
+        self.set_depth(depth);
+        self.emit(Instruction::Null);
+    }
+
+    /*
+    The handler of the body catches errors into the catch block. When there is a
+    finally block the catch gets a handler too, which runs finally and raises
+    the error again. Normal exits run the finally block inline
+    */
+    fn compile_try(&mut self, try_statement: &TryStatement) {
+        let depth = self.state().depth;
+        let finally_body = try_statement.finally_body.clone();
+        let handler = self.emit(Instruction::PushHandler(0));
+        self.state_mut().tries.push(TryContext {
+            finally_body: finally_body.clone(),
+        });
+        self.compile_block(&try_statement.body.statements);
+        self.state_mut().tries.pop();
+        self.emit(Instruction::PopHandler);
+        self.compile_finally(&finally_body);
+        let mut exits = vec![self.emit(Instruction::Jump(0))];
+
+        //The error object is pushed when the handler is entered
+        self.patch_jump(handler);
+        self.set_depth(depth + 1);
+        if let (Some(parameter), Some(catch_body)) = (
+            try_statement.catch_parameter.as_ref(),
+            try_statement.catch_body.as_ref(),
+        ) {
+            self.begin_scope(
+                vec![parameter.value.clone()],
+                declared_names(&catch_body.statements),
+            );
+            self.define(parameter);
+            self.emit(Instruction::Pop);
+            let finally_handler = match finally_body.is_some() {
+                true => {
+                    let finally_handler = self.emit(Instruction::PushHandler(0));
+                    self.state_mut().tries.push(TryContext {
+                        finally_body: finally_body.clone(),
+                    });
+                    Some(finally_handler)
+                }
+                false => None,
+            };
+            self.compile_block(&catch_body.statements);
+            if let Some(finally_handler) = finally_handler {
+                self.state_mut().tries.pop();
+                self.emit(Instruction::PopHandler);
+                self.compile_finally(&finally_body);
+                exits.push(self.emit(Instruction::Jump(0)));
+                self.patch_jump(finally_handler);
+                self.set_depth(depth + 1);
+            }
+            self.end_scope();
+            if finally_handler.is_none() {
+                for exit in exits {
+                    self.patch_jump(exit);
+                }
+                return;
+            }
+        }
+        //The error stays below the finally block and is raised again after it
+        self.compile_finally(&finally_body);
+        self.emit(Instruction::Rethrow);
+        for exit in exits {
+            self.patch_jump(exit);
+        }
+        self.set_depth(depth + 1);
+    }
+
+    fn compile_finally(&mut self, finally_body: &Option<Rc<Box<BlockStatement>>>) {
+        if let Some(finally_body) = finally_body {
+            self.compile_block(&finally_body.statements);
+            self.emit(Instruction::Pop);
+        }
+    }
+
+    fn compile_class(&mut self, class_statement: &ClassStatement) {
+        //Methods of a subclass see the superclass as super
+        if let Some(superclass) = class_statement.superclass.as_ref() {
+            self.load(&superclass.value, superclass.binding.get());
+            self.begin_scope(vec![String::from("super")], vec![]);
+            let storage = self.find_in_scope("super").unwrap();
+            self.store_local(storage);
+        }
+        for method in &class_statement.methods {
+            let name = self.constant(Value::string(method.name.value.clone()));
+            self.emit(Instruction::Constant(name));
+            self.compile_function(&method.function, &method.name.value, true);
+        }
+        if class_statement.superclass.is_some() {
+            self.end_scope();
+        }
+        let name = self.name(&class_statement.name.value);
+        self.emit(Instruction::Class {
+            name: name,
+            methods: class_statement.methods.len(),
+            inherits: class_statement.superclass.is_some(),
+        });
+        self.define(&class_statement.name);
+    }
+
+    fn compile_function(&mut self, function: &FunctionLiteral, name: &str, method: bool) {
+        let line = self.state().line;
+        let body = &function.body.statements;
+        self.begin_function(name, &function.parameters, body, method, line);
+        let state = self.state_mut();
+        state.function.is_generator = function.is_generator;
+        state.function.is_async = function.is_async;
+        self.compile_block(body);
+        //Running past the end of a generator finishes it with null
+        if function.is_generator {
+            self.emit(Instruction::Pop);
+            self.emit(Instruction::Null);
+        }
+        self.emit(Instruction::Return);
+        let compiled = self.end_function();
+        let functions = &mut self.state_mut().function.functions;
+        functions.push(Rc::new(compiled));
+        let index = functions.len() - 1;
+        self.emit(Instruction::Closure(index));
+    }
+
+    fn compile_expression(&mut self, expression: &Expression) {
+        match expression {
+            Expression::Integer(int) => {
+                let constant = self.constant(Value::Integer(int.value));
+                self.emit(Instruction::Constant(constant));
+            }
+            Expression::Float(float) => {
+                let constant = self.constant(Value::Float(float.value));
+                self.emit(Instruction::Constant(constant));
+            }
+            Expression::String(string) => {
+                let constant = self.constant(Value::string(string.value.clone()));
+                self.emit(Instruction::Constant(constant));
+            }
+            Expression::InterpolatedString(interpolated) => {
+                for part in &interpolated.parts {
+                    match part {
+                        StringPart::Literal(literal) => {
+                            let constant = self.constant(Value::string(literal.clone()));
+                            self.emit(Instruction::Constant(constant));
+                        }
+                        StringPart::Expression(expression) => self.compile_expression(expression),
+                    }
+                }
+                self.emit(Instruction::Interpolate(interpolated.parts.len()));
+            }
+            Expression::Null(_) => {
+                self.emit(Instruction::Null);
+            }
+            Expression::Boolean(boolean) => {
+                match boolean.value {
+                    true => self.emit(Instruction::True),
+                    false => self.emit(Instruction::False),
+                };
+            }
+            Expression::Prefix(prefix) => {
+                self.compile_expression(&prefix.right);
+                //An unknown operator fails, its operand stands in for the value it never produces
+                match PrefixOperator::from_operator(&prefix.operator) {
+                    Some(operator) => {
+                        self.emit(Instruction::Prefix(operator));
+                    }
+                    None => self.fail("Prefix that you are trying is not allowed"),
+                }
+            }
+            Expression::Binary(binary) => {
+                self.compile_expression(&binary.left);
+                self.compile_expression(&binary.right);
+                match BinaryOperator::from_operator(&binary.operator) {
+                    Some(operator) => {
+                        self.emit(Instruction::Binary(operator));
+                    }
+                    None => {
+                        self.emit(Instruction::Pop);
+                        self.fail("Binary Expression oprerator match failed");
+                    }
+                }
+            }
+            Expression::Logical(logical) => {
+                self.compile_expression(&logical.left);
+                let jump = match logical.operator.as_str() {
+                    "&&" => self.emit(Instruction::And(0)),
+                    "||" => self.emit(Instruction::Or(0)),
+                    _ => {
+                        let message = format!("Unknown logical operator {}", logical.operator);
+                        self.fail(&message);
+                        self.emit(Instruction::Or(0))
+                    }
+                };
+                self.compile_expression(&logical.right);
+                self.patch_jump(jump);
+            }
+            Expression::If(if_expression) => {
+                self.compile_expression(&if_expression.condition);
+                let alternate = self.emit(Instruction::JumpIfFalse(0));
+                self.compile_block(&if_expression.consequence.statements);
+                let exit = self.emit(Instruction::Jump(0));
+                self.patch_jump(alternate);
+                self.set_depth(self.state().depth - 1);
+                match if_expression.alternate.as_ref() {
+                    Some(alternate) => self.compile_block(&alternate.statements),
+                    None => {
+                        self.emit(Instruction::Null);
+                    }
+                }
+                self.patch_jump(exit);
+            }
+            Expression::Match(match_expression) => self.compile_match(match_expression),
+            Expression::Identifier(identifier) => {
+                self.load(&identifier.value, identifier.binding.get())
+            }
+            Expression::Function(function) => self.compile_function(function, "fn", false),
+            Expression::Call(call) => {
+                self.compile_expression(&call.funtion);
+                for parameter in call.parameters.iter() {
+                    self.compile_expression(parameter);
+                }
+                self.emit(Instruction::Call(call.parameters.len()));
+            }
+            Expression::Array(array) => {
+                for element in &array.elements {
+                    self.compile_expression(element);
+                }
+                self.emit(Instruction::Array(array.elements.len()));
+            }
+            Expression::Hash(hash) => {
+                for (key, value) in &hash.pairs {
+                    self.compile_expression(key);
+                    self.compile_expression(value);
+                }
+                self.emit(Instruction::Hash(hash.pairs.len()));
+            }
+            Expression::Index(index) => {
+                self.compile_expression(&index.left);
+                self.compile_expression(&index.index);
+                self.emit(Instruction::Index);
+            }
+            Expression::Assign(assign) => match assign.target.as_ref() {
+                Expression::Identifier(identifier) => {
+                    self.compile_expression(&assign.value);
+                    self.store(&identifier.value, identifier.binding.get());
+                }
+                Expression::Index(index) => {
+                    self.compile_expression(&index.left);
+                    self.compile_expression(&index.index);
+                    self.compile_expression(&assign.value);
+                    self.emit(Instruction::SetIndex);
+                }
+                Expression::Get(get) => {
+                    self.compile_expression(&get.object);
+                    self.compile_expression(&assign.value);
+                    let name = self.name(&get.name.value);
+                    self.emit(Instruction::SetProperty(name));
+                }
+                _ => {
+                    self.fail("Invalid assignment target");
+                    self.emit(Instruction::Null);
+                }
+            },
+            Expression::Get(get) => {
+                self.compile_expression(&get.object);
+                let name = self.name(&get.name.value);
+                self.emit(Instruction::GetProperty(name));
+            }
+            Expression::This(this) => match this.binding.get() {
+                Binding::Global => {
+                    self.fail("this is only available inside methods");
+                    self.emit(Instruction::Null);
+                }
+                binding => self.load("this", binding),
+            },
+            Expression::Super(super_expression) => {
+                let (binding, this_binding) = (
+                    super_expression.binding.get(),
+                    super_expression.this_binding.get(),
+                );
+                if binding == Binding::Global || this_binding == Binding::Global {
+                    self.fail("super is only available inside methods of a subclass");
+                    self.emit(Instruction::Null);
+                    return;
+                }
+                self.load("super", binding);
+                self.load("this", this_binding);
+                let name = self.name(&super_expression.method.value);
+                self.emit(Instruction::GetSuper(name));
+            }
+            Expression::Yield(yield_expression) => {
+                match yield_expression.value.as_ref() {
+                    Some(value) => self.compile_expression(value),
+                    None => {
+                        self.emit(Instruction::Null);
+                    }
+                }
+                self.emit(Instruction::Yield);
+            }
+            Expression::Await(await_expression) => {
+                self.compile_expression(&await_expression.value);
+                self.emit(Instruction::Await);
+            }
+        }
+    }
+
+    /*
+    The value being matched stays on the stack while the arms are tried. A
+    pattern that fails jumps to the end of its arm, which drops whatever the
+    pattern left on the stack and tries the next arm
+    */
+    fn compile_match(&mut self, match_expression: &MatchExpression) {
+        self.compile_expression(&match_expression.value);
+        let depth = self.state().depth;
+        let mut exits = vec![];
+        for arm in &match_expression.arms {
+            let mut bound = vec![];
+            pattern_names(&arm.pattern, &mut bound);
+            self.begin_scope(bound, declared_names(&arm.body.statements));
+            self.emit(Instruction::Dup);
+            let mut fails = vec![];
+            self.compile_pattern(&arm.pattern, &mut fails);
+            self.emit(Instruction::Pop);
+            self.compile_block(&arm.body.statements);
+            exits.push(self.emit(Instruction::Jump(0)));
+            self.end_scope();
+            for fail in fails {
+                self.patch_jump(fail);
+            }
+            self.drop_to(depth);
+        }
+        self.emit(Instruction::NoMatch);
+        for exit in exits {
+            self.patch_jump(exit);
+        }
+        self.set_depth(depth);
+    }
+
+    //Match the value on top of the stack and pop it, failing jumps are added to fails
+    fn compile_pattern(&mut self, pattern: &Pattern, fails: &mut Vec<usize>) {
+        match pattern {
+            Pattern::Wildcard => {
+                self.emit(Instruction::Pop);
+            }
+            Pattern::Binding(identifier) => {
+                self.define(identifier);
+                self.emit(Instruction::Pop);
+            }
+            Pattern::Literal(literal) => {
+                self.compile_expression(literal);
+                self.emit(Instruction::MatchLiteral);
+                fails.push(self.emit(Instruction::JumpIfFalse(0)));
+            }
+            Pattern::Alternative(patterns) => {
+                let depth = self.state().depth;
+                let mut matched = vec![];
+                for alternative in patterns {
+                    self.emit(Instruction::Dup);
+                    let mut alternative_fails = vec![];
+                    self.compile_pattern(alternative, &mut alternative_fails);
+                    self.emit(Instruction::Pop);
+                    matched.push(self.emit(Instruction::Jump(0)));
+                    for fail in alternative_fails {
+                        self.patch_jump(fail);
+                    }
+                    self.drop_to(depth);
+                }
+                fails.push(self.emit(Instruction::Jump(0)));
+                for jump in matched {
+                    self.patch_jump(jump);
+                }
+                self.set_depth(depth - 1);
+            }
+            Pattern::Array(patterns) => {
+                self.emit(Instruction::IsArray(patterns.len()));
+                fails.push(self.emit(Instruction::JumpIfFalse(0)));
+                for (index, element) in patterns.iter().enumerate() {
+                    self.emit(Instruction::Element(index));
+                    self.compile_pattern(element, fails);
+                }
+                self.emit(Instruction::Pop);
+            }
+            Pattern::Hash(entries) => {
+                self.emit(Instruction::IsHash);
+                fails.push(self.emit(Instruction::JumpIfFalse(0)));
+                for (key, entry) in entries {
+                    self.compile_expression(key);
+                    self.emit(Instruction::Entry);
+                    fails.push(self.emit(Instruction::JumpIfFalse(0)));
+                    self.compile_pattern(entry, fails);
+                }
+                self.emit(Instruction::Pop);
+            }
+        }
+    }
+}
+
+fn names_of(parameters: &[Identifier]) -> Vec<String> {
+    return parameters.iter().map(|param| param.value.clone()).collect();
+}
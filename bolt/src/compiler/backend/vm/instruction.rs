@@ -0,0 +1,187 @@
+/*
+Operands are indexes into the constant pool or the nested functions of the
+function being run, slots are relative to the start of its frame. Jump
+targets are absolute positions in the code of the same function
+*/
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Instruction {
+    Constant(usize),
+    Null,
+    True,
+    False,
+    Pop,
+    Dup,
+    PopTo(usize), //Drop temporaries until the given number is left
+    GetLocal(usize),
+    SetLocal(usize),    //Assign a local, which fails before its declaration ran
+    DefineLocal(usize), //Initialize a local for its declaration
+    ClearLocal(usize),  //Forget a local of a scope being entered
+    GetCell(usize),
+    SetCell(usize),
+    DefineCell(usize),
+    NewCell(usize), //Give a captured local of a scope being entered a fresh cell
+    MoveToCell(usize, usize), //Move a captured parameter from its slot into its cell
+    GetUpvalue(usize),
+    SetUpvalue(usize),
+    DefineGlobal(usize),
+    GetGlobal(usize),
+    SetGlobal(usize),
+    Export(usize),
+    Binary(BinaryOperator),
+    Prefix(PrefixOperator),
+    Jump(usize),
+    JumpIfFalse(usize),
+    And(usize), //Keeps a falsy left operand and jumps, pops it otherwise
+    Or(usize),  //Keeps a truthy left operand and jumps, pops it otherwise
+    Array(usize),
+    Hash(usize),
+    Interpolate(usize),
+    Index,
+    SetIndex,
+    GetProperty(usize),
+    SetProperty(usize),
+    GetSuper(usize),
+    Call(usize),
+    Closure(usize),
+    Class {
+        name: usize,
+        methods: usize,
+        inherits: bool,
+    },
+    Import {
+        path: usize,
+        alias: bool,
+    },
+    Return,
+    WrapReturn, //Value of a return outside of any function
+    Yield,
+    Await,
+    Throw,
+    PushHandler(usize),
+    PopHandler,
+    Rethrow,
+    Fail(usize), //Raise an error with the message in the constant
+    IsArray(usize),
+    IsHash,
+    Element(usize),
+    Entry,
+    MatchLiteral,
+    NoMatch,
+}
+
+impl Instruction {
+    //Change in the number of values on the stack when execution falls through to the next instruction
+    pub fn stack_effect(&self) -> isize {
+        match self {
+            Instruction::Constant(_)
+            | Instruction::Null
+            | Instruction::True
+            | Instruction::False
+            | Instruction::Dup
+            | Instruction::GetLocal(_)
+            | Instruction::GetCell(_)
+            | Instruction::GetUpvalue(_)
+            | Instruction::GetGlobal(_)
+            | Instruction::Closure(_)
+            | Instruction::IsArray(_)
+            | Instruction::IsHash
+            | Instruction::Element(_)
+            | Instruction::Entry => return 1,
+            Instruction::Pop
+            | Instruction::JumpIfFalse(_)
+            | Instruction::And(_)
+            | Instruction::Or(_)
+            | Instruction::Binary(_)
+            | Instruction::Index
+            | Instruction::GetSuper(_)
+            | Instruction::Throw
+            | Instruction::Rethrow
+            | Instruction::MatchLiteral
+            | Instruction::NoMatch
+            | Instruction::Return => return -1,
+            Instruction::SetIndex => return -2,
+            Instruction::SetProperty(_) => return -1,
+            Instruction::Array(count) | Instruction::Interpolate(count) => {
+                return 1 - *count as isize;
+            }
+            Instruction::Hash(count) => return 1 - 2 * *count as isize,
+            Instruction::Call(count) => return -(*count as isize),
+            Instruction::Class {
+                methods, inherits, ..
+            } => return 1 - 2 * *methods as isize - *inherits as isize,
+            Instruction::Import { .. } => return 1,
+            //PopTo depends on the count before it, the compiler sets the count itself
+            _ => return 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BinaryOperator {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    Less,
+    Greater,
+    Equal,
+    NotEqual,
+    GreaterEqual,
+    LessEqual,
+}
+
+impl BinaryOperator {
+    pub fn from_operator(operator: &str) -> Option<Self> {
+        match operator {
+            "+" => return Some(BinaryOperator::Add),
+            "-" => return Some(BinaryOperator::Subtract),
+            "*" => return Some(BinaryOperator::Multiply),
+            "/" => return Some(BinaryOperator::Divide),
+            "<" => return Some(BinaryOperator::Less),
+            ">" => return Some(BinaryOperator::Greater),
+            "==" => return Some(BinaryOperator::Equal),
+            "!=" => return Some(BinaryOperator::NotEqual),
+            ">=" => return Some(BinaryOperator::GreaterEqual),
+            "<=" => return Some(BinaryOperator::LessEqual),
+            _ => return None,
+        }
+    }
+
+    pub fn operator(&self) -> &'static str {
+        match self {
+            BinaryOperator::Add => return "+",
+            BinaryOperator::Subtract => return "-",
+            BinaryOperator::Multiply => return "*",
+            BinaryOperator::Divide => return "/",
+            BinaryOperator::Less => return "<",
+            BinaryOperator::Greater => return ">",
+            BinaryOperator::Equal => return "==",
+            BinaryOperator::NotEqual => return "!=",
+            BinaryOperator::GreaterEqual => return ">=",
+            BinaryOperator::LessEqual => return "<=",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PrefixOperator {
+    Not,
+    Negate,
+}
+
+impl PrefixOperator {
+    pub fn from_operator(operator: &str) -> Option<Self> {
+        match operator {
+            "!" => return Some(PrefixOperator::Not),
+            "-" => return Some(PrefixOperator::Negate),
+            _ => return None,
+        }
+    }
+
+    pub fn operator(&self) -> &'static str {
+        match self {
+            PrefixOperator::Not => return "!",
+            PrefixOperator::Negate => return "-",
+        }
+    }
+}
@@ -0,0 +1,808 @@
+use std::{
+    cell::{Cell, RefCell},
+    collections::HashMap,
+    rc::Rc,
+};
+
+use crate::{
+    error::{BoltError, EvaluatorError},
+    evaluator::{
+        builtins::get_builtin,
+        constants::{FALSE, MAX_CALL_DEPTH, NULL, TRUE, UNINITIALIZED},
+        environment::Environment,
+        event_loop::EventLoop,
+        generator::Coroutine,
+        utils::{
+            bind_exports, bind_super_method, call_depth_error, duplicate_variable_error,
+            error_object, evaluate_binary_expression, evaluate_index_expression,
+            evaluate_prefix_expression, get_hash_key, get_property, import_module, is_truthy,
+            literal_matches, set_index, set_property, superclass_of, throw_value,
+        },
+    },
+    gc::heap::{alloc_array, alloc_cell, alloc_hash, alloc_instance},
+    object::object::{Class, Closure, Generator, Hash, HashPair, Value},
+};
+
+use super::{chunk::CompiledFunction, instruction::Instruction, run_module};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum FiberKind {
+    Main,      //Runs to the end, an await runs the event loop until the promise settles
+    Generator, //Suspends at every yield
+    Async,     //Suspends at every await, the event loop continues it
+}
+
+#[derive(Debug)]
+struct Frame {
+    closure: Rc<Closure>,
+    ip: usize,
+    base: usize, //Position of the first slot on the stack, the callee is right below it
+    cells: Vec<Rc<RefCell<Value>>>,
+    construct: bool, //Running init for a class call, which gives the instance
+}
+
+//Installed by a try statement, an error unwinds the fiber to it
+#[derive(Debug)]
+struct Handler {
+    frame: usize,
+    height: usize,
+    target: usize,
+}
+
+enum Exit {
+    Suspend(Value),
+    Finish(Value),
+}
+
+/*
+A stack of frames with its own value stack. Programs run on one fiber, every
+generator and async call gets one of its own, so suspending only has to
+return from the run loop and resuming continues it where it stopped
+*/
+#[derive(Debug)]
+pub struct Fiber {
+    stack: Vec<Value>,
+    frames: Vec<Frame>,
+    handlers: Vec<Handler>,
+    kind: FiberKind,
+    started: bool,
+    done: bool,
+    outer: usize, //Call depth of the fibers that resumed this one, while it runs
+}
+
+thread_local! {
+    //Call depth of the running fiber, a generator resumed inside another fiber continues from it
+    static CALL_DEPTH: Cell<usize> = const { Cell::new(0) };
+}
+
+//Run the compiled body of a program or module with env as its global environment
+pub fn execute(
+    function: Rc<CompiledFunction>,
+    env: Rc<RefCell<Environment>>,
+) -> Result<Value, EvaluatorError> {
+    let closure = Rc::new(Closure {
+        function: function,
+        upvalues: vec![],
+        globals: env,
+    });
+    let mut fiber = Fiber::new(FiberKind::Main, closure, None, vec![]);
+    match fiber.execute(None)? {
+        Exit::Finish(value) | Exit::Suspend(value) => return Ok(value),
+    }
+}
+
+//Body of an async closure that has not started yet, used to spawn it as a task
+pub fn async_task(
+    closure: Rc<Closure>,
+    this: Option<Value>,
+    args: Vec<Value>,
+) -> Box<dyn Coroutine> {
+    return Box::new(Fiber::new(FiberKind::Async, closure, this, args));
+}
+
+fn event_loop(env: &Rc<RefCell<Environment>>) -> Result<Rc<EventLoop>, EvaluatorError> {
+    match env.borrow().get_event_loop() {
+        Some(event_loop) => return Ok(event_loop),
+        None => {
+            return Err(EvaluatorError::new(
+                String::from("Async functions need an event loop to run"),
+                None,
+                None,
+            ));
+        }
+    }
+}
+
+fn arity_error() -> EvaluatorError {
+    return EvaluatorError::new("No of args in function mismatch".to_string(), None, None);
+}
+
+//Value of a local, a local whose declaration has not run yet is looked up as a builtin
+fn read_local(value: Value, name: &str) -> Result<Value, EvaluatorError> {
+    if let Value::Uninitialized = value {
+        return get_builtin(name).ok_or_else(|| {
+            EvaluatorError::new(
+                format!("Error getting environment variable {}", name),
+                None,
+                None,
+            )
+        });
+    }
+    return Ok(value);
+}
+
+//Assign a local, which fails when its declaration has not run yet
+fn write_local(current: &mut Value, value: Value, name: &str) -> Result<(), EvaluatorError> {
+    if let Value::Uninitialized = current {
+        return Err(EvaluatorError::new(
+            format!("Cannot assign to undeclared variable {}", name),
+            None,
+            None,
+        ));
+    }
+    *current = value;
+    return Ok(());
+}
+
+impl Fiber {
+    fn new(kind: FiberKind, closure: Rc<Closure>, this: Option<Value>, args: Vec<Value>) -> Self {
+        let mut fiber = Self {
+            stack: vec![Value::Closure(closure.clone())],
+            frames: vec![],
+            handlers: vec![],
+            kind: kind,
+            started: false,
+            done: false,
+            outer: 0,
+        };
+        fiber.stack.extend(args);
+        fiber.push_frame(closure, 1, this, false);
+        return fiber;
+    }
+
+    fn push_frame(
+        &mut self,
+        closure: Rc<Closure>,
+        base: usize,
+        this: Option<Value>,
+        construct: bool,
+    ) {
+        let function = &closure.function;
+        self.stack
+            .resize(base + function.slots.len(), UNINITIALIZED);
+        if let (Some(slot), Some(this)) = (function.this_slot, this) {
+            self.stack[base + slot] = this;
+        }
+        let cells = (0..function.cells.len())
+            .map(|_| alloc_cell(UNINITIALIZED))
+            .collect();
+        self.frames.push(Frame {
+            closure: closure,
+            ip: 0,
+            base: base,
+            cells: cells,
+            construct: construct,
+        });
+    }
+
+    fn frame(&self) -> &Frame {
+        return self.frames.last().unwrap();
+    }
+
+    fn pop(&mut self) -> Value {
+        return self.stack.pop().unwrap_or(NULL);
+    }
+
+    fn peek(&self) -> &Value {
+        return self.stack.last().unwrap();
+    }
+
+    fn name(&self, index: usize) -> Rc<String> {
+        match &self.frame().closure.function.constants[index] {
+            Value::Str(name) => return name.clone(),
+            constant => return Rc::new(constant.inspect()),
+        }
+    }
+
+    fn line(&self) -> usize {
+        let frame = self.frame();
+        return frame.closure.function.lines[frame.ip.saturating_sub(1)];
+    }
+
+    /*
+    Calls in progress on this fiber and the fibers below it. The first frame of
+    a program only runs its body, the first frame of a generator or task counts
+    like resuming it does on the tree walking evaluator
+    */
+    fn depth(&self) -> usize {
+        match self.kind {
+            FiberKind::Main => return self.outer + self.frames.len() - 1,
+            FiberKind::Generator | FiberKind::Async => return self.outer + self.frames.len(),
+        }
+    }
+
+    fn track_depth(&self) {
+        CALL_DEPTH.with(|depth| depth.set(self.depth()));
+    }
+
+    //Run until the fiber suspends or finishes, an error given is raised where it is paused
+    fn execute(&mut self, error: Option<EvaluatorError>) -> Result<Exit, EvaluatorError> {
+        self.outer = CALL_DEPTH.with(|depth| depth.get());
+        //Resuming a generator or task is a call too, it ends the fiber like on the tree walking evaluator
+        if self.depth() > MAX_CALL_DEPTH {
+            self.finish();
+            return Err(call_depth_error());
+        }
+        self.track_depth();
+        let exit = self.execute_frames(error);
+        CALL_DEPTH.with(|depth| depth.set(self.outer));
+        return exit;
+    }
+
+    fn execute_frames(
+        &mut self,
+        mut error: Option<EvaluatorError>,
+    ) -> Result<Exit, EvaluatorError> {
+        loop {
+            if let Some(error) = error.take() {
+                self.unwind(error)?;
+            }
+            match self.run() {
+                Ok(exit) => return Ok(exit),
+                //Errors are located at the statement the failing instruction belongs to
+                Err(e) if e.get_line().is_none() => {
+                    error = Some(EvaluatorError::new(
+                        e.get_message(),
+                        Some(e.get_type()),
+                        Some(self.line()),
+                    ));
+                }
+                Err(e) => error = Some(e),
+            }
+        }
+    }
+
+    //Continue at the innermost handler with the error object, without one the fiber ends with the error
+    fn unwind(&mut self, error: EvaluatorError) -> Result<(), EvaluatorError> {
+        match self.handlers.pop() {
+            Some(handler) => {
+                self.frames.truncate(handler.frame + 1);
+                self.track_depth();
+                self.stack.truncate(handler.height);
+                self.frames.last_mut().unwrap().ip = handler.target;
+                self.stack.push(Value::Error(Rc::new(error_object(&error))));
+                return Ok(());
+            }
+            None => {
+                self.finish();
+                return Err(error);
+            }
+        }
+    }
+
+    fn finish(&mut self) {
+        self.done = true;
+        self.frames.clear();
+        self.stack.clear();
+    }
+
+    fn run(&mut self) -> Result<Exit, EvaluatorError> {
+        loop {
+            let frame = self.frames.last_mut().unwrap();
+            let instruction = frame.closure.function.code[frame.ip];
+            frame.ip += 1;
+            match instruction {
+                Instruction::Constant(index) => {
+                    let value = self.frame().closure.function.constants[index].clone();
+                    self.stack.push(value);
+                }
+                Instruction::Null => self.stack.push(NULL),
+                Instruction::True => self.stack.push(TRUE),
+                Instruction::False => self.stack.push(FALSE),
+                Instruction::Pop => {
+                    self.stack.pop();
+                }
+                Instruction::Dup => self.stack.push(self.peek().clone()),
+                Instruction::PopTo(depth) => {
+                    let frame = self.frame();
+                    let height = frame.base + frame.closure.function.slots.len() + depth;
+                    self.stack.truncate(height);
+                }
+                Instruction::GetLocal(slot) => {
+                    let frame = self.frame();
+                    let value = self.stack[frame.base + slot].clone();
+                    let value = read_local(value, &frame.closure.function.slots[slot])?;
+                    self.stack.push(value);
+                }
+                Instruction::SetLocal(slot) => {
+                    let value = self.peek().clone();
+                    let frame = self.frames.last().unwrap();
+                    let name = &frame.closure.function.slots[slot];
+                    write_local(&mut self.stack[frame.base + slot], value, name)?;
+                }
+                Instruction::DefineLocal(slot) => {
+                    let position = self.frame().base + slot;
+                    self.stack[position] = self.peek().clone();
+                }
+                Instruction::ClearLocal(slot) => {
+                    let position = self.frame().base + slot;
+                    self.stack[position] = UNINITIALIZED;
+                }
+                Instruction::GetCell(cell) => {
+                    let frame = self.frame();
+                    let value = frame.cells[cell].borrow().clone();
+                    let value = read_local(value, &frame.closure.function.cells[cell])?;
+                    self.stack.push(value);
+                }
+                Instruction::SetCell(cell) => {
+                    let frame = self.frame();
+                    let name = &frame.closure.function.cells[cell];
+                    write_local(
+                        &mut frame.cells[cell].borrow_mut(),
+                        self.peek().clone(),
+                        name,
+                    )?;
+                }
+                Instruction::DefineCell(cell) => {
+                    *self.frame().cells[cell].borrow_mut() = self.peek().clone();
+                }
+                Instruction::NewCell(cell) => {
+                    self.frames.last_mut().unwrap().cells[cell] = alloc_cell(UNINITIALIZED);
+                }
+                Instruction::MoveToCell(slot, cell) => {
+                    let position = self.frame().base + slot;
+                    let value = std::mem::replace(&mut self.stack[position], NULL);
+                    *self.frame().cells[cell].borrow_mut() = value;
+                }
+                Instruction::GetUpvalue(upvalue) => {
+                    let closure = &self.frame().closure;
+                    let value = closure.upvalues[upvalue].borrow().clone();
+                    let value = read_local(value, &closure.function.captures[upvalue].0)?;
+                    self.stack.push(value);
+                }
+                Instruction::SetUpvalue(upvalue) => {
+                    let closure = &self.frame().closure;
+                    let name = &closure.function.captures[upvalue].0;
+                    let mut current = closure.upvalues[upvalue].borrow_mut();
+                    write_local(&mut current, self.peek().clone(), name)?;
+                }
+                Instruction::DefineGlobal(name) => {
+                    let name = self.name(name);
+                    let value = self.peek().clone();
+                    let globals = self.frame().closure.globals.clone();
+                    if globals.borrow().get_import(&name).is_some() {
+                        return Err(duplicate_variable_error(&name));
+                    }
+                    globals.borrow_mut().set(name.as_ref().clone(), value);
+                }
+                Instruction::GetGlobal(name) => {
+                    let name = self.name(name);
+                    let value = self.frame().closure.globals.borrow().get(&name);
+                    match value.or_else(|| get_builtin(&name)) {
+                        Some(value) => self.stack.push(value),
+                        None => {
+                            return Err(EvaluatorError::new(
+                                format!("Error getting environment variable {}", name),
+                                None,
+                                None,
+                            ));
+                        }
+                    }
+                }
+                Instruction::SetGlobal(name) => {
+                    let name = self.name(name);
+                    let value = self.peek().clone();
+                    let globals = self.frame().closure.globals.clone();
+                    let assigned = globals.borrow_mut().assign(name.as_ref().clone(), value);
+                    if assigned.is_none() {
+                        return Err(EvaluatorError::new(
+                            format!("Cannot assign to undeclared variable {}", name),
+                            None,
+                            None,
+                        ));
+                    }
+                }
+                Instruction::Export(name) => {
+                    let name = self.name(name);
+                    let globals = self.frame().closure.globals.clone();
+                    globals.borrow_mut().export(name.as_ref().clone());
+                }
+                Instruction::Binary(operator) => {
+                    let right = self.pop();
+                    let left = self.pop();
+                    let value = match (&left, &right) {
+                        //Integer arithmetic that can not overflow skips the shared checks
+                        (Value::Integer(left), Value::Integer(right)) => {
+                            integer_binary(operator.operator(), *left, *right)
+                        }
+                        _ => None,
+                    };
+                    let value = match value {
+                        Some(value) => value,
+                        None => evaluate_binary_expression(operator.operator(), left, right)?,
+                    };
+                    self.stack.push(value);
+                }
+                Instruction::Prefix(operator) => {
+                    let right = self.pop();
+                    let value = evaluate_prefix_expression(operator.operator(), right)?;
+                    self.stack.push(value);
+                }
+                Instruction::Jump(target) => self.frames.last_mut().unwrap().ip = target,
+                Instruction::JumpIfFalse(target) => {
+                    let condition = self.pop();
+                    if !is_truthy(&condition) {
+                        self.frames.last_mut().unwrap().ip = target;
+                    }
+                }
+                Instruction::And(target) => {
+                    if !is_truthy(self.peek()) {
+                        self.frames.last_mut().unwrap().ip = target;
+                    } else {
+                        self.stack.pop();
+                    }
+                }
+                Instruction::Or(target) => {
+                    if is_truthy(self.peek()) {
+                        self.frames.last_mut().unwrap().ip = target;
+                    } else {
+                        self.stack.pop();
+                    }
+                }
+                Instruction::Array(count) => {
+                    let elements = self.stack.split_off(self.stack.len() - count);
+                    self.stack.push(alloc_array(elements));
+                }
+                Instruction::Hash(count) => {
+                    let values = self.stack.split_off(self.stack.len() - 2 * count);
+                    let hash = Hash::new();
+                    let mut values = values.into_iter();
+                    while let (Some(key), Some(value)) = (values.next(), values.next()) {
+                        hash.set(get_hash_key(&key)?, HashPair { key, value });
+                    }
+                    self.stack.push(alloc_hash(hash));
+                }
+                Instruction::Interpolate(count) => {
+                    let parts = self.stack.split_off(self.stack.len() - count);
+                    let mut value = String::new();
+                    for part in parts {
+                        value.push_str(&part.inspect());
+                    }
+                    self.stack.push(Value::string(value));
+                }
+                Instruction::Index => {
+                    let index = self.pop();
+                    let left = self.pop();
+                    self.stack.push(evaluate_index_expression(left, index)?);
+                }
+                Instruction::SetIndex => {
+                    let value = self.pop();
+                    let index = self.pop();
+                    let left = self.pop();
+                    self.stack.push(set_index(left, index, value)?);
+                }
+                Instruction::GetProperty(name) => {
+                    let name = self.name(name);
+                    let object = self.pop();
+                    self.stack.push(get_property(object, &name)?);
+                }
+                Instruction::SetProperty(name) => {
+                    let name = self.name(name);
+                    let value = self.pop();
+                    let object = self.pop();
+                    self.stack.push(set_property(object, &name, value)?);
+                }
+                Instruction::GetSuper(name) => {
+                    let name = self.name(name);
+                    let receiver = self.pop();
+                    let superclass = self.pop();
+                    self.stack
+                        .push(bind_super_method(&superclass, receiver, &name)?);
+                }
+                Instruction::Call(count) => self.call(count)?,
+                Instruction::Closure(index) => {
+                    let frame = self.frame();
+                    let function = frame.closure.function.functions[index].clone();
+                    let upvalues = function
+                        .captures
+                        .iter()
+                        .map(|(_, capture)| match capture {
+                            super::chunk::Capture::Cell(cell) => frame.cells[*cell].clone(),
+                            super::chunk::Capture::Upvalue(upvalue) => {
+                                frame.closure.upvalues[*upvalue].clone()
+                            }
+                        })
+                        .collect();
+                    let closure = Closure {
+                        function: function,
+                        upvalues: upvalues,
+                        globals: frame.closure.globals.clone(),
+                    };
+                    self.stack.push(Value::Closure(Rc::new(closure)));
+                }
+                Instruction::Class {
+                    name,
+                    methods,
+                    inherits,
+                } => {
+                    let name = self.name(name);
+                    let values = self.stack.split_off(self.stack.len() - 2 * methods);
+                    let superclass = match inherits {
+                        true => Some(superclass_of(&self.pop())?),
+                        false => None,
+                    };
+                    let mut table = HashMap::new();
+                    let mut values = values.into_iter();
+                    while let (Some(method_name), Some(method)) = (values.next(), values.next()) {
+                        table.insert(method_name.inspect(), method);
+                    }
+                    self.stack.push(Value::Class(Rc::new(Class {
+                        name: name.as_ref().clone(),
+                        superclass: superclass,
+                        methods: table,
+                    })));
+                }
+                Instruction::Import { path, alias } => {
+                    let path = self.name(path);
+                    let globals = self.frame().closure.globals.clone();
+                    let module = import_module(&path, &globals, run_module)?;
+                    if !alias {
+                        bind_exports(&module, &globals)?;
+                    }
+                    self.stack.push(module);
+                }
+                Instruction::Return => {
+                    let value = self.pop();
+                    let frame = self.frames.pop().unwrap();
+                    if !self.frames.is_empty() {
+                        self.track_depth();
+                    }
+                    //A class call gives the instance whatever init returns
+                    let value = match (frame.construct, frame.closure.function.this_slot) {
+                        (true, Some(slot)) => self.stack[frame.base + slot].clone(),
+                        _ => value,
+                    };
+                    self.stack.truncate(frame.base - 1);
+                    if self.frames.is_empty() {
+                        return Ok(Exit::Finish(value));
+                    }
+                    self.stack.push(value);
+                }
+                Instruction::WrapReturn => {
+                    let value = self.pop();
+                    self.stack.push(Value::Return(Box::new(value)));
+                }
+                Instruction::Yield => return Ok(Exit::Suspend(self.pop())),
+                Instruction::Await => {
+                    let value = self.pop();
+                    if self.kind == FiberKind::Async {
+                        return Ok(Exit::Suspend(value));
+                    }
+                    //Outside of async functions await runs the event loop until the promise settles
+                    let value = match &value {
+                        Value::Promise(promise) => {
+                            let globals = self.frame().closure.globals.clone();
+                            let event_loop = globals.borrow().get_event_loop();
+                            match event_loop {
+                                Some(event_loop) => event_loop.run_until(promise)?,
+                                None => {
+                                    return Err(EvaluatorError::new(
+                                        String::from("await needs an event loop to run"),
+                                        None,
+                                        None,
+                                    ));
+                                }
+                            }
+                        }
+                        _ => value,
+                    };
+                    self.stack.push(value);
+                }
+                Instruction::Throw | Instruction::Rethrow => {
+                    let value = self.pop();
+                    return Err(throw_value(value, self.line()));
+                }
+                Instruction::PushHandler(target) => {
+                    self.handlers.push(Handler {
+                        frame: self.frames.len() - 1,
+                        height: self.stack.len(),
+                        target: target,
+                    });
+                }
+                Instruction::PopHandler => {
+                    self.handlers.pop();
+                }
+                Instruction::Fail(message) => {
+                    let message = self.name(message);
+                    return Err(EvaluatorError::new(message.as_ref().clone(), None, None));
+                }
+                Instruction::IsArray(length) => {
+                    let matched = match self.peek() {
+                        Value::Array(array) => array.elements.borrow().len() == length,
+                        _ => false,
+                    };
+                    self.stack.push(Value::Boolean(matched));
+                }
+                Instruction::IsHash => {
+                    let matched = matches!(self.peek(), Value::Hash(_));
+                    self.stack.push(Value::Boolean(matched));
+                }
+                Instruction::Element(index) => {
+                    let element = match self.peek() {
+                        Value::Array(array) => array.elements.borrow()[index].clone(),
+                        _ => NULL,
+                    };
+                    self.stack.push(element);
+                }
+                Instruction::Entry => {
+                    let key = self.pop();
+                    let entry = match self.peek() {
+                        Value::Hash(hash) => hash.get(&get_hash_key(&key)?),
+                        _ => None,
+                    };
+                    let found = entry.is_some();
+                    self.stack.push(entry.unwrap_or(NULL));
+                    self.stack.push(Value::Boolean(found));
+                }
+                Instruction::MatchLiteral => {
+                    let expected = self.pop();
+                    let value = self.pop();
+                    self.stack
+                        .push(Value::Boolean(literal_matches(&expected, &value)));
+                }
+                Instruction::NoMatch => {
+                    let value = self.pop();
+                    return Err(EvaluatorError::new(
+                        format!("No match arm for value {}", value.inspect()),
+                        None,
+                        None,
+                    ));
+                }
+            }
+        }
+    }
+
+    //The callee and its arguments are on top of the stack and are replaced by the result
+    fn call(&mut self, count: usize) -> Result<(), EvaluatorError> {
+        let position = self.stack.len() - count - 1;
+        let callee = self.stack[position].clone();
+        match &callee {
+            Value::Closure(closure) => {
+                return self.call_closure(closure.clone(), None, count, false)
+            }
+            Value::BoundMethod(bound) => {
+                if let Value::Closure(method) = &bound.method {
+                    let receiver = Some(bound.receiver.clone());
+                    return self.call_closure(method.clone(), receiver, count, false);
+                }
+            }
+            Value::Class(class) => {
+                let instance = alloc_instance(class.clone());
+                match class.find_method("init") {
+                    Some(Value::Closure(init)) => {
+                        return self.call_closure(init, Some(instance), count, true);
+                    }
+                    None if count == 0 => {
+                        self.stack.truncate(position);
+                        self.stack.push(instance);
+                        return Ok(());
+                    }
+                    None => return Err(arity_error()),
+                    Some(_) => {}
+                }
+            }
+            Value::Builtin(builtin) => {
+                let args = self.stack.split_off(position + 1);
+                self.stack.pop();
+                let globals = self.frame().closure.globals.clone();
+                let value = (builtin.function)(args, globals)?;
+                self.stack.push(value);
+                return Ok(());
+            }
+            Value::BuiltinMethod(method) => {
+                let args = self.stack.split_off(position + 1);
+                self.stack.pop();
+                let value = (method.function)(method.receiver.clone(), args)?;
+                self.stack.push(value);
+                return Ok(());
+            }
+            _ => {}
+        }
+        return Err(EvaluatorError::new(
+            "Error Downcasting function".to_string(),
+            None,
+            None,
+        ));
+    }
+
+    /*
+    A plain closure gets a new frame on this fiber. Generators and async
+    functions get a fiber of their own, the caller receives the generator or
+    the promise of the task
+    */
+    fn call_closure(
+        &mut self,
+        closure: Rc<Closure>,
+        this: Option<Value>,
+        count: usize,
+        construct: bool,
+    ) -> Result<(), EvaluatorError> {
+        let function = &closure.function;
+        if function.parameters.len() != count {
+            return Err(arity_error());
+        }
+        let base = self.stack.len() - count;
+        if !function.is_generator && !function.is_async {
+            if self.depth() >= MAX_CALL_DEPTH {
+                return Err(call_depth_error());
+            }
+            self.push_frame(closure, base, this, construct);
+            self.track_depth();
+            return Ok(());
+        }
+        let args = self.stack.split_off(base);
+        self.stack.pop();
+        let instance = match construct {
+            true => this.clone(),
+            false => None,
+        };
+        let value = match function.is_generator {
+            true => {
+                let fiber = Fiber::new(FiberKind::Generator, closure, this, args);
+                Value::Generator(Rc::new(Generator {
+                    execution: RefCell::new(Box::new(fiber)),
+                }))
+            }
+            false => {
+                let event_loop = event_loop(&closure.globals)?;
+                event_loop.spawn(async_task(closure, this, args))
+            }
+        };
+        self.stack.push(instance.unwrap_or(value));
+        return Ok(());
+    }
+}
+
+impl Coroutine for Fiber {
+    fn resume(
+        &mut self,
+        sent: Result<Value, EvaluatorError>,
+    ) -> Result<(Value, bool), EvaluatorError> {
+        if self.done {
+            return Ok((NULL, true));
+        }
+        //The value sent to start the body has nowhere to go
+        let error = match (self.started, sent) {
+            (_, Err(e)) => Some(e),
+            (true, Ok(value)) => {
+                self.stack.push(value);
+                None
+            }
+            (false, Ok(_)) => None,
+        };
+        self.started = true;
+        match self.execute(error)? {
+            Exit::Suspend(value) => return Ok((value, false)),
+            Exit::Finish(value) => {
+                self.finish();
+                return Ok((value, true));
+            }
+        }
+    }
+}
+
+//Arithmetic and comparison of two integers, None when it needs the checks of the evaluator
+fn integer_binary(operator: &str, left: i64, right: i64) -> Option<Value> {
+    match operator {
+        "+" => return left.checked_add(right).map(Value::Integer),
+        "-" => return left.checked_sub(right).map(Value::Integer),
+        "*" => return left.checked_mul(right).map(Value::Integer),
+        "<" => return Some(Value::Boolean(left < right)),
+        ">" => return Some(Value::Boolean(left > right)),
+        "==" => return Some(Value::Boolean(left == right)),
+        "!=" => return Some(Value::Boolean(left != right)),
+        ">=" => return Some(Value::Boolean(left >= right)),
+        "<=" => return Some(Value::Boolean(left <= right)),
+        _ => return None,
+    }
+}
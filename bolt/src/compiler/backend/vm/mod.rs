@@ -0,0 +1,115 @@
+mod chunk;
+mod compiler;
+mod instruction;
+mod machine;
+
+use std::{cell::RefCell, fs, rc::Rc};
+
+use crate::{
+    compiler::compiler::Compiler,
+    error::EvaluatorError,
+    evaluator::{environment::Environment, evaluator::prepare_environment, generator::Coroutine},
+    object::object::{Closure, Value},
+    parser::ast::Program,
+    resolver::resolver::resolve_program,
+};
+
+pub use chunk::CompiledFunction;
+
+//Compile the resolved program and run it with env as its global environment
+pub fn run(
+    program: &Program,
+    name: &str,
+    env: Rc<RefCell<Environment>>,
+) -> Result<Value, EvaluatorError> {
+    let function = compiler::compile_program(program, name);
+    return machine::execute(Rc::new(function), env);
+}
+
+//Runs the body of an imported module when the importer runs on the virtual machine
+pub(crate) fn run_module(
+    program: Program,
+    env: Rc<RefCell<Environment>>,
+) -> Result<(), EvaluatorError> {
+    run(&program, "module", env)?;
+    return Ok(());
+}
+
+//Call of an async closure that has not started yet, for spawn
+pub(crate) fn async_call(
+    closure: Rc<Closure>,
+    this: Option<Value>,
+    args: Vec<Value>,
+) -> Box<dyn Coroutine> {
+    return machine::async_task(closure, this, args);
+}
+
+/*
+Backend that compiles the program to bytecode and runs it on a stack based
+virtual machine instead of walking the tree. The files it writes hold the
+disassembled bytecode
+*/
+pub struct VM {
+    program: Rc<Program>,
+    filename: String,
+    function: Option<Rc<CompiledFunction>>,
+}
+
+impl VM {
+    pub fn new(program: Program, filename: &str) -> Self {
+        return Self {
+            program: Rc::new(program),
+            filename: String::from(filename),
+            function: None,
+        };
+    }
+
+    fn function(&mut self) -> Result<Rc<CompiledFunction>, EvaluatorError> {
+        if self.function.is_none() {
+            self.compile()?;
+        }
+        return Ok(self.function.clone().unwrap());
+    }
+}
+
+impl Compiler for VM {
+    fn compile(&mut self) -> Result<(), EvaluatorError> {
+        //Locals are compiled to the slots the resolver gives them
+        resolve_program(&self.program)?;
+        let function = compiler::compile_program(&self.program, &self.filename);
+        self.function = Some(Rc::new(function));
+        return Ok(());
+    }
+
+    fn clean(&mut self) {
+        self.function = None;
+    }
+
+    fn generate_ir(&mut self) -> String {
+        //A program that does not compile has no listing, compile reports why
+        match self.function() {
+            Ok(function) => return function.disassemble(),
+            Err(_) => return String::new(),
+        }
+    }
+
+    fn ir_to_file(&mut self, filename: String) {
+        let listing = self.generate_ir();
+        if let Err(e) = fs::write(&filename, listing) {
+            eprintln!("Error writing {}: {}", filename, e);
+        }
+    }
+
+    fn bytecode_to_file(&mut self, filename: String, _target: &String) {
+        self.ir_to_file(filename);
+    }
+
+    //Runs like the run command, with a module context and an event loop for the tasks it starts
+    fn bytecode_to_jit(&mut self, _target: &String) -> Result<(), EvaluatorError> {
+        let function = self.function()?;
+        let env = Environment::new();
+        let event_loop = prepare_environment(&env, &self.filename);
+        machine::execute(function, env)?;
+        return event_loop.run();
+    }
+}
@@ -1,27 +1,30 @@
+use crate::error::EvaluatorError;
 use crate::parser::ast::Program;
 
-use super::LLVM;
+use super::{LLVM, VM};
 
 pub trait Compiler {
-    fn compile(&mut self);
+    fn compile(&mut self) -> Result<(), EvaluatorError>;
     fn clean(&mut self);
     fn generate_ir(&mut self) -> String;
     fn ir_to_file(&mut self, filename: String);
     fn bytecode_to_file(&mut self, filename: String, target: &String);
-    fn bytecode_to_jit(&mut self, target: &String);
+    fn bytecode_to_jit(&mut self, target: &String) -> Result<(), EvaluatorError>;
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq)]
 pub enum CompilerBackend {
     LLVM,
+    VM, //Bytecode run by the virtual machine, needs no external tools
 }
 
 pub struct Factory {}
 
 impl Factory {
-    pub fn new(backend: CompilerBackend, program: Program, filename: &str) -> impl Compiler {
+    pub fn new(backend: CompilerBackend, program: Program, filename: &str) -> Box<dyn Compiler> {
         match backend {
-            CompilerBackend::LLVM => LLVM::new(program, filename),
+            CompilerBackend::LLVM => return Box::new(LLVM::new(program, filename)),
+            CompilerBackend::VM => return Box::new(VM::new(program, filename)),
         }
     }
 }
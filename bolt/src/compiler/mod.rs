@@ -2,5 +2,7 @@ mod backend;
 pub(crate) mod compiler;
 
 pub(crate) use backend::llvm::LLVM;
+pub(crate) use backend::vm;
+pub use backend::vm::{CompiledFunction, VM};
 pub use compiler::Compiler;
 pub use compiler::{CompilerBackend, Factory};
@@ -7,6 +7,7 @@ pub trait BoltError {
     fn new(message: ErrorMessage, kind: Option<BoltErrorType>, line: Option<LineNumber>) -> Self;
     fn get_type(&self) -> BoltErrorType;
     fn get_message(&self) -> ErrorMessage;
+    fn get_line(&self) -> Option<LineNumber>;
 }
 
 #[derive(Debug, Clone)]
@@ -14,8 +15,34 @@ pub enum BoltErrorType {
     GENERIC,
     INTERNAL,
     PARSE,
+    RESOLVE, //Found by the resolver before the program runs
     EOF,
     EVAL,
+    EXCEPTION(String), //Thrown from Bolt code, holds the kind of the error object
+}
+
+impl BoltErrorType {
+    //Kind of the error object a catch block receives for this error
+    pub fn name(&self) -> String {
+        match self {
+            BoltErrorType::EXCEPTION(kind) => kind.clone(),
+            BoltErrorType::EVAL => String::from("RuntimeError"),
+            BoltErrorType::PARSE | BoltErrorType::EOF => String::from("ParseError"),
+            BoltErrorType::RESOLVE => String::from("ResolveError"),
+            BoltErrorType::GENERIC | BoltErrorType::INTERNAL => String::from("InternalError"),
+        }
+    }
+
+    //Type of an error raised again from its error object
+    pub fn from_name(name: &str) -> Self {
+        match name {
+            "RuntimeError" => BoltErrorType::EVAL,
+            "ParseError" => BoltErrorType::PARSE,
+            "ResolveError" => BoltErrorType::RESOLVE,
+            "InternalError" => BoltErrorType::INTERNAL,
+            _ => BoltErrorType::EXCEPTION(String::from(name)),
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -42,6 +69,10 @@ impl BoltError for ParseError {
     fn get_type(&self) -> BoltErrorType {
         return self.kind.clone();
     }
+
+    fn get_line(&self) -> Option<LineNumber> {
+        return self.line;
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -68,4 +99,8 @@ impl BoltError for EvaluatorError {
     fn get_type(&self) -> BoltErrorType {
         return self.kind.clone();
     }
+
+    fn get_line(&self) -> Option<LineNumber> {
+        return self.line;
+    }
 }
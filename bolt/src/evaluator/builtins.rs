@@ -0,0 +1,346 @@
+use std::{cell::RefCell, rc::Rc};
+
+use crate::{
+    compiler::vm,
+    error::{BoltError, EvaluatorError},
+    gc::heap::{alloc_hash, collect, stats},
+    object::{
+        object::{Builtin, Hash, HashPair, Value},
+        types::{HashKey, ObjectType},
+    },
+};
+
+use super::{
+    constants::NULL,
+    environment::Environment,
+    event_loop::EventLoop,
+    generator::{Coroutine, Execution},
+    utils::{as_float, extend_funtion_env, is_truthy, method_function},
+};
+
+type Arguments = Vec<Value>;
+type BuiltinResult = Result<Value, EvaluatorError>;
+
+/*
+Registry of functions implemented in Rust, consulted when an identifier is not
+found in the environment so user defined names can shadow them. Values refer
+to the entries of the table so looking one up does not allocate
+*/
+static BUILTINS: [Builtin; 11] = [
+    Builtin {
+        name: "print",
+        function: builtin_print,
+    },
+    Builtin {
+        name: "len",
+        function: builtin_len,
+    },
+    Builtin {
+        name: "type",
+        function: builtin_type,
+    },
+    Builtin {
+        name: "str",
+        function: builtin_str,
+    },
+    Builtin {
+        name: "num",
+        function: builtin_num,
+    },
+    Builtin {
+        name: "int",
+        function: builtin_int,
+    },
+    Builtin {
+        name: "float",
+        function: builtin_float,
+    },
+    Builtin {
+        name: "assert",
+        function: builtin_assert,
+    },
+    Builtin {
+        name: "sleep",
+        function: builtin_sleep,
+    },
+    Builtin {
+        name: "spawn",
+        function: builtin_spawn,
+    },
+    Builtin {
+        name: "gc",
+        function: builtin_gc,
+    },
+];
+
+pub fn get_builtin(name: &str) -> Option<Value> {
+    for builtin in &BUILTINS {
+        if builtin.name == name {
+            return Some(Value::Builtin(builtin));
+        }
+    }
+    return None;
+}
+
+pub fn type_name(object_type: ObjectType) -> &'static str {
+    match object_type {
+        ObjectType::INTERGER => "integer",
+        ObjectType::FLOAT => "float",
+        ObjectType::BOOLEAN => "boolean",
+        ObjectType::NULL => "null",
+        ObjectType::STRING => "string",
+        ObjectType::FUNCTION | ObjectType::BUILTIN => "function",
+        ObjectType::ARRAY => "array",
+        ObjectType::HASH => "hash",
+        ObjectType::MODULE => "module",
+        ObjectType::CLASS => "class",
+        ObjectType::INSTANCE => "instance",
+        ObjectType::ERROR => "error",
+        ObjectType::GENERATOR => "generator",
+        ObjectType::PROMISE => "promise",
+        ObjectType::RETURN => "return",
+        ObjectType::BREAK => "break",
+        ObjectType::CONTINUE => "continue",
+        ObjectType::UNINITIALIZED => "uninitialized",
+    }
+}
+
+fn check_arguments(name: &str, args: &Arguments, expected: usize) -> Result<(), EvaluatorError> {
+    if args.len() != expected {
+        return Err(EvaluatorError::new(
+            format!(
+                "Wrong number of arguments to {}: expected {}, found {}",
+                name,
+                expected,
+                args.len()
+            ),
+            None,
+            None,
+        ));
+    }
+    return Ok(());
+}
+
+fn builtin_print(args: Arguments, _env: Rc<RefCell<Environment>>) -> BuiltinResult {
+    let mut values = vec![];
+    for arg in &args {
+        values.push(arg.inspect());
+    }
+    println!("{}", values.join(" "));
+    return Ok(NULL);
+}
+
+fn builtin_len(args: Arguments, _env: Rc<RefCell<Environment>>) -> BuiltinResult {
+    check_arguments("len", &args, 1)?;
+    let length = match &args[0] {
+        Value::Str(string) => string.chars().count(),
+        Value::Array(array) => array.elements.borrow().len(),
+        Value::Hash(hash) => hash.len(),
+        _ => {
+            return Err(EvaluatorError::new(
+                format!("Argument to len not supported, found {}", args[0].inspect()),
+                None,
+                None,
+            ));
+        }
+    };
+    return Ok(Value::Integer(length as i64));
+}
+
+fn builtin_type(args: Arguments, _env: Rc<RefCell<Environment>>) -> BuiltinResult {
+    check_arguments("type", &args, 1)?;
+    return Ok(Value::string(String::from(type_name(args[0].get_type()))));
+}
+
+fn builtin_str(args: Arguments, _env: Rc<RefCell<Environment>>) -> BuiltinResult {
+    check_arguments("str", &args, 1)?;
+    return Ok(Value::string(args[0].inspect()));
+}
+
+//Strings are read as an integer when possible and as a float otherwise
+fn builtin_num(args: Arguments, _env: Rc<RefCell<Environment>>) -> BuiltinResult {
+    check_arguments("num", &args, 1)?;
+    match &args[0] {
+        Value::Integer(_) | Value::Float(_) => return Ok(args[0].clone()),
+        Value::Boolean(boolean) => return Ok(Value::Integer(if *boolean { 1 } else { 0 })),
+        Value::Str(string) => {
+            let text = string.trim();
+            if let Ok(value) = text.parse::<i64>() {
+                return Ok(Value::Integer(value));
+            }
+            match text.parse::<f64>() {
+                Ok(value) => return Ok(Value::Float(value)),
+                Err(_) => {
+                    return Err(EvaluatorError::new(
+                        format!("Cannot convert \"{}\" to a number", string),
+                        None,
+                        None,
+                    ));
+                }
+            }
+        }
+        _ => {
+            return Err(EvaluatorError::new(
+                format!("Cannot convert {} to a number", args[0].inspect()),
+                None,
+                None,
+            ));
+        }
+    }
+}
+
+//Floats are truncated towards zero
+fn builtin_int(args: Arguments, env: Rc<RefCell<Environment>>) -> BuiltinResult {
+    check_arguments("int", &args, 1)?;
+    let number = builtin_num(args, env)?;
+    if let Value::Float(float) = number {
+        if !float.is_finite() || float.abs() >= 9.2e18 {
+            return Err(EvaluatorError::new(
+                format!("Cannot convert {} to an integer", number.inspect()),
+                None,
+                None,
+            ));
+        }
+        return Ok(Value::Integer(float as i64));
+    }
+    return Ok(number);
+}
+
+fn builtin_float(args: Arguments, env: Rc<RefCell<Environment>>) -> BuiltinResult {
+    check_arguments("float", &args, 1)?;
+    let number = builtin_num(args, env)?;
+    return Ok(Value::Float(as_float(&number).unwrap_or_default()));
+}
+
+fn builtin_assert(args: Arguments, _env: Rc<RefCell<Environment>>) -> BuiltinResult {
+    if args.is_empty() || args.len() > 2 {
+        return Err(EvaluatorError::new(
+            format!(
+                "Wrong number of arguments to assert: expected 1 or 2, found {}",
+                args.len()
+            ),
+            None,
+            None,
+        ));
+    }
+    if !is_truthy(&args[0]) {
+        let message = match args.get(1) {
+            Some(message) => format!("Assertion failed: {}", message.inspect()),
+            None => String::from("Assertion failed"),
+        };
+        return Err(EvaluatorError::new(message, None, None));
+    }
+    return Ok(NULL);
+}
+
+fn event_loop(name: &str, env: &Rc<RefCell<Environment>>) -> Result<Rc<EventLoop>, EvaluatorError> {
+    match env.borrow().get_event_loop() {
+        Some(event_loop) => return Ok(event_loop),
+        None => {
+            return Err(EvaluatorError::new(
+                format!("{} needs an event loop to run", name),
+                None,
+                None,
+            ));
+        }
+    }
+}
+
+//Promise that is fulfilled with null after the given number of milliseconds
+fn builtin_sleep(args: Arguments, env: Rc<RefCell<Environment>>) -> BuiltinResult {
+    check_arguments("sleep", &args, 1)?;
+    let milliseconds = match as_float(&args[0]) {
+        Some(value) if value >= 0.0 && value.is_finite() => value as u64,
+        _ => {
+            return Err(EvaluatorError::new(
+                format!(
+                    "Argument to sleep must be a non negative number, found {}",
+                    args[0].inspect()
+                ),
+                None,
+                None,
+            ));
+        }
+    };
+    return Ok(event_loop("sleep", &env)?.sleep(milliseconds));
+}
+
+/*
+Runs a function with the remaining arguments as a task on the event loop and
+returns a promise for its result, the same way an async function is called
+*/
+fn builtin_spawn(args: Arguments, env: Rc<RefCell<Environment>>) -> BuiltinResult {
+    let mut args = args.into_iter();
+    let function_value = match args.next() {
+        Some(function_value) => function_value,
+        None => {
+            return Err(EvaluatorError::new(
+                String::from("Wrong number of arguments to spawn: expected at least 1, found 0"),
+                None,
+                None,
+            ));
+        }
+    };
+    let args: Arguments = args.collect();
+    let (callee, this) = match &function_value {
+        Value::BoundMethod(bound) => (&bound.method, Some(bound.receiver.clone())),
+        _ => (&function_value, None),
+    };
+    let (is_generator, parameter_count) = match callee {
+        Value::Function(function) => (function.is_generator, function.parameters.len()),
+        Value::Closure(closure) => (
+            closure.function.is_generator,
+            closure.function.parameters.len(),
+        ),
+        _ => {
+            return Err(EvaluatorError::new(
+                format!(
+                    "Argument to spawn must be a function, found {}",
+                    function_value.inspect()
+                ),
+                None,
+                None,
+            ));
+        }
+    };
+    if is_generator {
+        return Err(EvaluatorError::new(
+            String::from("A generator can not be spawned"),
+            None,
+            None,
+        ));
+    }
+    check_arguments("the spawned function", &args, parameter_count)?;
+    let execution: Box<dyn Coroutine> = match callee {
+        Value::Closure(closure) => vm::async_call(closure.clone(), this, args),
+        _ => {
+            let function = method_function(callee)?;
+            let extended_env = extend_funtion_env(function, args, this);
+            Box::new(Execution::new_async(function.body.clone(), extended_env))
+        }
+    };
+    return Ok(event_loop("spawn", &env)?.spawn(execution));
+}
+
+//Runs the cycle collector and returns the allocation statistics of the heap
+fn builtin_gc(args: Arguments, _env: Rc<RefCell<Environment>>) -> BuiltinResult {
+    check_arguments("gc", &args, 0)?;
+    collect();
+    let stats = stats();
+    let result = Hash::new();
+    for (key, count) in [
+        ("allocated", stats.allocated),
+        ("live", stats.live),
+        ("collected", stats.collected),
+        ("collections", stats.collections),
+    ] {
+        result.set(
+            HashKey::Str(String::from(key)),
+            HashPair {
+                key: Value::string(String::from(key)),
+                value: Value::Integer(count as i64),
+            },
+        );
+    }
+    return Ok(alloc_hash(result));
+}
@@ -1,5 +1,12 @@
-use crate::object::object::{BooleanObj, Null};
+use crate::object::object::Value;
 
-pub const TRUE: BooleanObj = BooleanObj { value: true };
-pub const FALSE: BooleanObj = BooleanObj { value: false };
-pub const NULL: Null = Null {};
+//Values stored inline in the enum, using them never allocates
+pub const TRUE: Value = Value::Boolean(true);
+pub const FALSE: Value = Value::Boolean(false);
+pub const NULL: Value = Value::Null;
+pub const BREAK: Value = Value::Break;
+pub const CONTINUE: Value = Value::Continue;
+pub const UNINITIALIZED: Value = Value::Uninitialized;
+
+//Function calls that can be running at once, deeper recursion raises a RuntimeError
+pub const MAX_CALL_DEPTH: usize = 1000;
@@ -1,25 +1,52 @@
 use std::{cell::RefCell, collections::HashMap, rc::Rc};
 
-use crate::object::object::Object;
+use crate::{
+    gc::heap::track_environment,
+    object::object::{Module, Value},
+    parser::ast::{Binding, Identifier},
+};
+
+use super::{event_loop::EventLoop, module::ModuleContext};
 
 #[derive(Debug)]
 pub struct Environment {
-    store: HashMap<String, Rc<Box<dyn Object>>>,
+    store: HashMap<String, Value>,
+    imports: HashMap<String, Rc<Module>>, //Names imported without an alias, read from their module
+    slots: Vec<Option<Value>>, //Locals by the slot the resolver gave them, None until declared
     outer: Option<Rc<RefCell<Environment>>>,
+    exports: Vec<String>,
+    module: Option<Rc<ModuleContext>>, //Set on the top level environment of a module
+    event_loop: Option<Rc<EventLoop>>, //Set on the top level environment of a module
 }
 
 impl Environment {
     pub fn new() -> Rc<RefCell<Self>> {
-        return Rc::new(RefCell::new(Environment {
+        let env = Rc::new(RefCell::new(Environment {
             store: HashMap::new(),
+            imports: HashMap::new(),
+            slots: vec![],
             outer: None,
+            exports: vec![],
+            module: None,
+            event_loop: None,
         }));
+        track_environment(&env);
+        return env;
+    }
+
+    pub fn new_module(module: Rc<ModuleContext>) -> Rc<RefCell<Self>> {
+        let env = Environment::new();
+        env.borrow_mut().module = Some(module);
+        return env;
     }
 
-    pub fn get(&self, key: String) -> Option<Rc<Box<dyn Object>>> {
-        match self.store.get(&key) {
+    pub fn get(&self, key: &str) -> Option<Value> {
+        match self.store.get(key) {
             Some(val) => return Some(val.clone()),
             None => {
+                if let Some(module) = self.imports.get(key) {
+                    return module.get(key);
+                }
                 if let Some(outer_env) = self.outer.as_ref() {
                     return outer_env.borrow().get(key);
                 }
@@ -28,10 +55,167 @@ impl Environment {
         }
     }
 
-    pub fn set(&mut self, key: String, value: Rc<Box<dyn Object>>) -> Option<Rc<Box<dyn Object>>> {
+    pub fn set(&mut self, key: String, value: Value) -> Option<Value> {
         self.store.insert(key, value.clone());
         return Some(value);
     }
+
+    /*
+    Update an existing binding, the outer chain is walked so that the value is
+    updated in the environment where it was declared. Returns None when the
+    key was never declared
+    */
+    pub fn assign(&mut self, key: String, value: Value) -> Option<Value> {
+        if let Some(current) = self.store.get_mut(&key) {
+            *current = value.clone();
+            return Some(value);
+        }
+        if let Some(module) = self.imports.get(&key) {
+            return module.env.borrow_mut().assign(key, value);
+        }
+        match self.outer.as_ref() {
+            Some(outer_env) => return outer_env.borrow_mut().assign(key, value),
+            None => return None,
+        }
+    }
+
+    /*
+    Bring an exported name of the module into this environment. The variable
+    stays in the module, so reads and assignments on either side see the same
+    value
+    */
+    pub fn import(&mut self, key: String, module: Rc<Module>) {
+        self.imports.insert(key, module);
+    }
+
+    //Module the name was imported from, only this environment is searched
+    pub fn get_import(&self, key: &str) -> Option<Rc<Module>> {
+        return self.imports.get(key).cloned();
+    }
+
+    //Whether this environment declares or imports the name, outer environments are not searched
+    pub fn contains(&self, key: &str) -> bool {
+        return self.store.contains_key(key) || self.imports.contains_key(key);
+    }
+
+    //Value of the variable a binding was resolved to, globals are looked up by name
+    pub fn get_binding(&self, binding: Binding, name: &str) -> Option<Value> {
+        match binding {
+            Binding::Local { depth, slot } => return self.get_local(depth, slot),
+            Binding::Global => return self.get(name),
+        }
+    }
+
+    fn get_local(&self, depth: usize, slot: usize) -> Option<Value> {
+        if depth == 0 {
+            return self.slots.get(slot).cloned().flatten();
+        }
+        match self.outer.as_ref() {
+            Some(outer_env) => return outer_env.borrow().get_local(depth - 1, slot),
+            None => return None,
+        }
+    }
+
+    pub fn set_slot(&mut self, slot: usize, value: Value) {
+        if self.slots.len() <= slot {
+            self.slots.resize(slot + 1, None);
+        }
+        self.slots[slot] = Some(value);
+    }
+
+    //Declare the variable of the identifier in this environment
+    pub fn define(&mut self, identifier: &Identifier, value: Value) {
+        match identifier.binding.get() {
+            Binding::Local { slot, .. } => self.set_slot(slot, value),
+            Binding::Global => {
+                self.store.insert(identifier.value.clone(), value);
+            }
+        }
+    }
+
+    //Same as assign for a variable the resolver found, None when it is not declared yet
+    pub fn assign_binding(&mut self, binding: Binding, name: &str, value: Value) -> Option<Value> {
+        match binding {
+            Binding::Local { depth, slot } => return self.assign_local(depth, slot, value),
+            Binding::Global => return self.assign(String::from(name), value),
+        }
+    }
+
+    fn assign_local(&mut self, depth: usize, slot: usize, value: Value) -> Option<Value> {
+        if depth == 0 {
+            match self.slots.get_mut(slot) {
+                Some(current) if current.is_some() => {
+                    *current = Some(value.clone());
+                    return Some(value);
+                }
+                _ => return None,
+            }
+        }
+        match self.outer.as_ref() {
+            Some(outer_env) => return outer_env.borrow_mut().assign_local(depth - 1, slot, value),
+            None => return None,
+        }
+    }
+
+    //Values and the outer environment it keeps alive, for the cycle collector
+    pub(crate) fn references(&self) -> (Vec<Value>, Option<Rc<RefCell<Environment>>>) {
+        let mut values: Vec<Value> = self.store.values().cloned().collect();
+        values.extend(self.slots.iter().flatten().cloned());
+        values.extend(
+            self.imports
+                .values()
+                .map(|module| Value::Module(module.clone())),
+        );
+        return (values, self.outer.clone());
+    }
+
+    //Drop every variable, the cycle collector does this to environments nothing can reach
+    pub(crate) fn clear(&mut self) {
+        self.store.clear();
+        self.imports.clear();
+        self.slots.clear();
+        self.outer = None;
+    }
+
+    pub fn export(&mut self, key: String) {
+        if !self.exports.contains(&key) {
+            self.exports.push(key);
+        }
+    }
+
+    pub fn get_exports(&self) -> Vec<String> {
+        return self.exports.clone();
+    }
+
+    pub fn set_module(&mut self, module: Rc<ModuleContext>) {
+        self.module = Some(module);
+    }
+
+    //Module the environment belongs to, found on the outermost environment
+    pub fn get_module(&self) -> Option<Rc<ModuleContext>> {
+        if let Some(module) = self.module.as_ref() {
+            return Some(module.clone());
+        }
+        match self.outer.as_ref() {
+            Some(outer_env) => return outer_env.borrow().get_module(),
+            None => return None,
+        }
+    }
+
+    pub fn set_event_loop(&mut self, event_loop: Rc<EventLoop>) {
+        self.event_loop = Some(event_loop);
+    }
+
+    //Event loop that runs the async functions, found on the outermost environment
+    pub fn get_event_loop(&self) -> Option<Rc<EventLoop>> {
+        if let Some(event_loop) = self.event_loop.as_ref() {
+            return Some(event_loop.clone());
+        }
+        match self.outer.as_ref() {
+            Some(outer_env) => return outer_env.borrow().get_event_loop(),
+            None => return None,
+        }
+    }
 }
 
 pub fn new_enclosed_environment(outer: Rc<RefCell<Environment>>) -> Rc<RefCell<Environment>> {
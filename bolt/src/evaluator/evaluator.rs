@@ -1,27 +1,31 @@
 use std::{cell::RefCell, rc::Rc};
 
 use crate::{
-    compiler::{Compiler, CompilerBackend, Factory},
+    compiler::{vm, CompilerBackend, Factory},
     error::{BoltError, EvaluatorError},
     evaluator::utils::{apply_function, eval_arg_expression},
-    object::object::{Function, Interger, Object},
+    object::object::{Function, Value},
     parser::{
-        ast::{
-            BinaryExpression, BlockStatement, Boolean, CallExpression, Expression,
-            ExpressionStatement, FunctionLiteral, Identifier, IfExpression, IntegerLiteral,
-            LetStatement, NullLiteral, PrefixExpression, ReturnStatement, Statement,
-        },
+        ast::{Expression, Statement},
         parser::Parser,
     },
+    resolver::resolver::resolve_program,
 };
 
 use super::{
-    constants::{FALSE, NULL, TRUE},
+    constants::{BREAK, CONTINUE, FALSE, NULL, TRUE},
     environment::Environment,
+    event_loop::EventLoop,
+    module::ModuleContext,
     utils::{
-        evaluate_binary_expression, evaluate_block_statement_ref, evaluate_condition_expression,
-        evaluate_identifier, evaluate_let_statement, evaluate_prefix_expression,
-        evaluate_return_statement,
+        evaluate_array_literal, evaluate_assign_expression, evaluate_binary_expression,
+        evaluate_block_statement_ref, evaluate_class_statement, evaluate_condition_expression,
+        evaluate_export_statement, evaluate_get_expression, evaluate_hash_literal,
+        evaluate_identifier, evaluate_import_statement, evaluate_index_expression,
+        evaluate_interpolated_string, evaluate_let_statement, evaluate_logical_expression,
+        evaluate_match_expression, evaluate_prefix_expression, evaluate_return_statement,
+        evaluate_super_expression, evaluate_this_expression, evaluate_throw_statement,
+        evaluate_try_statement, evaluate_while_statement,
     },
 };
 
@@ -54,36 +58,76 @@ impl Evaluator {
         }
     }
 
-    fn eval_jit(&self) -> Option<Result<Rc<Box<dyn Object>>, EvaluatorError>> {
+    fn eval_jit(&self) -> Option<Result<Value, EvaluatorError>> {
         let source = self.source.clone();
         let mut parser = Parser::new(&source);
-        let evaluated_result: Option<Result<Rc<Box<dyn Object>>, EvaluatorError>> = None;
+        let evaluated_result: Option<Result<Value, EvaluatorError>> = None;
         match parser.parse_program() {
             Ok(program) => {
                 let mut compiler =
                     Factory::new(self.backend.unwrap(), program, self.filename.as_str());
                 println!("Compiling to bytecode");
-                compiler.bytecode_to_jit(&self.target);
+                if let Err(e) = compiler.bytecode_to_jit(&self.target) {
+                    return Some(Err(e));
+                }
+            }
+            Err(e) => {
+                return Some(Err(EvaluatorError::new(
+                    e.get_message(),
+                    Some(e.get_type()),
+                    e.get_line(),
+                )));
             }
+        }
+        return evaluated_result;
+    }
+
+    fn prepare_environment(&self) -> Rc<EventLoop> {
+        return prepare_environment(&self.environment, &self.filename);
+    }
+
+    //Compile the program to bytecode and run it on the virtual machine
+    fn eval_vm(&self) -> Option<Result<Value, EvaluatorError>> {
+        let event_loop = self.prepare_environment();
+        let mut parser = Parser::new(&self.source);
+        let program = match parser.parse_program() {
+            Ok(program) => program,
             Err(e) => {
                 return Some(Err(EvaluatorError::new(
                     e.get_message(),
                     Some(e.get_type()),
-                    None,
+                    e.get_line(),
                 )));
             }
+        };
+        if let Err(e) = resolve_program(&program) {
+            return Some(Err(e));
+        }
+        let evaluated_result = match program.stmts.is_empty() {
+            true => None,
+            false => match vm::run(&program, &self.filename, self.environment.clone()) {
+                Ok(value) => Some(Ok(value)),
+                Err(e) => return Some(Err(e)),
+            },
+        };
+        if let Err(e) = event_loop.run() {
+            return Some(Err(e));
         }
         return evaluated_result;
     }
 
-    fn eval_interpretted(&self) -> Option<Result<Rc<Box<dyn Object>>, EvaluatorError>> {
+    fn eval_interpretted(&self) -> Option<Result<Value, EvaluatorError>> {
         let source = self.source.clone();
         let environment = self.environment.clone();
+        let event_loop = self.prepare_environment();
 
         let mut parser = Parser::new(&source);
-        let mut evaluated_result: Option<Result<Rc<Box<dyn Object>>, EvaluatorError>> = None;
+        let mut evaluated_result: Option<Result<Value, EvaluatorError>> = None;
         match parser.parse_program() {
             Ok(program) => {
+                if let Err(e) = resolve_program(&program) {
+                    return Some(Err(e));
+                }
                 for stmt in program.stmts {
                     match evaluate_statement(&stmt, environment.clone()) {
                         Ok(eval) => {
@@ -93,7 +137,7 @@ impl Evaluator {
                             return Some(Err(EvaluatorError::new(
                                 e.get_message(),
                                 Some(e.get_type()),
-                                None,
+                                e.get_line(),
                             )));
                         }
                     }
@@ -103,14 +147,21 @@ impl Evaluator {
                 return Some(Err(EvaluatorError::new(
                     e.get_message(),
                     Some(e.get_type()),
-                    None,
+                    e.get_line(),
                 )));
             }
         }
+        //Tasks started by the program run once its statements are done
+        if let Err(e) = event_loop.run() {
+            return Some(Err(e));
+        }
         return evaluated_result;
     }
 
-    pub fn eval(&self) -> Option<Result<Rc<Box<dyn Object>>, EvaluatorError>> {
+    pub fn eval(&self) -> Option<Result<Value, EvaluatorError>> {
+        if self.backend == Some(CompilerBackend::VM) {
+            return self.eval_vm();
+        }
         if self.jit {
             return self.eval_jit();
         } else {
@@ -119,76 +170,170 @@ impl Evaluator {
     }
 }
 
+//Give the environment the module and event loop it runs in, unless it already has them
+pub(crate) fn prepare_environment(
+    environment: &Rc<RefCell<Environment>>,
+    filename: &str,
+) -> Rc<EventLoop> {
+    //Imports are resolved relative to the file being evaluated
+    if environment.borrow().get_module().is_none() {
+        environment
+            .borrow_mut()
+            .set_module(ModuleContext::new_main(filename));
+    }
+    //The REPL keeps its environment between lines, so pending tasks carry over too
+    let event_loop = environment.borrow().get_event_loop();
+    match event_loop {
+        Some(event_loop) => return event_loop,
+        None => {
+            let event_loop = EventLoop::new();
+            environment.borrow_mut().set_event_loop(event_loop.clone());
+            return event_loop;
+        }
+    }
+}
+
 pub fn evaluate_expression(
-    expression: &Box<dyn Expression>,
+    expression: &Expression,
     environment: Rc<RefCell<Environment>>,
-) -> Result<Rc<Box<dyn Object>>, EvaluatorError> {
-    let value_any = expression.as_any();
-    if let Some(int) = value_any.downcast_ref::<IntegerLiteral>() {
-        return Ok(Rc::new(Box::new(Interger { value: int.value })));
-    } else if let Some(boolean) = value_any.downcast_ref::<Boolean>() {
-        if boolean.value == true {
-            return Ok(Rc::new(Box::new(TRUE)));
-        } else {
-            return Ok(Rc::new(Box::new(FALSE)));
-        }
-    } else if let Some(prefix) = value_any.downcast_ref::<PrefixExpression>() {
-        let right = evaluate_expression(&prefix.right, environment);
-        return evaluate_prefix_expression(prefix.operator.clone(), right.unwrap());
-    } else if let Some(binary) = value_any.downcast_ref::<BinaryExpression>() {
-        let left = evaluate_expression(&binary.left, environment.clone());
-        let right: Result<Rc<Box<dyn Object>>, EvaluatorError> =
-            evaluate_expression(&binary.right, environment.clone());
-        return evaluate_binary_expression(binary.operator.clone(), left.unwrap(), right.unwrap());
-    } else if let Some(if_expression) = value_any.downcast_ref::<IfExpression>() {
-        return evaluate_condition_expression(if_expression, environment);
-    } else if let Some(ident) = value_any.downcast_ref::<Identifier>() {
-        return evaluate_identifier(ident, environment);
-    } else if let Some(function) = value_any.downcast_ref::<FunctionLiteral>() {
-        let parameters = function.parameters.clone();
-        let body = function.body.clone();
-        return Ok(Rc::new(Box::new(Function {
-            parameters: parameters,
-            body: body,
-            env: Environment::new(),
-        })));
-    } else if let Some(call_expression) = value_any.downcast_ref::<CallExpression>() {
-        let function = evaluate_expression(&call_expression.funtion, environment.clone())?;
-        let args = eval_arg_expression(
-            call_expression.parameters.clone(),
-            function.clone(),
-            environment.clone(),
-        )?;
-        return apply_function(function, args, environment.clone());
-    } else if let Some(_null) = value_any.downcast_ref::<NullLiteral>() {
-        return Ok(Rc::new(Box::new(NULL)));
-    } else {
-        return Err(EvaluatorError::new(
-            String::from("Expression not found for eval"),
-            None,
-            None,
-        ));
+) -> Result<Value, EvaluatorError> {
+    match expression {
+        Expression::Integer(int) => return Ok(Value::Integer(int.value)),
+        Expression::Float(float) => return Ok(Value::Float(float.value)),
+        Expression::String(string) => return Ok(Value::string(string.value.clone())),
+        Expression::InterpolatedString(interpolated) => {
+            return evaluate_interpolated_string(interpolated, environment);
+        }
+        Expression::Boolean(boolean) => {
+            if boolean.value == true {
+                return Ok(TRUE);
+            } else {
+                return Ok(FALSE);
+            }
+        }
+        Expression::Prefix(prefix) => {
+            let right = evaluate_expression(&prefix.right, environment)?;
+            return evaluate_prefix_expression(&prefix.operator, right);
+        }
+        Expression::Binary(binary) => {
+            let left = evaluate_expression(&binary.left, environment.clone())?;
+            let right = evaluate_expression(&binary.right, environment.clone())?;
+            return evaluate_binary_expression(&binary.operator, left, right);
+        }
+        Expression::Super(super_expression) => {
+            return evaluate_super_expression(super_expression, environment);
+        }
+        Expression::This(this) => return evaluate_this_expression(this, environment),
+        Expression::Get(get) => return evaluate_get_expression(get, environment),
+        Expression::Logical(logical) => return evaluate_logical_expression(logical, environment),
+        Expression::Match(match_expression) => {
+            return evaluate_match_expression(match_expression, environment);
+        }
+        Expression::If(if_expression) => {
+            return evaluate_condition_expression(if_expression, environment);
+        }
+        Expression::Identifier(ident) => return evaluate_identifier(ident, environment),
+        Expression::Function(function) => {
+            let parameters = function.parameters.clone();
+            let body = function.body.clone();
+            //Capture the defining environment so the function closes over it
+            return Ok(Value::Function(Rc::new(Function {
+                parameters: parameters,
+                body: body,
+                env: environment.clone(),
+                is_generator: function.is_generator,
+                is_async: function.is_async,
+            })));
+        }
+        Expression::Call(call_expression) => {
+            let function = evaluate_expression(&call_expression.funtion, environment.clone())?;
+            let args = eval_arg_expression(
+                call_expression.parameters.clone(),
+                function.clone(),
+                environment.clone(),
+            )?;
+            return apply_function(function, args, environment.clone());
+        }
+        Expression::Array(array) => return evaluate_array_literal(array, environment),
+        Expression::Hash(hash) => return evaluate_hash_literal(hash, environment),
+        Expression::Index(index_expression) => {
+            let left = evaluate_expression(&index_expression.left, environment.clone())?;
+            let index = evaluate_expression(&index_expression.index, environment.clone())?;
+            return evaluate_index_expression(left, index);
+        }
+        Expression::Assign(assign) => return evaluate_assign_expression(assign, environment),
+        Expression::Null(_null) => return Ok(NULL),
+        Expression::Await(await_expression) => {
+            //Inside async functions await is handled by the executor, this is await at the top level
+            let value = evaluate_expression(&await_expression.value, environment.clone())?;
+            let promise = match &value {
+                Value::Promise(promise) => promise,
+                _ => return Ok(value),
+            };
+            let event_loop = environment.borrow().get_event_loop();
+            match event_loop {
+                Some(event_loop) => return event_loop.run_until(promise),
+                None => {
+                    return Err(EvaluatorError::new(
+                        String::from("await needs an event loop to run"),
+                        None,
+                        None,
+                    ));
+                }
+            }
+        }
+        Expression::Yield(_yield) => {
+            //The parser only allows yield where the generator executor handles it
+            return Err(EvaluatorError::new(
+                String::from("yield can only suspend a generator"),
+                None,
+                None,
+            ));
+        }
     }
 }
 
 pub fn evaluate_statement(
-    statement: &Box<dyn Statement>,
+    statement: &Statement,
     environment: Rc<RefCell<Environment>>,
-) -> Result<Rc<Box<dyn Object>>, EvaluatorError> {
-    let value_any = statement.as_any();
-    if let Some(expr) = value_any.downcast_ref::<ExpressionStatement>() {
-        return evaluate_expression(&expr.value, environment.clone());
-    } else if let Some(block_statement) = value_any.downcast_ref::<BlockStatement>() {
-        return evaluate_block_statement_ref(block_statement, environment.clone());
-    } else if let Some(return_statement) = value_any.downcast_ref::<ReturnStatement>() {
-        return evaluate_return_statement(return_statement, environment.clone());
-    } else if let Some(let_statement) = value_any.downcast_ref::<LetStatement>() {
-        return evaluate_let_statement(let_statement, environment.clone());
-    } else {
-        return Err(EvaluatorError::new(
-            String::from("Requested Statement type not found"),
-            None,
-            None,
-        ));
+) -> Result<Value, EvaluatorError> {
+    //Errors are located at the innermost statement they were raised in
+    match evaluate_statement_node(statement, environment) {
+        Err(e) if e.get_line().is_none() => {
+            return Err(EvaluatorError::new(
+                e.get_message(),
+                Some(e.get_type()),
+                Some(statement.line()),
+            ));
+        }
+        result => return result,
+    }
+}
+
+fn evaluate_statement_node(
+    statement: &Statement,
+    environment: Rc<RefCell<Environment>>,
+) -> Result<Value, EvaluatorError> {
+    match statement {
+        Statement::Expression(expr) => return evaluate_expression(&expr.value, environment),
+        Statement::Block(block_statement) => {
+            return evaluate_block_statement_ref(block_statement, environment);
+        }
+        Statement::Return(return_statement) => {
+            return evaluate_return_statement(return_statement, environment);
+        }
+        Statement::Let(let_statement) => return evaluate_let_statement(let_statement, environment),
+        Statement::While(while_statement) => {
+            return evaluate_while_statement(while_statement, environment);
+        }
+        Statement::Class(class_statement) => {
+            return evaluate_class_statement(class_statement, environment);
+        }
+        Statement::Throw(throw) => return evaluate_throw_statement(throw, environment),
+        Statement::Try(try_statement) => return evaluate_try_statement(try_statement, environment),
+        Statement::Import(import) => return evaluate_import_statement(import, environment),
+        Statement::Export(export) => return evaluate_export_statement(export, environment),
+        Statement::Break(_break) => return Ok(BREAK),
+        Statement::Continue(_continue) => return Ok(CONTINUE),
     }
 }
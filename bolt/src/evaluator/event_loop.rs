@@ -0,0 +1,188 @@
+use std::{
+    cell::{Cell, RefCell},
+    collections::{BTreeMap, VecDeque},
+    fmt,
+    rc::Rc,
+};
+
+use crate::{
+    error::{BoltError, EvaluatorError},
+    object::object::{Promise, PromiseState, Value},
+};
+
+use super::{constants::NULL, generator::Coroutine};
+
+type Outcome = Result<Value, EvaluatorError>;
+//Promises of sleeping tasks keyed by due time and creation order
+type Timers = BTreeMap<(u64, u64), Rc<Promise>>;
+
+//Body of an async or spawned function, each run continues it until its next await
+pub struct Task {
+    execution: RefCell<Box<dyn Coroutine>>,
+    promise: Rc<Promise>,
+}
+
+//A waiting task is referenced by the promise it waits on, so only its name is printed
+impl fmt::Debug for Task {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        return write!(f, "Task");
+    }
+}
+
+/*
+Runs tasks one at a time on a single thread. Ready tasks run in the order they
+were scheduled and timers fire only when nothing is ready. Time is simulated,
+sleep moves the clock forward instead of blocking, so a program always runs
+the same way
+*/
+#[derive(Debug)]
+pub struct EventLoop {
+    ready: RefCell<VecDeque<(Rc<Task>, Outcome)>>,
+    timers: RefCell<Timers>,
+    time: Cell<u64>,
+    timer_count: Cell<u64>,
+    rejected: RefCell<Vec<Rc<Promise>>>, //Reported at the end when nobody awaited them
+    running: Cell<bool>,
+}
+
+impl EventLoop {
+    pub fn new() -> Rc<Self> {
+        return Rc::new(EventLoop {
+            ready: RefCell::new(VecDeque::new()),
+            timers: RefCell::new(BTreeMap::new()),
+            time: Cell::new(0),
+            timer_count: Cell::new(0),
+            rejected: RefCell::new(vec![]),
+            running: Cell::new(false),
+        });
+    }
+
+    //Schedule the execution as a new task, the returned promise settles with its result
+    pub fn spawn(&self, execution: Box<dyn Coroutine>) -> Value {
+        let promise = Rc::new(Promise::new());
+        let task = Rc::new(Task {
+            execution: RefCell::new(execution),
+            promise: promise.clone(),
+        });
+        self.ready.borrow_mut().push_back((task, Ok(NULL)));
+        return Value::Promise(promise);
+    }
+
+    //Promise fulfilled with null once the clock has moved forward by the given time
+    pub fn sleep(&self, milliseconds: u64) -> Value {
+        let promise = Rc::new(Promise::new());
+        let due = self.time.get().saturating_add(milliseconds);
+        self.timers
+            .borrow_mut()
+            .insert((due, self.timer_count.get()), promise.clone());
+        self.timer_count.set(self.timer_count.get() + 1);
+        return Value::Promise(promise);
+    }
+
+    //Run tasks and timers until nothing is left, then report a rejection nobody awaited
+    pub fn run(&self) -> Result<(), EvaluatorError> {
+        self.enter()?;
+        while self.step() {}
+        self.running.set(false);
+
+        let rejected: Vec<Rc<Promise>> = self.rejected.borrow_mut().drain(..).collect();
+        for promise in rejected {
+            if let PromiseState::Rejected(error) = &*promise.state.borrow() {
+                if !promise.handled.get() {
+                    return Err(error.clone());
+                }
+            }
+        }
+        return Ok(());
+    }
+
+    //Run the loop until the promise settles, this is how await works outside of async functions
+    pub fn run_until(&self, promise: &Promise) -> Outcome {
+        self.enter()?;
+        promise.handled.set(true);
+        let outcome = loop {
+            match &*promise.state.borrow() {
+                PromiseState::Fulfilled(value) => break Ok(value.clone()),
+                PromiseState::Rejected(error) => break Err(error.clone()),
+                PromiseState::Pending(_) => {}
+            }
+            if !self.step() {
+                break Err(EvaluatorError::new(
+                    String::from("Awaited promise can never settle"),
+                    None,
+                    None,
+                ));
+            }
+        };
+        self.running.set(false);
+        return outcome;
+    }
+
+    fn enter(&self) -> Result<(), EvaluatorError> {
+        if self.running.replace(true) {
+            return Err(EvaluatorError::new(
+                String::from("await outside of an async function while the event loop is running"),
+                None,
+                None,
+            ));
+        }
+        return Ok(());
+    }
+
+    //Run one ready task or fire the earliest timer, returns false when there is nothing to do
+    fn step(&self) -> bool {
+        let next = self.ready.borrow_mut().pop_front();
+        if let Some((task, outcome)) = next {
+            let result = task.execution.borrow_mut().resume(outcome);
+            match result {
+                Ok((value, true)) => self.settle(&task.promise, Ok(value)),
+                Ok((awaited, false)) => self.wait(task, awaited),
+                Err(error) => self.settle(&task.promise, Err(error)),
+            }
+            return true;
+        }
+        let timer = self.timers.borrow_mut().pop_first();
+        if let Some(((due, _), promise)) = timer {
+            self.time.set(due);
+            self.settle(&promise, Ok(NULL));
+            return true;
+        }
+        return false;
+    }
+
+    //Resume the task once the awaited promise settles, any other value is passed straight back
+    fn wait(&self, task: Rc<Task>, awaited: Value) {
+        let promise = match &awaited {
+            Value::Promise(promise) => promise,
+            _ => {
+                self.ready.borrow_mut().push_back((task, Ok(awaited)));
+                return;
+            }
+        };
+        promise.handled.set(true);
+        let outcome = match &mut *promise.state.borrow_mut() {
+            PromiseState::Pending(waiting) => {
+                waiting.push(task);
+                return;
+            }
+            PromiseState::Fulfilled(value) => Ok(value.clone()),
+            PromiseState::Rejected(error) => Err(error.clone()),
+        };
+        self.ready.borrow_mut().push_back((task, outcome));
+    }
+
+    fn settle(&self, promise: &Rc<Promise>, outcome: Outcome) {
+        let state = match &outcome {
+            Ok(value) => PromiseState::Fulfilled(value.clone()),
+            Err(error) => PromiseState::Rejected(error.clone()),
+        };
+        if let PromiseState::Pending(waiting) = promise.state.replace(state) {
+            for task in waiting {
+                self.ready.borrow_mut().push_back((task, outcome.clone()));
+            }
+        }
+        if outcome.is_err() {
+            self.rejected.borrow_mut().push(promise.clone());
+        }
+    }
+}
@@ -0,0 +1,633 @@
+use std::{cell::RefCell, rc::Rc};
+
+use crate::{
+    error::{BoltError, EvaluatorError},
+    gc::heap::alloc_hash,
+    object::{
+        object::{Hash, HashPair, Value},
+        types::HashKey,
+    },
+    parser::ast::{BlockStatement, Expression, Statement, TryStatement},
+};
+
+use super::{
+    constants::NULL,
+    environment::{new_enclosed_environment, Environment},
+    evaluator::{evaluate_expression, evaluate_statement},
+    utils::{
+        assign_to_target, error_object, is_control_flow, is_truthy, nested_call, select_match_arm,
+    },
+};
+
+/*
+A generator can not keep its place on the Rust call stack between two calls to
+next, so its body runs on an explicit stack of frames instead. Async functions
+pause at an await the same way. Only statements that contain a yield or an
+await get a frame, everything else is evaluated by the tree walking evaluator
+as usual
+*/
+#[derive(Debug)]
+enum Frame {
+    //Runs the statements of a block in order
+    Block {
+        block: Rc<Box<BlockStatement>>,
+        index: usize,
+        env: Rc<RefCell<Environment>>,
+    },
+    //The while statement at index in block, its condition is checked whenever the frame is on top
+    While {
+        block: Rc<Box<BlockStatement>>,
+        index: usize,
+        env: Rc<RefCell<Environment>>,
+    },
+    //The try statement at index in block, it is on top when the running part finished normally
+    Try {
+        block: Rc<Box<BlockStatement>>,
+        index: usize,
+        env: Rc<RefCell<Environment>>,
+        stage: TryStage,
+        pending: Option<Signal>, //Raised in the body or catch, raised again after finally
+    },
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum TryStage {
+    Body,
+    Catch,
+    Finally,
+}
+
+//Reason the running block stopped early, frames are unwound until one handles it
+#[derive(Debug)]
+enum Signal {
+    Error(EvaluatorError),
+    Flow(Value), //Return, break or continue
+}
+
+//What the value passed to next is used for when the generator continues
+#[derive(Debug)]
+enum Resume {
+    Discard,
+    Let {
+        block: Rc<Box<BlockStatement>>,
+        index: usize,
+        env: Rc<RefCell<Environment>>,
+    },
+    Assign {
+        block: Rc<Box<BlockStatement>>,
+        index: usize,
+        env: Rc<RefCell<Environment>>,
+    },
+}
+
+enum Step {
+    Yield(Value),
+    Return(Value),
+}
+
+/*
+Body of a generator or an async function that can be paused and continued.
+Both the tree walking evaluator and the virtual machine provide one, so the
+generator protocol and the event loop work the same for either
+*/
+pub trait Coroutine: std::fmt::Debug {
+    /*
+    Run the body until the next yield or await or until it finishes, returns the
+    value and whether the body is done. A finished body stays done. An error
+    is raised at the point the body is paused at, like a rejected await
+    */
+    fn resume(
+        &mut self,
+        sent: Result<Value, EvaluatorError>,
+    ) -> Result<(Value, bool), EvaluatorError>;
+}
+
+#[derive(Debug)]
+pub struct Execution {
+    frames: Vec<Frame>,
+    resume: Resume,
+    done: bool,
+    last: Option<Value>, //Value of the last statement, kept only for async functions
+}
+
+impl Coroutine for Execution {
+    fn resume(
+        &mut self,
+        sent: Result<Value, EvaluatorError>,
+    ) -> Result<(Value, bool), EvaluatorError> {
+        if self.done {
+            return Ok((NULL, true));
+        }
+        let signal = match sent {
+            Ok(sent) => self.apply_resume(sent).err(),
+            Err(e) => {
+                self.resume = Resume::Discard;
+                Some(Signal::Error(e))
+            }
+        };
+        let result = nested_call(|| self.run(signal));
+        if !matches!(result, Ok(Step::Yield(_))) {
+            self.done = true;
+            self.frames.clear();
+        }
+        match result {
+            Ok(Step::Yield(value)) => return Ok((value, false)),
+            Ok(Step::Return(value)) => return Ok((value, true)),
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+impl Execution {
+    pub fn new(body: Rc<Box<BlockStatement>>, env: Rc<RefCell<Environment>>) -> Self {
+        return Self {
+            frames: vec![Frame::Block {
+                block: body,
+                index: 0,
+                env: env,
+            }],
+            resume: Resume::Discard,
+            done: false,
+            last: None,
+        };
+    }
+
+    //Like a normal function, an async function without a return gives its last value
+    pub fn new_async(body: Rc<Box<BlockStatement>>, env: Rc<RefCell<Environment>>) -> Self {
+        let mut execution = Self::new(body, env);
+        execution.last = Some(NULL);
+        return execution;
+    }
+
+    fn set_last(&mut self, value: Value) {
+        if self.last.is_some() {
+            self.last = Some(value);
+        }
+    }
+
+    fn apply_resume(&mut self, sent: Value) -> Result<(), Signal> {
+        match std::mem::replace(&mut self.resume, Resume::Discard) {
+            Resume::Discard => {
+                self.set_last(sent);
+                return Ok(());
+            }
+            Resume::Let { block, index, env } => {
+                if let Statement::Let(let_statement) = &block.statements[index] {
+                    env.borrow_mut().define(&let_statement.identifier, sent);
+                }
+                self.set_last(NULL);
+                return Ok(());
+            }
+            Resume::Assign { block, index, env } => {
+                let statement = &block.statements[index];
+                if let Some(Expression::Assign(assign)) = expression_statement(statement) {
+                    match assign_to_target(&assign.target, || Ok(sent), env) {
+                        Ok(value) => self.set_last(value),
+                        Err(e) => return Err(Signal::Error(located(e, statement))),
+                    }
+                }
+                return Ok(());
+            }
+        }
+    }
+
+    fn run(&mut self, mut signal: Option<Signal>) -> Result<Step, EvaluatorError> {
+        loop {
+            if let Some(pending) = signal.take() {
+                if let Some(step) = self.unwind(pending)? {
+                    return Ok(step);
+                }
+            }
+            let frame = match self.frames.last_mut() {
+                Some(frame) => frame,
+                //Running past the end of the body returns null, or the last value for async functions
+                None => {
+                    let value = self.last.take();
+                    return Ok(Step::Return(value.unwrap_or(NULL)));
+                }
+            };
+            match frame {
+                Frame::Block { block, index, env } => {
+                    if *index >= block.statements.len() {
+                        self.frames.pop();
+                        continue;
+                    }
+                    let (block, position, env) = (block.clone(), *index, env.clone());
+                    *index += 1;
+                    match self.execute(block, position, env) {
+                        Ok(Some(value)) => return Ok(Step::Yield(value)),
+                        Ok(None) => {}
+                        Err(pending) => signal = Some(pending),
+                    }
+                }
+                Frame::While { block, index, env } => {
+                    let (block, env) = (block.clone(), env.clone());
+                    let statement = &block.statements[*index];
+                    let while_statement = match statement {
+                        Statement::While(while_statement) => while_statement,
+                        _ => {
+                            self.frames.pop();
+                            continue;
+                        }
+                    };
+                    match evaluate_expression(&while_statement.condition, env.clone()) {
+                        Ok(condition) => {
+                            if is_truthy(&condition) {
+                                self.frames.push(Frame::Block {
+                                    block: while_statement.body.clone(),
+                                    index: 0,
+                                    env: env,
+                                });
+                            } else {
+                                self.frames.pop();
+                                self.set_last(NULL);
+                            }
+                        }
+                        Err(e) => signal = Some(Signal::Error(located(e, statement))),
+                    }
+                }
+                Frame::Try { .. } => {
+                    //The body or the catch finished normally, finally runs next if there is one
+                    if let Some(Frame::Try {
+                        block,
+                        index,
+                        env,
+                        stage,
+                        pending,
+                    }) = self.frames.pop()
+                    {
+                        let finally_body = try_statement(&block, index)
+                            .and_then(|try_statement| try_statement.finally_body.clone());
+                        match finally_body {
+                            Some(finally_body) if stage != TryStage::Finally => {
+                                self.frames.push(Frame::Try {
+                                    block: block,
+                                    index: index,
+                                    env: env.clone(),
+                                    stage: TryStage::Finally,
+                                    pending: None,
+                                });
+                                self.frames.push(Frame::Block {
+                                    block: finally_body,
+                                    index: 0,
+                                    env: env,
+                                });
+                            }
+                            _ => signal = pending,
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /*
+    Run the statement at position in block. A yield returns the value to produce,
+    statements containing a yield push frames for their blocks instead of running them
+    */
+    fn execute(
+        &mut self,
+        block: Rc<Box<BlockStatement>>,
+        position: usize,
+        env: Rc<RefCell<Environment>>,
+    ) -> Result<Option<Value>, Signal> {
+        let statement = &block.statements[position];
+        if !statement_contains_yield(statement) {
+            match evaluate_statement(statement, env) {
+                Ok(value) if is_control_flow(&value) => return Err(Signal::Flow(value)),
+                Ok(value) => {
+                    self.set_last(value);
+                    return Ok(None);
+                }
+                Err(e) => return Err(Signal::Error(e)),
+            }
+        }
+
+        match statement {
+            Statement::Let(let_statement) => {
+                let value = yielded_value(&let_statement.value, statement, env.clone())?;
+                self.resume = Resume::Let {
+                    block: block.clone(),
+                    index: position,
+                    env: env,
+                };
+                return Ok(Some(value));
+            }
+            Statement::While(_) => {
+                self.frames.push(Frame::While {
+                    block: block.clone(),
+                    index: position,
+                    env: env,
+                });
+            }
+            Statement::Try(try_body) => {
+                let body = try_body.body.clone();
+                self.frames.push(Frame::Try {
+                    block: block.clone(),
+                    index: position,
+                    env: env.clone(),
+                    stage: TryStage::Body,
+                    pending: None,
+                });
+                self.frames.push(Frame::Block {
+                    block: body,
+                    index: 0,
+                    env: env,
+                });
+            }
+            Statement::Expression(expression_statement) => {
+                match expression_statement.value.as_ref() {
+                    Expression::Yield(_) | Expression::Await(_) => {
+                        let value = yielded_value(&expression_statement.value, statement, env)?;
+                        self.resume = Resume::Discard;
+                        return Ok(Some(value));
+                    }
+                    Expression::Assign(assign) => {
+                        let value = yielded_value(&assign.value, statement, env.clone())?;
+                        self.resume = Resume::Assign {
+                            block: block.clone(),
+                            index: position,
+                            env: env,
+                        };
+                        return Ok(Some(value));
+                    }
+                    Expression::If(if_expression) => {
+                        let condition = evaluate_expression(&if_expression.condition, env.clone())
+                            .map_err(|e| Signal::Error(located(e, statement)))?;
+                        let branch = if is_truthy(&condition) {
+                            Some(if_expression.consequence.clone())
+                        } else {
+                            if_expression.alternate.clone()
+                        };
+                        if let Some(branch) = branch {
+                            self.frames.push(Frame::Block {
+                                block: branch,
+                                index: 0,
+                                env: env,
+                            });
+                        }
+                    }
+                    Expression::Match(match_expression) => {
+                        let (body, arm_env) = select_match_arm(match_expression, env)
+                            .map_err(|e| Signal::Error(located(e, statement)))?;
+                        self.frames.push(Frame::Block {
+                            block: body,
+                            index: 0,
+                            env: arm_env,
+                        });
+                    }
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+        return Ok(None);
+    }
+
+    //Pop frames until one handles the signal, returns a step when the generator finishes
+    fn unwind(&mut self, signal: Signal) -> Result<Option<Step>, EvaluatorError> {
+        loop {
+            let frame = match self.frames.pop() {
+                Some(frame) => frame,
+                None => match signal {
+                    Signal::Error(e) => return Err(e),
+                    Signal::Flow(Value::Return(value)) => return Ok(Some(Step::Return(*value))),
+                    //The parser keeps break and continue inside loops
+                    Signal::Flow(_) => return Ok(Some(Step::Return(NULL))),
+                },
+            };
+            match frame {
+                Frame::Block { .. } => {}
+                Frame::While { block, index, env } => {
+                    if let Signal::Flow(value) = &signal {
+                        if let Value::Break = value {
+                            return Ok(None);
+                        }
+                        if let Value::Continue = value {
+                            self.frames.push(Frame::While {
+                                block: block,
+                                index: index,
+                                env: env,
+                            });
+                            return Ok(None);
+                        }
+                    }
+                }
+                Frame::Try {
+                    block,
+                    index,
+                    env,
+                    stage,
+                    pending: _,
+                } => {
+                    let (catch, finally_body) = match try_statement(&block, index) {
+                        Some(try_statement) => (
+                            try_statement
+                                .catch_parameter
+                                .as_ref()
+                                .zip(try_statement.catch_body.as_ref())
+                                .map(|(parameter, body)| (parameter, body.clone())),
+                            try_statement.finally_body.clone(),
+                        ),
+                        None => (None, None),
+                    };
+                    if let (TryStage::Body, Signal::Error(error), Some((parameter, catch_body))) =
+                        (stage, &signal, catch)
+                    {
+                        let catch_env = new_enclosed_environment(env.clone());
+                        catch_env
+                            .borrow_mut()
+                            .define(parameter, Value::Error(Rc::new(error_object(error))));
+                        self.frames.push(Frame::Try {
+                            block: block,
+                            index: index,
+                            env: env,
+                            stage: TryStage::Catch,
+                            pending: None,
+                        });
+                        self.frames.push(Frame::Block {
+                            block: catch_body,
+                            index: 0,
+                            env: catch_env,
+                        });
+                        return Ok(None);
+                    }
+                    //A signal raised inside finally replaces the pending one
+                    if let (Some(finally_body), false) = (finally_body, stage == TryStage::Finally)
+                    {
+                        self.frames.push(Frame::Try {
+                            block: block,
+                            index: index,
+                            env: env.clone(),
+                            stage: TryStage::Finally,
+                            pending: Some(signal),
+                        });
+                        self.frames.push(Frame::Block {
+                            block: finally_body,
+                            index: 0,
+                            env: env,
+                        });
+                        return Ok(None);
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn expression_statement(statement: &Statement) -> Option<&Expression> {
+    match statement {
+        Statement::Expression(expression_statement) => return Some(&expression_statement.value),
+        _ => return None,
+    }
+}
+
+fn try_statement(block: &BlockStatement, index: usize) -> Option<&TryStatement> {
+    match &block.statements[index] {
+        Statement::Try(try_statement) => return Some(try_statement),
+        _ => return None,
+    }
+}
+
+//Evaluate the operand of a yield or await, a yield without one produces null
+fn yielded_value(
+    expression: &Expression,
+    statement: &Statement,
+    env: Rc<RefCell<Environment>>,
+) -> Result<Value, Signal> {
+    let value = match expression {
+        Expression::Yield(yield_expression) => yield_expression.value.as_ref(),
+        Expression::Await(await_expression) => Some(&await_expression.value),
+        _ => None,
+    };
+    match value {
+        Some(value) => {
+            return evaluate_expression(value, env)
+                .map_err(|e| Signal::Error(located(e, statement)));
+        }
+        None => return Ok(NULL),
+    }
+}
+
+//Errors without a line are reported at the statement they were raised in
+fn located(error: EvaluatorError, statement: &Statement) -> EvaluatorError {
+    if error.get_line().is_some() {
+        return error;
+    }
+    return EvaluatorError::new(
+        error.get_message(),
+        Some(error.get_type()),
+        Some(statement.line()),
+    );
+}
+
+/*
+The parser moves every yield to a statement, the value of a let or an
+assignment, or inside the blocks of statements that can be resumed
+*/
+fn statement_contains_yield(statement: &Statement) -> bool {
+    match statement {
+        Statement::Expression(expression_statement) => {
+            return expression_contains_yield(&expression_statement.value);
+        }
+        Statement::Let(let_statement) => return is_yield(&let_statement.value),
+        Statement::While(while_statement) => return block_contains_yield(&while_statement.body),
+        Statement::Try(try_statement) => {
+            return block_contains_yield(&try_statement.body)
+                || try_statement
+                    .catch_body
+                    .as_ref()
+                    .is_some_and(|body| block_contains_yield(body))
+                || try_statement
+                    .finally_body
+                    .as_ref()
+                    .is_some_and(|body| block_contains_yield(body));
+        }
+        _ => return false,
+    }
+}
+
+fn expression_contains_yield(expression: &Expression) -> bool {
+    match expression {
+        Expression::Assign(assign) => return is_yield(&assign.value),
+        Expression::If(if_expression) => {
+            return block_contains_yield(&if_expression.consequence)
+                || if_expression
+                    .alternate
+                    .as_ref()
+                    .is_some_and(|alternate| block_contains_yield(alternate));
+        }
+        Expression::Match(match_expression) => {
+            return match_expression
+                .arms
+                .iter()
+                .any(|arm| block_contains_yield(&arm.body));
+        }
+        _ => return is_yield(expression),
+    }
+}
+
+fn block_contains_yield(block: &BlockStatement) -> bool {
+    return block.statements.iter().any(statement_contains_yield);
+}
+
+//Await suspends an async function exactly where yield suspends a generator
+fn is_yield(expression: &Expression) -> bool {
+    return matches!(expression, Expression::Yield(_) | Expression::Await(_));
+}
+
+/*
+next runs the generator to its next yield and returns {"value": v, "done": false},
+once the body returns it gives the returned value with done set to true. The
+argument of next becomes the result of the yield the generator is paused at
+*/
+pub fn generator_next(receiver: Value, args: Vec<Value>) -> Result<Value, EvaluatorError> {
+    if args.len() > 1 {
+        return Err(EvaluatorError::new(
+            format!(
+                "Wrong number of arguments to next: expected at most 1, found {}",
+                args.len()
+            ),
+            None,
+            None,
+        ));
+    }
+    let generator = match &receiver {
+        Value::Generator(generator) => generator,
+        _ => {
+            return Err(EvaluatorError::new(
+                format!("next called on {}", receiver.inspect()),
+                None,
+                None,
+            ));
+        }
+    };
+    //The body calling next on its own generator would need to resume itself
+    let mut execution = match generator.execution.try_borrow_mut() {
+        Ok(execution) => execution,
+        Err(_) => {
+            return Err(EvaluatorError::new(
+                String::from("Generator is already running"),
+                None,
+                None,
+            ));
+        }
+    };
+    let sent: Value = match args.into_iter().next() {
+        Some(sent) => sent,
+        None => NULL,
+    };
+    let (value, done) = execution.resume(Ok(sent))?;
+
+    let result = Hash::new();
+    for (key, value) in [("value", value), ("done", Value::Boolean(done))] {
+        result.set(
+            HashKey::Str(String::from(key)),
+            HashPair {
+                key: Value::string(String::from(key)),
+                value: value,
+            },
+        );
+    }
+    return Ok(alloc_hash(result));
+}
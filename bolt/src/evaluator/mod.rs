@@ -1,4 +1,8 @@
+pub(crate) mod builtins;
 pub(crate) mod constants;
 pub mod environment;
 pub mod evaluator;
-mod utils;
+pub mod event_loop;
+pub mod generator;
+pub mod module;
+pub(crate) mod utils;
@@ -0,0 +1,168 @@
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    rc::Rc,
+};
+
+use crate::{
+    error::{BoltError, EvaluatorError},
+    object::object::{Module, Value},
+    parser::{ast::Program, parser::Parser},
+    resolver::resolver::resolve_program,
+};
+
+use super::{environment::Environment, event_loop::EventLoop};
+
+//Runs the body of a module in its environment, each backend provides its own
+pub type ModuleRunner = fn(Program, Rc<RefCell<Environment>>) -> Result<(), EvaluatorError>;
+
+/*
+Shared by every module of a program, each file is evaluated once and the
+resulting module is cached by its canonical path. Files currently being
+evaluated are kept on a stack to detect import cycles
+*/
+#[derive(Debug)]
+pub struct ModuleLoader {
+    cache: HashMap<PathBuf, Value>,
+    loading: Vec<PathBuf>,
+}
+
+impl ModuleLoader {
+    pub fn new() -> Rc<RefCell<Self>> {
+        return Rc::new(RefCell::new(ModuleLoader {
+            cache: HashMap::new(),
+            loading: vec![],
+        }));
+    }
+}
+
+#[derive(Debug)]
+pub struct ModuleContext {
+    pub path: PathBuf, //File of the module, imports are resolved relative to it
+    pub loader: Rc<RefCell<ModuleLoader>>,
+}
+
+impl ModuleContext {
+    //Context for the file the program was started from
+    pub fn new_main(path: &str) -> Rc<Self> {
+        let path = PathBuf::from(path);
+        let loader = ModuleLoader::new();
+        if let Ok(canonical) = fs::canonicalize(&path) {
+            loader.borrow_mut().loading.push(canonical);
+        }
+        return Rc::new(ModuleContext {
+            path: path,
+            loader: loader,
+        });
+    }
+}
+
+fn module_name(path: &Path) -> String {
+    return path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+}
+
+fn resolve_module_path(importer: &Path, path: &str) -> Result<PathBuf, EvaluatorError> {
+    let base = importer.parent().unwrap_or(Path::new(""));
+    let mut candidate = base.join(path);
+    if candidate.extension().is_none() {
+        candidate.set_extension("bolt");
+    }
+    match fs::canonicalize(&candidate) {
+        Ok(resolved) => return Ok(resolved),
+        Err(_) => {
+            return Err(EvaluatorError::new(
+                format!("Cannot find module \"{}\"", path),
+                None,
+                None,
+            ));
+        }
+    }
+}
+
+pub fn load_module(
+    context: &ModuleContext,
+    path: &str,
+    event_loop: Option<Rc<EventLoop>>,
+    run: ModuleRunner,
+) -> Result<Value, EvaluatorError> {
+    let resolved = resolve_module_path(&context.path, path)?;
+    {
+        let loader = context.loader.borrow();
+        if let Some(module) = loader.cache.get(&resolved) {
+            return Ok(module.clone());
+        }
+        if let Some(position) = loader.loading.iter().position(|file| *file == resolved) {
+            let mut chain: Vec<String> = loader.loading[position..]
+                .iter()
+                .map(|file| module_name(file))
+                .collect();
+            chain.push(module_name(&resolved));
+            return Err(EvaluatorError::new(
+                format!("Import cycle detected: {}", chain.join(" -> ")),
+                None,
+                None,
+            ));
+        }
+    }
+
+    let source = match fs::read_to_string(&resolved) {
+        Ok(source) => source,
+        Err(e) => {
+            return Err(EvaluatorError::new(
+                format!("Cannot read module \"{}\": {}", path, e),
+                None,
+                None,
+            ));
+        }
+    };
+    let mut parser = Parser::new(&source);
+    let program = match parser.parse_program() {
+        Ok(program) => program,
+        Err(e) => {
+            return Err(EvaluatorError::new(
+                format!("Error in module \"{}\": {}", path, e.get_message()),
+                Some(e.get_type()),
+                None,
+            ));
+        }
+    };
+    if let Err(e) = resolve_program(&program) {
+        return Err(EvaluatorError::new(
+            format!("Error in module \"{}\": {}", path, e.get_message()),
+            Some(e.get_type()),
+            None,
+        ));
+    }
+
+    let env = Environment::new_module(Rc::new(ModuleContext {
+        path: resolved.clone(),
+        loader: context.loader.clone(),
+    }));
+    //Async functions of every module run on the loop of the program
+    if let Some(event_loop) = event_loop {
+        env.borrow_mut().set_event_loop(event_loop);
+    }
+    //The loader must not stay borrowed while the module runs, it may import others
+    context.loader.borrow_mut().loading.push(resolved.clone());
+    let result = run(program, env.clone());
+    context.loader.borrow_mut().loading.pop();
+    result?;
+
+    let exports = env.borrow().get_exports();
+    let module = Value::Module(Rc::new(Module {
+        name: module_name(&resolved),
+        env: env,
+        exports: exports,
+    }));
+    context
+        .loader
+        .borrow_mut()
+        .cache
+        .insert(resolved, module.clone());
+    return Ok(module);
+}
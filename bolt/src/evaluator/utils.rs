@@ -1,57 +1,70 @@
-use std::{cell::RefCell, collections::HashMap, rc::Rc};
+use std::{
+    cell::{Cell, RefCell},
+    collections::HashMap,
+    rc::Rc,
+};
 
 use crate::{
-    error::{BoltError, EvaluatorError},
+    error::{BoltError, BoltErrorType, EvaluatorError},
+    gc::heap::{alloc_array, alloc_hash, alloc_instance},
     object::{
-        object::{BooleanObj, Function, Interger, Null, Object, Return},
-        types::ObjectType,
+        object::{
+            Array, BoundMethod, BuiltinMethod, Class, ErrorObj, Function, Generator, Hash,
+            HashPair, Value,
+        },
+        types::HashKey,
     },
     parser::ast::{
-        BlockStatement, Expression, Identifier, IfExpression, LetStatement, ReturnStatement,
-        Statement,
+        ArrayLiteral, AssignExpression, Binding, BlockStatement, ClassStatement, ExportStatement,
+        Expression, GetExpression, HashLiteral, Identifier, IfExpression, ImportStatement,
+        InterpolatedString, LetStatement, LogicalExpression, MatchExpression, Pattern, Program,
+        ReturnStatement, Statement, StringPart, SuperExpression, ThisExpression, ThrowStatement,
+        TryStatement, WhileStatement,
     },
 };
 
 use super::{
-    constants::{FALSE, NULL, TRUE},
+    builtins::get_builtin,
+    constants::{MAX_CALL_DEPTH, NULL, TRUE},
     environment::{new_enclosed_environment, Environment},
     evaluator::{evaluate_expression, evaluate_statement},
+    generator::{generator_next, Execution},
+    module::{load_module, ModuleRunner},
 };
 
-pub fn evaluate_prefix_expression(
-    operator: String,
-    right: Rc<Box<dyn Object>>,
-) -> Result<Rc<Box<dyn Object>>, EvaluatorError> {
-    match operator.as_str() {
-        "!" => {
-            if right.get_type() == ObjectType::BOOLEAN {
-                if right.inspect().as_str() == "true" {
-                    return Ok(Rc::new(Box::new(FALSE)));
-                } else {
-                    return Ok(Rc::new(Box::new(TRUE)));
-                }
-            } else if right.get_type() == ObjectType::NULL {
-                return Ok(Rc::new(Box::new(TRUE)));
-            } else {
+pub fn evaluate_prefix_expression(operator: &str, right: Value) -> Result<Value, EvaluatorError> {
+    match operator {
+        "!" => match right {
+            Value::Boolean(value) => return Ok(Value::Boolean(!value)),
+            Value::Null => return Ok(TRUE),
+            _ => {
                 return Err(EvaluatorError::new(
                     String::from("Only Boolean and NULL are allowed for ! prefix"),
                     None,
                     None,
                 ));
             }
-        }
-        "-" => {
-            let value_any = right.as_any();
-            if let Some(int) = value_any.downcast_ref::<Interger>() {
-                let new_float = -1.0 * int.value;
-                return Ok(Rc::new(Box::new(Interger { value: new_float })));
+        },
+        "-" => match right {
+            Value::Integer(int) => match int.checked_neg() {
+                Some(value) => return Ok(Value::Integer(value)),
+                None => {
+                    return Err(EvaluatorError::new(
+                        format!("Integer overflow negating {}", int),
+                        None,
+                        None,
+                    ));
+                }
+            },
+            Value::Float(float) => return Ok(Value::Float(-float)),
+            _ => {
+                return Err(EvaluatorError::new(
+                    String::from("Error Downcasting integer"),
+                    None,
+                    None,
+                ));
             }
-            return Err(EvaluatorError::new(
-                String::from("Error Downcasting integer"),
-                None,
-                None,
-            ));
-        }
+        },
         _ => {
             return Err(EvaluatorError::new(
                 String::from("Prefix that you are trying is not allowed"),
@@ -62,191 +75,736 @@ pub fn evaluate_prefix_expression(
     }
 }
 
+//Comparison shared by every operand type, None when the operator is not a comparison
+fn compare<T: PartialOrd + ?Sized>(operator: &str, left: &T, right: &T) -> Option<Value> {
+    let value = match operator {
+        "<" => left < right,
+        ">" => left > right,
+        "==" => left == right,
+        "!=" => left != right,
+        ">=" => left >= right,
+        "<=" => left <= right,
+        _ => return None,
+    };
+    return Some(Value::Boolean(value));
+}
+
 pub fn evaluate_binary_expression(
-    operator: String,
-    left: Rc<Box<dyn Object>>,
-    right: Rc<Box<dyn Object>>,
-) -> Result<Rc<Box<dyn Object>>, EvaluatorError> {
-    let right_value_any = right.as_any();
-    let left_value_any = left.as_any();
-    if left.get_type() == ObjectType::INTERGER && right.get_type() == ObjectType::INTERGER {
-        let right_val: &Interger;
-        let left_value: &Interger;
-        if let Some(int) = right_value_any.downcast_ref::<Interger>() {
-            right_val = int;
-        } else {
-            return Err(EvaluatorError::new(
-                String::from("Error Downcasting Integer"),
-                None,
-                None,
-            ));
-        }
-        if let Some(int) = left_value_any.downcast_ref::<Interger>() {
-            left_value = int;
-        } else {
-            return Err(EvaluatorError::new(
-                String::from("Error Downcasting Integer"),
-                None,
-                None,
-            ));
+    operator: &str,
+    left: Value,
+    right: Value,
+) -> Result<Value, EvaluatorError> {
+    match (&left, &right) {
+        (Value::Integer(left_value), Value::Integer(right_value)) => {
+            return evaluate_integer_expression(operator, *left_value, *right_value);
         }
-        match operator.as_str() {
-            "+" => {
-                let new_value = left_value.value + right_val.value;
-                return Ok(Rc::new(Box::new(Interger { value: new_value })));
-            }
-            "-" => {
-                let new_value = left_value.value - right_val.value;
-                return Ok(Rc::new(Box::new(Interger { value: new_value })));
-            }
-            "*" => {
-                let new_value = left_value.value * right_val.value;
-                return Ok(Rc::new(Box::new(Interger { value: new_value })));
-            }
-            "/" => {
-                let new_value = left_value.value / right_val.value;
-                return Ok(Rc::new(Box::new(Interger { value: new_value })));
-            }
-            "<" => {
-                let new_value = left_value.value < right_val.value;
-                return Ok(Rc::new(Box::new(BooleanObj { value: new_value })));
-            }
-            ">" => {
-                let new_value = left_value.value > right_val.value;
-                return Ok(Rc::new(Box::new(BooleanObj { value: new_value })));
+        (Value::Boolean(left_value), Value::Boolean(right_value)) => {
+            match compare(operator, left_value, right_value) {
+                Some(value) => return Ok(value),
+                None => {
+                    return Err(EvaluatorError::new(
+                        String::from("Binary Expression oprerator match failed for boolean"),
+                        None,
+                        None,
+                    ));
+                }
             }
-            "==" => {
-                let new_value = left_value.value == right_val.value;
-                return Ok(Rc::new(Box::new(BooleanObj { value: new_value })));
+        }
+        (Value::Str(left_value), Value::Str(right_value)) => {
+            if operator == "+" {
+                return Ok(Value::string(format!("{}{}", left_value, right_value)));
             }
-            "!=" => {
-                let new_value = left_value.value != right_val.value;
-                return Ok(Rc::new(Box::new(BooleanObj { value: new_value })));
+            match compare(operator, left_value.as_str(), right_value.as_str()) {
+                Some(value) => return Ok(value),
+                None => {
+                    return Err(EvaluatorError::new(
+                        format!("Operator {} is not supported for strings", operator),
+                        None,
+                        None,
+                    ));
+                }
             }
-            ">=" => {
-                let new_value = left_value.value >= right_val.value;
-                return Ok(Rc::new(Box::new(BooleanObj { value: new_value })));
+        }
+        _ => {}
+    }
+    if let (Some(left_value), Some(right_value)) = (as_float(&left), as_float(&right)) {
+        //One side is a float so the other one is promoted
+        return evaluate_float_expression(operator, left_value, right_value);
+    }
+    //Every pair of values can be compared for equality, values of different types are never equal
+    if operator == "==" || operator == "!=" {
+        let equal = values_equal(&left, &right);
+        return Ok(Value::Boolean(equal == (operator == "==")));
+    }
+    //Cases like 1 + true or 1 > true , true + 1 true > 1 are errored
+    return Err(EvaluatorError::new(
+        String::from("Invalid binary left and right operands"),
+        None,
+        None,
+    ));
+}
+
+//Equality of the values the operators above do not handle, heap values are equal only to themselves
+fn values_equal(left: &Value, right: &Value) -> bool {
+    match (left, right) {
+        (Value::Null, Value::Null) => return true,
+        (Value::Function(left), Value::Function(right)) => return Rc::ptr_eq(left, right),
+        (Value::Closure(left), Value::Closure(right)) => return Rc::ptr_eq(left, right),
+        (Value::Builtin(left), Value::Builtin(right)) => return std::ptr::eq(*left, *right),
+        (Value::BuiltinMethod(left), Value::BuiltinMethod(right)) => {
+            return Rc::ptr_eq(left, right)
+        }
+        (Value::Array(left), Value::Array(right)) => return Rc::ptr_eq(left, right),
+        (Value::Hash(left), Value::Hash(right)) => return Rc::ptr_eq(left, right),
+        (Value::Class(left), Value::Class(right)) => return Rc::ptr_eq(left, right),
+        (Value::Instance(left), Value::Instance(right)) => return Rc::ptr_eq(left, right),
+        //Every property access binds a new method, the same method of the same receiver is equal
+        (Value::BoundMethod(left), Value::BoundMethod(right)) => {
+            return values_equal(&left.receiver, &right.receiver)
+                && values_equal(&left.method, &right.method)
+        }
+        (Value::Error(left), Value::Error(right)) => return Rc::ptr_eq(left, right),
+        (Value::Module(left), Value::Module(right)) => return Rc::ptr_eq(left, right),
+        (Value::Generator(left), Value::Generator(right)) => return Rc::ptr_eq(left, right),
+        (Value::Promise(left), Value::Promise(right)) => return Rc::ptr_eq(left, right),
+        _ => return false,
+    }
+}
+
+//Numeric value of integers and floats as a float, None for everything else
+pub fn as_float(value: &Value) -> Option<f64> {
+    match value {
+        Value::Integer(int) => return Some(*int as f64),
+        Value::Float(float) => return Some(*float),
+        _ => return None,
+    }
+}
+
+fn integer_overflow(left: i64, operator: &str, right: i64) -> EvaluatorError {
+    return EvaluatorError::new(
+        format!("Integer overflow in {} {} {}", left, operator, right),
+        None,
+        None,
+    );
+}
+
+/*
+Integer arithmetic is checked so results that do not fit in 64 bits are
+errors instead of wrapping, division truncates towards zero
+*/
+fn evaluate_integer_expression(
+    operator: &str,
+    left: i64,
+    right: i64,
+) -> Result<Value, EvaluatorError> {
+    let value = match operator {
+        "+" => left.checked_add(right),
+        "-" => left.checked_sub(right),
+        "*" => left.checked_mul(right),
+        "/" => {
+            if right == 0 {
+                return Err(EvaluatorError::new(
+                    String::from("Division by zero"),
+                    None,
+                    None,
+                ));
             }
-            "<=" => {
-                let new_value = left_value.value <= right_val.value;
-                return Ok(Rc::new(Box::new(BooleanObj { value: new_value })));
+            left.checked_div(right)
+        }
+        _ => match compare(operator, &left, &right) {
+            Some(value) => return Ok(value),
+            None => {
+                return Err(EvaluatorError::new(
+                    String::from("Binary Expression oprerator match failed"),
+                    None,
+                    None,
+                ));
             }
-            _ => {
+        },
+    };
+    match value {
+        Some(value) => return Ok(Value::Integer(value)),
+        None => return Err(integer_overflow(left, operator, right)),
+    }
+}
+
+fn evaluate_float_expression(
+    operator: &str,
+    left: f64,
+    right: f64,
+) -> Result<Value, EvaluatorError> {
+    let value = match operator {
+        "+" => left + right,
+        "-" => left - right,
+        "*" => left * right,
+        "/" => left / right,
+        _ => match compare(operator, &left, &right) {
+            Some(value) => return Ok(value),
+            None => {
                 return Err(EvaluatorError::new(
                     String::from("Binary Expression oprerator match failed"),
                     None,
                     None,
                 ));
             }
+        },
+    };
+    return Ok(Value::Float(value));
+}
+
+pub fn evaluate_class_statement(
+    class_statement: &ClassStatement,
+    env: Rc<RefCell<Environment>>,
+) -> Result<Value, EvaluatorError> {
+    let mut superclass = None;
+    let mut method_env = env.clone();
+    if let Some(superclass_name) = class_statement.superclass.as_ref() {
+        let superclass_value = evaluate_identifier(superclass_name, env.clone())?;
+        superclass = Some(superclass_of(&superclass_value)?);
+        //Methods of a subclass close over an environment where super is the superclass
+        method_env = new_enclosed_environment(env.clone());
+        method_env.borrow_mut().set_slot(0, superclass_value);
+    }
+
+    let mut methods = HashMap::new();
+    for method in &class_statement.methods {
+        methods.insert(
+            method.name.value.clone(),
+            Value::Function(Rc::new(Function {
+                parameters: method.function.parameters.clone(),
+                body: method.function.body.clone(),
+                env: method_env.clone(),
+                is_generator: method.function.is_generator,
+                is_async: method.function.is_async,
+            })),
+        );
+    }
+    let class = Value::Class(Rc::new(Class {
+        name: class_statement.name.value.clone(),
+        superclass: superclass,
+        methods: methods,
+    }));
+    define_checked(&mut env.borrow_mut(), &class_statement.name, class.clone())?;
+    return Ok(class);
+}
+
+pub fn superclass_of(value: &Value) -> Result<Rc<Class>, EvaluatorError> {
+    match value {
+        Value::Class(class) => return Ok(class.clone()),
+        _ => {
+            return Err(EvaluatorError::new(
+                format!("Superclass must be a class, found {}", value.inspect()),
+                None,
+                None,
+            ));
         }
-    } else if left.get_type() == ObjectType::BOOLEAN && right.get_type() == ObjectType::BOOLEAN {
-        let right_val: &BooleanObj;
-        let left_value: &BooleanObj;
-        if let Some(bool) = right_value_any.downcast_ref::<BooleanObj>() {
-            right_val = bool;
-        } else {
+    }
+}
+
+pub fn evaluate_this_expression(
+    this: &ThisExpression,
+    env: Rc<RefCell<Environment>>,
+) -> Result<Value, EvaluatorError> {
+    match env
+        .borrow()
+        .get_binding(this.binding.get(), &this.token.literal)
+    {
+        Some(value) => return Ok(value),
+        None => {
             return Err(EvaluatorError::new(
-                String::from("Downcasting boolean failed"),
+                String::from("this is only available inside methods"),
                 None,
                 None,
             ));
         }
-        if let Some(bool) = left_value_any.downcast_ref::<BooleanObj>() {
-            left_value = bool;
-        } else {
+    }
+}
+
+pub fn evaluate_super_expression(
+    super_expression: &SuperExpression,
+    env: Rc<RefCell<Environment>>,
+) -> Result<Value, EvaluatorError> {
+    let superclass = env.borrow().get_binding(
+        super_expression.binding.get(),
+        &super_expression.token.literal,
+    );
+    let receiver = env
+        .borrow()
+        .get_binding(super_expression.this_binding.get(), "this");
+    let (superclass, receiver) = match (superclass, receiver) {
+        (Some(superclass), Some(receiver)) => (superclass, receiver),
+        _ => {
             return Err(EvaluatorError::new(
-                String::from("Downcasting boolean failed"),
+                String::from("super is only available inside methods of a subclass"),
                 None,
                 None,
             ));
         }
-        match operator.as_str() {
-            "<" => {
-                let new_value = left_value.value < right_val.value;
-                return Ok(Rc::new(Box::new(BooleanObj { value: new_value })));
-            }
-            ">" => {
-                let new_value = left_value.value > right_val.value;
-                return Ok(Rc::new(Box::new(BooleanObj { value: new_value })));
-            }
-            "==" => {
-                let new_value = left_value.value == right_val.value;
-                return Ok(Rc::new(Box::new(BooleanObj { value: new_value })));
-            }
-            "!=" => {
-                let new_value = left_value.value != right_val.value;
-                return Ok(Rc::new(Box::new(BooleanObj { value: new_value })));
-            }
-            ">=" => {
-                let new_value = left_value.value >= right_val.value;
-                return Ok(Rc::new(Box::new(BooleanObj { value: new_value })));
+    };
+    return bind_super_method(&superclass, receiver, &super_expression.method.value);
+}
+
+//Method of the superclass bound to the instance the current method was called on
+pub fn bind_super_method(
+    superclass: &Value,
+    receiver: Value,
+    name: &str,
+) -> Result<Value, EvaluatorError> {
+    let class = superclass_of(superclass)?;
+    if let Some(method) = class.find_method(name) {
+        return Ok(Value::BoundMethod(Rc::new(BoundMethod {
+            receiver: receiver,
+            method: method,
+        })));
+    }
+    return Err(EvaluatorError::new(
+        format!("Undefined method {} on superclass {}", name, class.name),
+        None,
+        None,
+    ));
+}
+
+pub fn evaluate_import_statement(
+    import: &ImportStatement,
+    env: Rc<RefCell<Environment>>,
+) -> Result<Value, EvaluatorError> {
+    let module = import_module(&import.path, &env, run_module_statements)?;
+    match import.alias.as_ref() {
+        Some(alias) => {
+            define_checked(&mut env.borrow_mut(), alias, module.clone())?;
+        }
+        None => bind_exports(&module, &env)?,
+    }
+    return Ok(module);
+}
+
+//Load the module at path relative to the module env belongs to, run runs its body once
+pub fn import_module(
+    path: &str,
+    env: &Rc<RefCell<Environment>>,
+    run: ModuleRunner,
+) -> Result<Value, EvaluatorError> {
+    let context = env.borrow().get_module();
+    let context = match context {
+        Some(context) => context,
+        None => {
+            return Err(EvaluatorError::new(
+                String::from("Imports are not supported without a module"),
+                None,
+                None,
+            ));
+        }
+    };
+    let event_loop = env.borrow().get_event_loop();
+    return load_module(&context, path, event_loop, run);
+}
+
+/*
+Without an alias every exported binding is brought into scope, reading it gives
+the current value in the module. A name the environment already has is an error,
+unless it comes from the same module imported before
+*/
+pub fn bind_exports(module: &Value, env: &Rc<RefCell<Environment>>) -> Result<(), EvaluatorError> {
+    if let Value::Module(module_value) = module {
+        let mut env = env.borrow_mut();
+        for name in &module_value.exports {
+            if let Some(imported) = env.get_import(name) {
+                if Rc::ptr_eq(&imported.env, &module_value.env) {
+                    continue;
+                }
             }
-            "<=" => {
-                let new_value = left_value.value <= right_val.value;
-                return Ok(Rc::new(Box::new(BooleanObj { value: new_value })));
+            if env.contains(name) {
+                return Err(duplicate_variable_error(name));
             }
+            env.import(name.clone(), module_value.clone());
+        }
+    }
+    return Ok(());
+}
+
+//Declare a let, class or import alias, at the top level it can not replace an imported name
+fn define_checked(
+    env: &mut Environment,
+    identifier: &Identifier,
+    value: Value,
+) -> Result<(), EvaluatorError> {
+    if identifier.binding.get() == Binding::Global && env.get_import(&identifier.value).is_some() {
+        return Err(duplicate_variable_error(&identifier.value));
+    }
+    env.define(identifier, value);
+    return Ok(());
+}
+
+//Checked at runtime for names the resolver can not see, like the ones an import brings in
+pub fn duplicate_variable_error(name: &str) -> EvaluatorError {
+    return EvaluatorError::new(
+        format!("Variable {} is already declared in this scope", name),
+        None,
+        None,
+    );
+}
+
+fn run_module_statements(
+    program: Program,
+    env: Rc<RefCell<Environment>>,
+) -> Result<(), EvaluatorError> {
+    for stmt in program.stmts {
+        evaluate_statement(&stmt, env.clone())?;
+    }
+    return Ok(());
+}
+
+pub fn evaluate_export_statement(
+    export: &ExportStatement,
+    env: Rc<RefCell<Environment>>,
+) -> Result<Value, EvaluatorError> {
+    if let Statement::Let(let_statement) = export.statement.as_ref() {
+        let value = evaluate_let_statement(let_statement, env.clone())?;
+        env.borrow_mut()
+            .export(let_statement.identifier.value.clone());
+        return Ok(value);
+    }
+    return Err(EvaluatorError::new(
+        String::from("Only let statements can be exported"),
+        None,
+        None,
+    ));
+}
+
+pub fn evaluate_get_expression(
+    get: &GetExpression,
+    env: Rc<RefCell<Environment>>,
+) -> Result<Value, EvaluatorError> {
+    let object = evaluate_expression(&get.object, env)?;
+    return get_property(object, &get.name.value);
+}
+
+pub fn get_property(object: Value, name: &str) -> Result<Value, EvaluatorError> {
+    if let Value::Instance(instance) = &object {
+        //Fields shadow methods with the same name
+        if let Some(value) = instance.fields.borrow().get(name) {
+            return Ok(value.clone());
+        }
+        if let Some(method) = instance.class.find_method(name) {
+            return Ok(Value::BoundMethod(Rc::new(BoundMethod {
+                receiver: object.clone(),
+                method: method,
+            })));
+        }
+        return Err(EvaluatorError::new(
+            format!("Undefined property {} on {}", name, object.inspect()),
+            None,
+            None,
+        ));
+    }
+    if let Value::Error(error) = &object {
+        match name {
+            "message" => return Ok(Value::string(error.message.clone())),
+            "kind" => return Ok(Value::string(error.kind.clone())),
+            "line" => match error.line {
+                Some(line) => return Ok(Value::Integer(line as i64)),
+                None => return Ok(NULL),
+            },
             _ => {
                 return Err(EvaluatorError::new(
-                    String::from("Binary Expression oprerator match failed for boolean"),
+                    format!("Undefined property {} on error", name),
                     None,
                     None,
                 ));
             }
         }
-    } else {
-        // Cases like 1 + true or 1 > true , true + 1 true > 1 are errored
+    }
+    if let Value::Generator(_) = &object {
+        if name == "next" {
+            return Ok(Value::BuiltinMethod(Rc::new(BuiltinMethod {
+                name: String::from("next"),
+                receiver: object.clone(),
+                function: generator_next,
+            })));
+        }
         return Err(EvaluatorError::new(
-            String::from("Invalid binary left and right operands"),
+            format!("Undefined property {} on generator", name),
             None,
             None,
         ));
     }
+    if let Value::Module(module) = &object {
+        match module.get(name) {
+            Some(value) => return Ok(value),
+            None => {
+                return Err(EvaluatorError::new(
+                    format!("Module {} has no export named {}", module.name, name),
+                    None,
+                    None,
+                ));
+            }
+        }
+    }
+    return Err(EvaluatorError::new(
+        format!("Property access not supported for {}", object.inspect()),
+        None,
+        None,
+    ));
+}
+
+pub fn evaluate_throw_statement(
+    throw: &ThrowStatement,
+    env: Rc<RefCell<Environment>>,
+) -> Result<Value, EvaluatorError> {
+    let value = evaluate_expression(&throw.value, env)?;
+    return Err(throw_value(value, throw.token.line));
 }
 
-pub fn is_truthy(condition: Rc<Box<dyn Object>>) -> bool {
-    let value_any = condition.as_any();
-    if let Some(value) = value_any.downcast_ref::<BooleanObj>() {
-        return value.value;
-    } else if let Some(value) = value_any.downcast_ref::<Interger>() {
-        if value.value == 0.0 {
-            return false;
+//Error raised by throwing a value on the given line
+pub fn throw_value(value: Value, line: usize) -> EvaluatorError {
+    //Rethrowing an error object keeps its kind and the line it was first raised on
+    if let Value::Error(error) = &value {
+        return EvaluatorError::new(
+            error.message.clone(),
+            Some(BoltErrorType::from_name(&error.kind)),
+            Some(error.line.unwrap_or(line)),
+        );
+    }
+    let message = match &value {
+        Value::Str(string) => string.as_ref().clone(),
+        _ => value.inspect(),
+    };
+    return EvaluatorError::new(
+        message,
+        Some(BoltErrorType::EXCEPTION(String::from("Error"))),
+        Some(line),
+    );
+}
+
+pub fn error_object(error: &EvaluatorError) -> ErrorObj {
+    return ErrorObj {
+        message: error.get_message(),
+        kind: error.get_type().name(),
+        line: error.get_line(),
+    };
+}
+
+pub fn evaluate_try_statement(
+    try_statement: &TryStatement,
+    env: Rc<RefCell<Environment>>,
+) -> Result<Value, EvaluatorError> {
+    let mut result = evaluate_block_statement_ref(&try_statement.body, env.clone());
+    if let (Err(error), Some(parameter), Some(catch_body)) = (
+        &result,
+        try_statement.catch_parameter.as_ref(),
+        try_statement.catch_body.as_ref(),
+    ) {
+        let catch_env = new_enclosed_environment(env.clone());
+        catch_env
+            .borrow_mut()
+            .define(parameter, Value::Error(Rc::new(error_object(error))));
+        result = evaluate_block_statement_ref(catch_body, catch_env);
+    }
+    if let Some(finally_body) = try_statement.finally_body.as_ref() {
+        //An error or a return, break or continue in finally replaces the pending result
+        let finally_result = evaluate_block_statement_ref(finally_body, env)?;
+        if is_control_flow(&finally_result) {
+            return Ok(finally_result);
         }
-        return true;
-    } else if let Some(_value) = value_any.downcast_ref::<Null>() {
-        return false;
     }
-    return false;
+    return result;
+}
+
+pub fn evaluate_match_expression(
+    match_expression: &MatchExpression,
+    env: Rc<RefCell<Environment>>,
+) -> Result<Value, EvaluatorError> {
+    let (body, arm_env) = select_match_arm(match_expression, env)?;
+    return evaluate_block_statement_ref(&body, arm_env);
+}
+
+//Find the first arm matching the value, its body runs in the returned environment
+pub fn select_match_arm(
+    match_expression: &MatchExpression,
+    env: Rc<RefCell<Environment>>,
+) -> Result<(Rc<Box<BlockStatement>>, Rc<RefCell<Environment>>), EvaluatorError> {
+    let value = evaluate_expression(&match_expression.value, env.clone())?;
+    for arm in &match_expression.arms {
+        let mut bindings = vec![];
+        if match_pattern(&arm.pattern, &value, &mut bindings, env.clone())? {
+            //Bindings are only visible inside the arm body
+            let arm_env = new_enclosed_environment(env.clone());
+            for (identifier, bound) in bindings {
+                arm_env.borrow_mut().define(identifier, bound);
+            }
+            return Ok((arm.body.clone(), arm_env));
+        }
+    }
+    return Err(EvaluatorError::new(
+        format!("No match arm for value {}", value.inspect()),
+        None,
+        None,
+    ));
+}
+
+fn match_pattern<'a>(
+    pattern: &'a Pattern,
+    value: &Value,
+    bindings: &mut Vec<(&'a Identifier, Value)>,
+    env: Rc<RefCell<Environment>>,
+) -> Result<bool, EvaluatorError> {
+    match pattern {
+        Pattern::Wildcard => return Ok(true),
+        Pattern::Binding(identifier) => {
+            bindings.push((identifier, value.clone()));
+            return Ok(true);
+        }
+        Pattern::Literal(literal) => {
+            let expected = evaluate_expression(literal, env)?;
+            return Ok(literal_matches(&expected, value));
+        }
+        Pattern::Alternative(patterns) => {
+            for alternative in patterns {
+                if match_pattern(alternative, value, bindings, env.clone())? {
+                    return Ok(true);
+                }
+            }
+            return Ok(false);
+        }
+        Pattern::Array(patterns) => {
+            let array = match value {
+                Value::Array(array) => array,
+                _ => return Ok(false),
+            };
+            let elements = array.elements.borrow().clone();
+            if elements.len() != patterns.len() {
+                return Ok(false);
+            }
+            for (element_pattern, element) in patterns.iter().zip(elements.iter()) {
+                if !match_pattern(element_pattern, element, bindings, env.clone())? {
+                    return Ok(false);
+                }
+            }
+            return Ok(true);
+        }
+        Pattern::Hash(entries) => {
+            let hash = match value {
+                Value::Hash(hash) => hash,
+                _ => return Ok(false),
+            };
+            //The hash may contain more keys than the pattern lists
+            for (key, entry_pattern) in entries {
+                let key = evaluate_expression(key, env.clone())?;
+                let entry = match hash.get(&get_hash_key(&key)?) {
+                    Some(entry) => entry,
+                    None => return Ok(false),
+                };
+                if !match_pattern(entry_pattern, &entry, bindings, env.clone())? {
+                    return Ok(false);
+                }
+            }
+            return Ok(true);
+        }
+    }
+}
+
+//Literal patterns compare like hash keys, so 1 matches 1.0 but never "1"
+pub fn literal_matches(expected: &Value, value: &Value) -> bool {
+    if let Value::Null = expected {
+        return matches!(value, Value::Null);
+    }
+    return expected.hash_key().is_some() && expected.hash_key() == value.hash_key();
+}
+
+pub fn evaluate_logical_expression(
+    logical: &LogicalExpression,
+    env: Rc<RefCell<Environment>>,
+) -> Result<Value, EvaluatorError> {
+    let left = evaluate_expression(&logical.left, env.clone())?;
+    //The right operand is only evaluated when the left one does not decide the result
+    match logical.operator.as_str() {
+        "&&" => {
+            if !is_truthy(&left) {
+                return Ok(left);
+            }
+        }
+        "||" => {
+            if is_truthy(&left) {
+                return Ok(left);
+            }
+        }
+        _ => {
+            return Err(EvaluatorError::new(
+                format!("Unknown logical operator {}", logical.operator),
+                None,
+                None,
+            ));
+        }
+    }
+    return evaluate_expression(&logical.right, env);
+}
+
+//Each embedded value is converted with the same rules as inspect
+pub fn evaluate_interpolated_string(
+    interpolated: &InterpolatedString,
+    env: Rc<RefCell<Environment>>,
+) -> Result<Value, EvaluatorError> {
+    let mut value = String::new();
+    for part in &interpolated.parts {
+        match part {
+            StringPart::Literal(literal) => value.push_str(literal),
+            StringPart::Expression(expression) => {
+                value.push_str(&evaluate_expression(expression, env.clone())?.inspect());
+            }
+        }
+    }
+    return Ok(Value::string(value));
+}
+
+pub fn is_truthy(condition: &Value) -> bool {
+    match condition {
+        Value::Boolean(value) => return *value,
+        Value::Integer(value) => return *value != 0,
+        Value::Float(value) => return *value != 0.0,
+        Value::Str(value) => return !value.is_empty(),
+        Value::Array(value) => return !value.elements.borrow().is_empty(),
+        Value::Hash(value) => return !value.is_empty(),
+        Value::Null => return false,
+        Value::Function(_)
+        | Value::Closure(_)
+        | Value::Builtin(_)
+        | Value::BuiltinMethod(_)
+        | Value::BoundMethod(_)
+        | Value::Module(_)
+        | Value::Instance(_)
+        | Value::Class(_)
+        | Value::Error(_)
+        | Value::Generator(_)
+        | Value::Promise(_) => return true,
+        Value::Return(value) => return is_truthy(value),
+        Value::Break | Value::Continue | Value::Uninitialized => return true,
+    }
+}
+
+pub fn is_control_flow(value: &Value) -> bool {
+    return matches!(value, Value::Return(_) | Value::Break | Value::Continue);
 }
 
 pub fn evaluate_block_statements(
-    statements: &Vec<Box<dyn Statement>>,
+    statements: &Vec<Statement>,
     environment: Rc<RefCell<Environment>>,
-) -> Result<Rc<Box<dyn Object>>, EvaluatorError> {
-    let mut result: Option<Result<Rc<Box<dyn Object>>, EvaluatorError>> = None;
+) -> Result<Value, EvaluatorError> {
+    //An empty block evaluates to null
+    let mut result: Value = NULL;
     for statement in statements {
-        result = Some(evaluate_statement(statement, environment.clone()));
-        if let Some(res) = &result {
-            let value_any = res.as_ref().unwrap().as_any();
-            if let Some(_v) = value_any.downcast_ref::<Return>() {
-                return result.unwrap();
-            }
+        result = evaluate_statement(statement, environment.clone())?;
+        //Return, break and continue stop the block and unwind to their handler
+        if is_control_flow(&result) {
+            return Ok(result);
         }
     }
-    if let Some(res) = result {
-        return res;
-    } else {
-        return Err(EvaluatorError::new(
-            String::from("Error evaluating block statement"),
-            None,
-            None,
-        ));
-    }
+    return Ok(result);
 }
 
 pub fn evaluate_block_statement(
     block_statement: &Box<BlockStatement>,
     environment: Rc<RefCell<Environment>>,
-) -> Result<Rc<Box<dyn Object>>, EvaluatorError> {
+) -> Result<Value, EvaluatorError> {
     let statements = &block_statement.statements;
     return evaluate_block_statements(statements, environment);
 }
@@ -254,7 +812,7 @@ pub fn evaluate_block_statement(
 pub fn evaluate_block_statement_ref(
     block_statement: &BlockStatement,
     environment: Rc<RefCell<Environment>>,
-) -> Result<Rc<Box<dyn Object>>, EvaluatorError> {
+) -> Result<Value, EvaluatorError> {
     let statements = &block_statement.statements;
     return evaluate_block_statements(statements, environment);
 }
@@ -262,9 +820,9 @@ pub fn evaluate_block_statement_ref(
 pub fn evaluate_condition_expression(
     if_expression: &IfExpression,
     environment: Rc<RefCell<Environment>>,
-) -> Result<Rc<Box<dyn Object>>, EvaluatorError> {
+) -> Result<Value, EvaluatorError> {
     let condition_eval = evaluate_expression(&if_expression.condition, environment.clone())?;
-    let truthy = is_truthy(condition_eval);
+    let truthy = is_truthy(&condition_eval);
     if truthy {
         let consequence = &if_expression.consequence;
         return evaluate_block_statement(consequence, environment.clone());
@@ -274,19 +832,39 @@ pub fn evaluate_condition_expression(
                 return evaluate_block_statement(alternate, environment.clone());
             }
             None => {
-                return Ok(Rc::new(Box::new(NULL)));
+                return Ok(NULL);
             }
         }
     }
 }
 
+pub fn evaluate_while_statement(
+    while_statement: &WhileStatement,
+    environment: Rc<RefCell<Environment>>,
+) -> Result<Value, EvaluatorError> {
+    loop {
+        let condition_eval = evaluate_expression(&while_statement.condition, environment.clone())?;
+        if !is_truthy(&condition_eval) {
+            break;
+        }
+        let evaluated = evaluate_block_statement(&while_statement.body, environment.clone())?;
+        match evaluated {
+            Value::Return(_) => return Ok(evaluated),
+            Value::Break => break,
+            _ => {}
+        }
+        //Continue needs no handling as the body already stopped at it
+    }
+    return Ok(NULL);
+}
+
 pub fn evaluate_return_statement(
     return_statement: &ReturnStatement,
     environment: Rc<RefCell<Environment>>,
-) -> Result<Rc<Box<dyn Object>>, EvaluatorError> {
+) -> Result<Value, EvaluatorError> {
     match evaluate_expression(&return_statement.value, environment) {
         Ok(value) => {
-            return Ok(Rc::new(Box::new(Return { value: value })));
+            return Ok(Value::Return(Box::new(value)));
         }
         Err(e) => return Err(e),
     }
@@ -295,23 +873,12 @@ pub fn evaluate_return_statement(
 pub fn evaluate_let_statement(
     let_statement: &LetStatement,
     environment: Rc<RefCell<Environment>>,
-) -> Result<Rc<Box<dyn Object>>, EvaluatorError> {
+) -> Result<Value, EvaluatorError> {
     match evaluate_expression(&let_statement.value, environment.clone()) {
         Ok(value) => match environment.try_borrow_mut() {
             Ok(mut mutable_ref) => {
-                let ident = let_statement.identifier.value.clone();
-                match mutable_ref.set(ident.clone(), value.clone()) {
-                    Some(_v) => {
-                        return Ok(value.clone());
-                    }
-                    None => {
-                        return Err(EvaluatorError::new(
-                            String::from("Error Setting environment value"),
-                            None,
-                            None,
-                        ));
-                    }
-                }
+                define_checked(&mut mutable_ref, &let_statement.identifier, value.clone())?;
+                return Ok(value);
             }
             Err(e) => {
                 return Err(EvaluatorError::new(e.to_string(), None, None));
@@ -324,18 +891,19 @@ pub fn evaluate_let_statement(
 pub fn evaluate_identifier(
     identifier: &Identifier,
     environment: Rc<RefCell<Environment>>,
-) -> Result<Rc<Box<dyn Object>>, EvaluatorError> {
-    let ident = identifier.value.clone();
+) -> Result<Value, EvaluatorError> {
+    let ident = &identifier.value;
     match environment.try_borrow() {
         Ok(borrow_ref) => {
-            let optional_value = borrow_ref.get(ident.clone());
+            let optional_value = borrow_ref.get_binding(identifier.binding.get(), ident);
             match optional_value {
                 Some(value) => {
                     return Ok(value);
                 }
                 None => {
-                    //Todo if not found in env should be assign NULL or Panic?
-                    // return Ok(Rc::new(Box::new(NULL)));
+                    if let Some(builtin) = get_builtin(ident) {
+                        return Ok(builtin);
+                    }
                     return Err(EvaluatorError::new(
                         String::from(format!(
                             "Error getting environment variable {}",
@@ -355,47 +923,154 @@ pub fn evaluate_identifier(
 
 pub fn extend_funtion_env(
     function: &Function,
-    args: HashMap<String, Rc<Box<dyn Object>>>,
+    args: Vec<Value>,
+    this: Option<Value>,
 ) -> Rc<RefCell<Environment>> {
     let env = new_enclosed_environment(function.env.clone());
-
-    for param in args {
-        env.borrow_mut().set(param.0, param.1.clone());
+    for (param, arg) in function.parameters.iter().zip(args) {
+        env.borrow_mut().define(param, arg);
+    }
+    //Methods see the instance they were called on as this, in the slot after the parameters
+    if let Some(receiver) = this {
+        env.borrow_mut()
+            .set_slot(function.parameters.len(), receiver);
     }
 
     return env;
 }
 
 pub fn apply_function(
-    function: Rc<Box<dyn Object>>,
-    args: HashMap<String, Rc<Box<dyn Object>>>,
-    _env: Rc<RefCell<Environment>>,
-) -> Result<Rc<Box<dyn Object>>, EvaluatorError> {
-    let value_any = function.as_any();
-    if let Some(function_value) = value_any.downcast_ref::<Function>() {
-        let extended_env = extend_funtion_env(function_value, args);
-        let evaluated =
-            evaluate_block_statement_ref(function_value.body.as_ref(), extended_env.clone());
-        return evaluated;
-    } else {
-        return Err(EvaluatorError::new(
-            "Error in evaluating function".to_string(),
-            None,
-            None,
-        ));
+    function: Value,
+    args: Vec<Value>,
+    env: Rc<RefCell<Environment>>,
+) -> Result<Value, EvaluatorError> {
+    match &function {
+        Value::Function(function_value) => return call_function(function_value, args, None),
+        Value::BoundMethod(bound) => {
+            let method = method_function(&bound.method)?;
+            return call_function(method, args, Some(bound.receiver.clone()));
+        }
+        Value::Class(class) => {
+            let instance = alloc_instance(class.clone());
+            if let Some(init) = class.find_method("init") {
+                call_function(method_function(&init)?, args, Some(instance.clone()))?;
+            }
+            return Ok(instance);
+        }
+        Value::Builtin(builtin) => return (builtin.function)(args, env),
+        Value::BuiltinMethod(method) => return (method.function)(method.receiver.clone(), args),
+        _ => {
+            return Err(EvaluatorError::new(
+                "Error in evaluating function".to_string(),
+                None,
+                None,
+            ));
+        }
+    }
+}
+
+thread_local! {
+    //Function bodies and generators the tree walking evaluator is running, each one nests native calls
+    static CALL_DEPTH: Cell<usize> = const { Cell::new(0) };
+}
+
+pub fn call_depth_error() -> EvaluatorError {
+    return EvaluatorError::new(String::from("Maximum call depth exceeded"), None, None);
+}
+
+//Run a function body or resume a generator one level deeper, past MAX_CALL_DEPTH it is an error
+pub fn nested_call<T>(
+    call: impl FnOnce() -> Result<T, EvaluatorError>,
+) -> Result<T, EvaluatorError> {
+    let depth = CALL_DEPTH.with(|calls| calls.get());
+    if depth >= MAX_CALL_DEPTH {
+        return Err(call_depth_error());
+    }
+    CALL_DEPTH.with(|calls| calls.set(depth + 1));
+    let result = call();
+    CALL_DEPTH.with(|calls| calls.set(depth));
+    return result;
+}
+
+//Classes declared by the tree walking evaluator only have functions as methods
+pub fn method_function(method: &Value) -> Result<&Function, EvaluatorError> {
+    match method {
+        Value::Function(function) => return Ok(function),
+        _ => {
+            return Err(EvaluatorError::new(
+                "Error Downcasting function".to_string(),
+                None,
+                None,
+            ));
+        }
     }
 }
 
+fn call_function(
+    function: &Function,
+    args: Vec<Value>,
+    this: Option<Value>,
+) -> Result<Value, EvaluatorError> {
+    let extended_env = extend_funtion_env(function, args, this);
+    //The body of a generator only starts running on the first call to next
+    if function.is_generator {
+        return Ok(Value::Generator(Rc::new(Generator {
+            execution: RefCell::new(Box::new(Execution::new(
+                function.body.clone(),
+                extended_env,
+            ))),
+        })));
+    }
+    //The body of an async function runs on the event loop, the caller gets its promise
+    if function.is_async {
+        let event_loop = match extended_env.borrow().get_event_loop() {
+            Some(event_loop) => event_loop,
+            None => {
+                return Err(EvaluatorError::new(
+                    String::from("Async functions need an event loop to run"),
+                    None,
+                    None,
+                ));
+            }
+        };
+        return Ok(event_loop.spawn(Box::new(Execution::new_async(
+            function.body.clone(),
+            extended_env,
+        ))));
+    }
+    let evaluated =
+        nested_call(|| evaluate_block_statement_ref(function.body.as_ref(), extended_env.clone()))?;
+    //Unwrap the return value so it does not unwind past the call site
+    if let Value::Return(return_value) = evaluated {
+        return Ok(*return_value);
+    }
+    return Ok(evaluated);
+}
+
 pub fn eval_arg_expression(
-    args: Rc<Vec<Box<dyn Expression>>>,
-    function: Rc<Box<dyn Object>>,
+    args: Rc<Vec<Box<Expression>>>,
+    function: Value,
     env: Rc<RefCell<Environment>>,
-) -> Result<HashMap<String, Rc<Box<dyn Object>>>, EvaluatorError> {
-    let mut result: HashMap<String, Rc<Box<dyn Object>>> = HashMap::new();
-    let value_any = function.as_any();
-    if let Some(function_object) = value_any.downcast_ref::<Function>() {
-        let params = function_object.parameters.clone();
-        let length = params.len();
+) -> Result<Vec<Value>, EvaluatorError> {
+    let mut result: Vec<Value> = vec![];
+    //Builtins validate their own arguments
+    let expected_length = match &function {
+        Value::Function(function_object) => Some(function_object.parameters.len()),
+        Value::BoundMethod(bound) => Some(method_function(&bound.method)?.parameters.len()),
+        Value::Class(class) => match class.find_method("init") {
+            Some(init) => Some(method_function(&init)?.parameters.len()),
+            None => Some(0),
+        },
+        Value::Builtin(_) | Value::BuiltinMethod(_) => None,
+        _ => {
+            return Err(EvaluatorError::new(
+                "Error Downcasting function".to_string(),
+                None,
+                None,
+            ));
+        }
+    };
+    if let Some(length) = expected_length {
         if length != args.len() {
             return Err(EvaluatorError::new(
                 "No of args in function mismatch".to_string(),
@@ -403,18 +1078,197 @@ pub fn eval_arg_expression(
                 None,
             ));
         }
-        for i in 0..length {
-            let param = params[i].value.clone();
-            let evaluated = evaluate_expression(&args[i], env.clone())?;
-            result.insert(param, evaluated);
+    }
+    for arg in args.iter() {
+        let evaluated = evaluate_expression(arg, env.clone())?;
+        result.push(evaluated);
+    }
+
+    return Ok(result);
+}
+
+pub fn evaluate_array_literal(
+    array_literal: &ArrayLiteral,
+    env: Rc<RefCell<Environment>>,
+) -> Result<Value, EvaluatorError> {
+    let mut elements: Vec<Value> = vec![];
+    for element in &array_literal.elements {
+        let evaluated = evaluate_expression(element, env.clone())?;
+        elements.push(evaluated);
+    }
+    return Ok(alloc_array(elements));
+}
+
+pub fn evaluate_hash_literal(
+    hash_literal: &HashLiteral,
+    env: Rc<RefCell<Environment>>,
+) -> Result<Value, EvaluatorError> {
+    let hash = Hash::new();
+    for (key_expression, value_expression) in &hash_literal.pairs {
+        let key = evaluate_expression(key_expression, env.clone())?;
+        let hash_key = get_hash_key(&key)?;
+        let value = evaluate_expression(value_expression, env.clone())?;
+        hash.set(hash_key, HashPair { key, value });
+    }
+    return Ok(alloc_hash(hash));
+}
+
+pub fn get_hash_key(key: &Value) -> Result<HashKey, EvaluatorError> {
+    match key.hash_key() {
+        Some(hash_key) => return Ok(hash_key),
+        None => {
+            return Err(EvaluatorError::new(
+                format!("Unusable as hash key: {}", key.inspect()),
+                None,
+                None,
+            ));
+        }
+    }
+}
+
+/*
+Validate the index against the array bounds, only non negative integers
+smaller than the length of the array are allowed
+*/
+pub fn array_position(array: &Array, index: &Value) -> Result<usize, EvaluatorError> {
+    let length = array.elements.borrow().len();
+    if let Value::Integer(int) = index {
+        if *int < 0 || *int as u64 >= length as u64 {
+            return Err(EvaluatorError::new(
+                format!("Index {} out of range for array of length {}", int, length),
+                None,
+                None,
+            ));
         }
+        return Ok(*int as usize);
+    }
+    return Err(EvaluatorError::new(
+        format!("Array index must be an integer, found {}", index.inspect()),
+        None,
+        None,
+    ));
+}
 
-        return Ok(result);
-    } else {
-        return Err(EvaluatorError::new(
-            "Error Downcasting function".to_string(),
-            None,
-            None,
-        ));
+pub fn evaluate_index_expression(left: Value, index: Value) -> Result<Value, EvaluatorError> {
+    match &left {
+        Value::Array(array) => {
+            let position = array_position(array, &index)?;
+            return Ok(array.elements.borrow()[position].clone());
+        }
+        Value::Hash(hash) => {
+            let hash_key = get_hash_key(&index)?;
+            match hash.get(&hash_key) {
+                Some(value) => return Ok(value),
+                None => return Ok(NULL),
+            }
+        }
+        _ => {}
+    }
+    return Err(EvaluatorError::new(
+        format!("Index operator not supported for {}", left.inspect()),
+        None,
+        None,
+    ));
+}
+
+pub fn evaluate_assign_expression(
+    assign: &AssignExpression,
+    env: Rc<RefCell<Environment>>,
+) -> Result<Value, EvaluatorError> {
+    return assign_to_target(
+        &assign.target,
+        || evaluate_expression(&assign.value, env.clone()),
+        env.clone(),
+    );
+}
+
+/*
+Store a value in an identifier, index or property target. The value is produced
+after the parts of the target are evaluated, which keeps left to right order
+*/
+pub fn assign_to_target<F>(
+    target: &Rc<Expression>,
+    value: F,
+    env: Rc<RefCell<Environment>>,
+) -> Result<Value, EvaluatorError>
+where
+    F: FnOnce() -> Result<Value, EvaluatorError>,
+{
+    if let Expression::Identifier(identifier) = target.as_ref() {
+        let value = value()?;
+        let ident = identifier.value.clone();
+        match env.try_borrow_mut() {
+            Ok(mut mutable_ref) => {
+                match mutable_ref.assign_binding(identifier.binding.get(), &ident, value.clone()) {
+                    Some(_v) => return Ok(value),
+                    None => {
+                        return Err(EvaluatorError::new(
+                            format!("Cannot assign to undeclared variable {}", ident),
+                            None,
+                            None,
+                        ));
+                    }
+                }
+            }
+            Err(e) => {
+                return Err(EvaluatorError::new(e.to_string(), None, None));
+            }
+        }
+    } else if let Expression::Index(index_expression) = target.as_ref() {
+        let left = evaluate_expression(&index_expression.left, env.clone())?;
+        let index = evaluate_expression(&index_expression.index, env.clone())?;
+        let value = value()?;
+        return set_index(left, index, value);
+    } else if let Expression::Get(get) = target.as_ref() {
+        let object = evaluate_expression(&get.object, env.clone())?;
+        let value = value()?;
+        return set_property(object, &get.name.value, value);
+    }
+    return Err(EvaluatorError::new(
+        String::from("Invalid assignment target"),
+        None,
+        None,
+    ));
+}
+
+pub fn set_index(left: Value, index: Value, value: Value) -> Result<Value, EvaluatorError> {
+    match &left {
+        Value::Array(array) => {
+            let position = array_position(array, &index)?;
+            array.elements.borrow_mut()[position] = value.clone();
+            return Ok(value);
+        }
+        Value::Hash(hash) => {
+            let hash_key = get_hash_key(&index)?;
+            hash.set(
+                hash_key,
+                HashPair {
+                    key: index,
+                    value: value.clone(),
+                },
+            );
+            return Ok(value);
+        }
+        _ => {}
+    }
+    return Err(EvaluatorError::new(
+        format!("Index assignment not supported for {}", left.inspect()),
+        None,
+        None,
+    ));
+}
+
+pub fn set_property(object: Value, name: &str, value: Value) -> Result<Value, EvaluatorError> {
+    if let Value::Instance(instance) = &object {
+        instance
+            .fields
+            .borrow_mut()
+            .insert(String::from(name), value.clone());
+        return Ok(value);
     }
+    return Err(EvaluatorError::new(
+        format!("Only instances have fields, found {}", object.inspect()),
+        None,
+        None,
+    ));
 }
@@ -0,0 +1,379 @@
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    rc::{Rc, Weak},
+};
+
+use crate::{
+    evaluator::environment::Environment,
+    object::object::{Array, Class, Hash, Instance, Value},
+};
+
+//Fewest allocations between two automatic collections
+const MIN_THRESHOLD: usize = 10000;
+
+//Allocations that can close a cycle, the collector only has to look at objects that hold mutable values
+enum Tracked {
+    Environment(Weak<RefCell<Environment>>),
+    Array(Weak<Array>),
+    Hash(Weak<Hash>),
+    Instance(Weak<Instance>),
+    Cell(Weak<RefCell<Value>>), //Variable captured by closures of the virtual machine
+}
+
+impl Tracked {
+    fn is_alive(&self) -> bool {
+        match self {
+            Tracked::Environment(env) => return env.strong_count() > 0,
+            Tracked::Array(array) => return array.strong_count() > 0,
+            Tracked::Hash(hash) => return hash.strong_count() > 0,
+            Tracked::Instance(instance) => return instance.strong_count() > 0,
+            Tracked::Cell(cell) => return cell.strong_count() > 0,
+        }
+    }
+
+    fn upgrade(&self) -> Option<Object> {
+        match self {
+            Tracked::Environment(env) => return env.upgrade().map(Object::Environment),
+            Tracked::Array(array) => {
+                return array.upgrade().map(|a| Object::Value(Value::Array(a)))
+            }
+            Tracked::Hash(hash) => return hash.upgrade().map(|h| Object::Value(Value::Hash(h))),
+            Tracked::Instance(instance) => {
+                return instance
+                    .upgrade()
+                    .map(|i| Object::Value(Value::Instance(i)))
+            }
+            Tracked::Cell(cell) => return cell.upgrade().map(Object::Cell),
+        }
+    }
+}
+
+//Allocation that can reference other allocations, keeps it alive while the collector looks at it
+enum Object {
+    Environment(Rc<RefCell<Environment>>),
+    Cell(Rc<RefCell<Value>>),
+    Value(Value),
+}
+
+impl Object {
+    //Values that can not reference anything are left out of the graph
+    fn of(value: &Value) -> Option<Object> {
+        match value {
+            Value::Function(_)
+            | Value::Closure(_)
+            | Value::BuiltinMethod(_)
+            | Value::Array(_)
+            | Value::Hash(_)
+            | Value::Class(_)
+            | Value::Instance(_)
+            | Value::BoundMethod(_)
+            | Value::Module(_) => return Some(Object::Value(value.clone())),
+            Value::Return(value) => return Object::of(value),
+            _ => return None,
+        }
+    }
+
+    fn id(&self) -> usize {
+        match self {
+            Object::Environment(env) => return Rc::as_ptr(env) as *const () as usize,
+            Object::Cell(cell) => return Rc::as_ptr(cell) as *const () as usize,
+            Object::Value(value) => match value {
+                Value::Function(function) => return Rc::as_ptr(function) as *const () as usize,
+                Value::Closure(closure) => return Rc::as_ptr(closure) as *const () as usize,
+                Value::BuiltinMethod(method) => return Rc::as_ptr(method) as *const () as usize,
+                Value::Array(array) => return Rc::as_ptr(array) as *const () as usize,
+                Value::Hash(hash) => return Rc::as_ptr(hash) as *const () as usize,
+                Value::Class(class) => return Rc::as_ptr(class) as *const () as usize,
+                Value::Instance(instance) => return Rc::as_ptr(instance) as *const () as usize,
+                Value::BoundMethod(bound) => return Rc::as_ptr(bound) as *const () as usize,
+                Value::Module(module) => return Rc::as_ptr(module) as *const () as usize,
+                _ => unreachable!("Value without references in the object graph"),
+            },
+        }
+    }
+
+    fn strong_count(&self) -> usize {
+        match self {
+            Object::Environment(env) => return Rc::strong_count(env),
+            Object::Cell(cell) => return Rc::strong_count(cell),
+            Object::Value(value) => match value {
+                Value::Function(function) => return Rc::strong_count(function),
+                Value::Closure(closure) => return Rc::strong_count(closure),
+                Value::BuiltinMethod(method) => return Rc::strong_count(method),
+                Value::Array(array) => return Rc::strong_count(array),
+                Value::Hash(hash) => return Rc::strong_count(hash),
+                Value::Class(class) => return Rc::strong_count(class),
+                Value::Instance(instance) => return Rc::strong_count(instance),
+                Value::BoundMethod(bound) => return Rc::strong_count(bound),
+                Value::Module(module) => return Rc::strong_count(module),
+                _ => unreachable!("Value without references in the object graph"),
+            },
+        }
+    }
+
+    fn is_tracked(&self) -> bool {
+        match self {
+            Object::Environment(_) | Object::Cell(_) => return true,
+            Object::Value(value) => {
+                return matches!(value, Value::Array(_) | Value::Hash(_) | Value::Instance(_))
+            }
+        }
+    }
+
+    /*
+    Objects this one references, once for every reference. None when it is
+    borrowed by the running program, it is then treated as used from outside
+    */
+    fn references(&self) -> Option<Vec<Object>> {
+        let mut values: Vec<Value> = vec![];
+        let mut objects: Vec<Object> = vec![];
+        match self {
+            Object::Environment(env) => {
+                let env = env.try_borrow().ok()?;
+                let (env_values, outer) = env.references();
+                values = env_values;
+                if let Some(outer) = outer {
+                    objects.push(Object::Environment(outer));
+                }
+            }
+            Object::Cell(cell) => values.push(cell.try_borrow().ok()?.clone()),
+            Object::Value(value) => match value {
+                Value::Function(function) => {
+                    objects.push(Object::Environment(function.env.clone()));
+                }
+                Value::Closure(closure) => {
+                    for upvalue in &closure.upvalues {
+                        objects.push(Object::Cell(upvalue.clone()));
+                    }
+                    objects.push(Object::Environment(closure.globals.clone()));
+                }
+                Value::BuiltinMethod(method) => values.push(method.receiver.clone()),
+                Value::Array(array) => values = array.elements.try_borrow().ok()?.clone(),
+                Value::Hash(hash) => values = hash.try_values()?,
+                Value::Class(class) => {
+                    if let Some(superclass) = class.superclass.as_ref() {
+                        values.push(Value::Class(superclass.clone()));
+                    }
+                    values.extend(class.methods.values().cloned());
+                }
+                Value::Instance(instance) => {
+                    values.push(Value::Class(instance.class.clone()));
+                    values.extend(instance.fields.try_borrow().ok()?.values().cloned());
+                }
+                Value::BoundMethod(bound) => {
+                    values.push(bound.receiver.clone());
+                    values.push(bound.method.clone());
+                }
+                Value::Module(module) => objects.push(Object::Environment(module.env.clone())),
+                _ => {}
+            },
+        }
+        objects.extend(values.iter().filter_map(Object::of));
+        return Some(objects);
+    }
+
+    //Drop everything a garbage object holds, which breaks the cycles it is part of
+    fn clear(&self) {
+        match self {
+            Object::Environment(env) => {
+                if let Ok(mut env) = env.try_borrow_mut() {
+                    env.clear();
+                }
+            }
+            Object::Cell(cell) => {
+                if let Ok(mut cell) = cell.try_borrow_mut() {
+                    *cell = Value::Null;
+                }
+            }
+            Object::Value(value) => match value {
+                Value::Array(array) => {
+                    if let Ok(mut elements) = array.elements.try_borrow_mut() {
+                        elements.clear();
+                    }
+                }
+                Value::Hash(hash) => hash.clear(),
+                Value::Instance(instance) => {
+                    if let Ok(mut fields) = instance.fields.try_borrow_mut() {
+                        fields.clear();
+                    }
+                }
+                _ => {}
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HeapStats {
+    pub allocated: usize,   //Tracked objects allocated so far
+    pub live: usize,        //Tracked objects that are still alive
+    pub collected: usize,   //Tracked objects freed by the collector
+    pub collections: usize, //Times the collector ran
+}
+
+struct Heap {
+    tracked: Vec<Tracked>,
+    allocated: usize,
+    collected: usize,
+    collections: usize,
+    since_collection: usize,
+    threshold: usize,
+}
+
+thread_local! {
+    static HEAP: RefCell<Heap> = const { RefCell::new(Heap {
+        tracked: vec![],
+        allocated: 0,
+        collected: 0,
+        collections: 0,
+        since_collection: 0,
+        threshold: MIN_THRESHOLD,
+    }) };
+}
+
+fn track(object: Tracked) {
+    let collect_now = HEAP.with(|heap| {
+        let mut heap = heap.borrow_mut();
+        heap.tracked.push(object);
+        heap.allocated += 1;
+        heap.since_collection += 1;
+        return heap.since_collection >= heap.threshold;
+    });
+    if collect_now {
+        collect();
+    }
+}
+
+pub fn track_environment(env: &Rc<RefCell<Environment>>) {
+    track(Tracked::Environment(Rc::downgrade(env)));
+}
+
+pub fn alloc_array(elements: Vec<Value>) -> Value {
+    let array = Rc::new(Array::new(elements));
+    track(Tracked::Array(Rc::downgrade(&array)));
+    return Value::Array(array);
+}
+
+pub fn alloc_hash(hash: Hash) -> Value {
+    let hash = Rc::new(hash);
+    track(Tracked::Hash(Rc::downgrade(&hash)));
+    return Value::Hash(hash);
+}
+
+pub fn alloc_instance(class: Rc<Class>) -> Value {
+    let instance = Rc::new(Instance {
+        class: class,
+        fields: RefCell::new(HashMap::new()),
+    });
+    track(Tracked::Instance(Rc::downgrade(&instance)));
+    return Value::Instance(instance);
+}
+
+pub fn alloc_cell(value: Value) -> Rc<RefCell<Value>> {
+    let cell = Rc::new(RefCell::new(value));
+    track(Tracked::Cell(Rc::downgrade(&cell)));
+    return cell;
+}
+
+pub fn stats() -> HeapStats {
+    return HEAP.with(|heap| {
+        let heap = heap.borrow();
+        return HeapStats {
+            allocated: heap.allocated,
+            live: heap
+                .tracked
+                .iter()
+                .filter(|object| object.is_alive())
+                .count(),
+            collected: heap.collected,
+            collections: heap.collections,
+        };
+    });
+}
+
+/*
+Free the cycles nothing can reach anymore and return how many tracked objects
+were freed. Values are reference counted so everything else is freed as soon
+as it is dropped, the collector only has to find groups of objects that keep
+each other alive. Starting from the tracked objects it counts the references
+each object gets from inside the graph, an object with more strong references
+than that is used from outside, by the running program, the REPL or a value
+the collector does not look into like a paused generator. Everything reachable
+from those is kept and the rest is cleared, which drops the references that
+formed the cycles
+*/
+pub fn collect() -> usize {
+    let tracked = HEAP.with(|heap| std::mem::take(&mut heap.borrow_mut().tracked));
+    let mut alive: Vec<Tracked> = vec![];
+    let mut pending: Vec<Object> = vec![];
+    for object in tracked {
+        if let Some(upgraded) = object.upgrade() {
+            pending.push(upgraded);
+            alive.push(object);
+        }
+    }
+
+    let mut objects: HashMap<usize, Object> = HashMap::new();
+    while let Some(object) = pending.pop() {
+        let id = object.id();
+        if objects.contains_key(&id) {
+            continue;
+        }
+        if let Some(references) = object.references() {
+            pending.extend(references);
+        }
+        objects.insert(id, object);
+    }
+
+    let mut internal: HashMap<usize, usize> = HashMap::new();
+    let mut roots: Vec<usize> = vec![];
+    for (id, object) in &objects {
+        match object.references() {
+            Some(references) => {
+                for reference in references {
+                    *internal.entry(reference.id()).or_insert(0) += 1;
+                }
+            }
+            None => roots.push(*id),
+        }
+    }
+    //Every object is held once by the collector itself
+    for (id, object) in &objects {
+        if object.strong_count() - 1 > internal.get(id).copied().unwrap_or(0) {
+            roots.push(*id);
+        }
+    }
+
+    let mut reachable: HashSet<usize> = HashSet::new();
+    while let Some(id) = roots.pop() {
+        if !reachable.insert(id) {
+            continue;
+        }
+        if let Some(references) = objects[&id].references() {
+            roots.extend(references.iter().map(|reference| reference.id()));
+        }
+    }
+
+    let mut collected = 0;
+    for (id, object) in &objects {
+        if !reachable.contains(id) {
+            object.clear();
+            if object.is_tracked() {
+                collected += 1;
+            }
+        }
+    }
+    drop(objects);
+    alive.retain(|object| object.is_alive());
+
+    HEAP.with(|heap| {
+        let mut heap = heap.borrow_mut();
+        heap.tracked.append(&mut alive);
+        heap.collected += collected;
+        heap.collections += 1;
+        heap.since_collection = 0;
+        heap.threshold = MIN_THRESHOLD.max(2 * heap.tracked.len());
+    });
+    return collected;
+}
@@ -1,43 +1,54 @@
 use super::token;
+use crate::error::{BoltError, ParseError};
 use regex::Regex;
+use unicode_ident::{is_xid_continue, is_xid_start};
 
 #[derive(Debug)]
 pub struct Lexer<'a> {
     input: &'a str,
-    input_chars: Vec<char>,
-    pub position: usize, // current position in input (points to current char)
-    pub read_position: usize, // current reading position in input (after current char)
+    pub position: usize, // current byte position in input (points to current char)
+    pub read_position: usize, // current reading byte position in input (after current char)
     curr_line: usize,
-    ch: char, // current char under examination
+    ch: char,                       // current char under examination
+    errors: Vec<ParseError>,        // malformed literals, they are lexed as ILLIGAL tokens
+    templates: Vec<(usize, usize)>, // open ${ of strings being interpolated, with their brace depth and the line the string started on
 }
 
 impl<'a> Lexer<'a> {
     pub fn new(source: &'a str) -> Lexer<'a> {
         let mut lexer = Self {
             input: source,
-            input_chars: source.chars().collect(), //Make the source into vector of chars
             position: 0,
-            curr_line: 0,
+            curr_line: 1, //Lines are counted from 1, like editors show them
             read_position: 0,
             ch: '\0',
+            errors: vec![],
+            templates: vec![],
         };
         lexer.read_char();
         return lexer;
     }
 
     pub fn read_char(&mut self) {
-        if self.read_position >= self.input.len() {
-            self.ch = '\0';
-        } else {
-            self.ch = *self.input_chars.get(self.read_position).unwrap();
+        self.position = self.read_position;
+        match self
+            .input
+            .get(self.read_position..)
+            .and_then(|rest| rest.chars().next())
+        {
+            Some(ch) => {
+                self.ch = ch;
+                self.read_position += ch.len_utf8();
+            }
+            None => {
+                self.ch = '\0';
+                self.read_position += 1;
+            }
         }
 
         if self.ch == '\n' {
             self.curr_line += 1;
         }
-
-        self.position = self.read_position;
-        self.read_position += 1;
     }
 
     fn create_new_token(
@@ -53,6 +64,10 @@ impl<'a> Lexer<'a> {
         };
     }
 
+    pub fn get_errors(&self) -> &Vec<ParseError> {
+        return &self.errors;
+    }
+
     pub fn get_tokens(&mut self) -> Vec<token::Token> {
         let mut tokens: Vec<token::Token> = vec![];
         loop {
@@ -78,6 +93,13 @@ impl<'a> Lexer<'a> {
                         String::from("=="),
                         self.curr_line,
                     );
+                } else if self.peek_char() == '>' {
+                    self.read_char();
+                    tok = self.create_new_token(
+                        token::TokenType::ARROW,
+                        String::from("=>"),
+                        self.curr_line,
+                    );
                 } else {
                     tok = self.create_new_token(
                         token::TokenType::ASSIGN,
@@ -107,6 +129,13 @@ impl<'a> Lexer<'a> {
                     self.curr_line,
                 );
             }
+            ':' => {
+                tok = self.create_new_token(
+                    token::TokenType::COLON,
+                    String::from(self.ch),
+                    self.curr_line,
+                );
+            }
             '(' => {
                 tok = self.create_new_token(
                     token::TokenType::LPAREN,
@@ -122,6 +151,9 @@ impl<'a> Lexer<'a> {
                 );
             }
             '{' => {
+                if let Some((depth, _)) = self.templates.last_mut() {
+                    *depth += 1;
+                }
                 tok = self.create_new_token(
                     token::TokenType::LBRACE,
                     String::from(self.ch),
@@ -129,12 +161,44 @@ impl<'a> Lexer<'a> {
                 );
             }
             '}' => {
+                match self.templates.last_mut() {
+                    //Closes the ${ of an interpolation, the string carries on after it
+                    Some((0, line)) => {
+                        let line = *line;
+                        self.templates.pop();
+                        self.read_char();
+                        return self.read_string_segment(true, line);
+                    }
+                    Some((depth, _)) => *depth -= 1,
+                    None => {}
+                }
                 tok = self.create_new_token(
                     token::TokenType::RBRACE,
                     String::from(self.ch),
                     self.curr_line,
                 );
             }
+            '[' => {
+                tok = self.create_new_token(
+                    token::TokenType::LBRACKET,
+                    String::from(self.ch),
+                    self.curr_line,
+                );
+            }
+            ']' => {
+                tok = self.create_new_token(
+                    token::TokenType::RBRACKET,
+                    String::from(self.ch),
+                    self.curr_line,
+                );
+            }
+            '.' => {
+                tok = self.create_new_token(
+                    token::TokenType::DOT,
+                    String::from(self.ch),
+                    self.curr_line,
+                );
+            }
             ',' => {
                 tok = self.create_new_token(
                     token::TokenType::COMMA,
@@ -161,12 +225,26 @@ impl<'a> Lexer<'a> {
             '/' => {
                 // Case for comments which needs to be ignored
                 if self.peek_char() == '/' {
-                    // Loop until you find the new line to exit the comment
-                    loop {
+                    let line = self.curr_line;
+                    let position = self.position;
+                    // Loop until you find the new line or the end to exit the comment
+                    while self.ch != '\n' && !self.is_at_end() {
                         self.read_char();
-                        if self.ch == '\n' {
-                            break;
-                        }
+                    }
+                    let comment = self.slice(position, self.position);
+                    //Exactly three slashes make a doc comment, it is kept for the parser
+                    if comment.starts_with("///") && !comment.starts_with("////") {
+                        let text = comment[3..].strip_prefix(' ').unwrap_or(&comment[3..]);
+                        return self.create_new_token(
+                            token::TokenType::DOC,
+                            String::from(text.trim_end()),
+                            line,
+                        );
+                    }
+                    return self.next_token();
+                } else if self.peek_char() == '*' {
+                    if let Err(error) = self.skip_block_comment() {
+                        return error;
                     }
                     return self.next_token();
                 } else {
@@ -177,6 +255,38 @@ impl<'a> Lexer<'a> {
                     );
                 }
             }
+            '&' => {
+                if self.peek_char() == '&' {
+                    self.read_char();
+                    tok = self.create_new_token(
+                        token::TokenType::AND,
+                        String::from("&&"),
+                        self.curr_line,
+                    );
+                } else {
+                    tok = self.create_new_token(
+                        token::TokenType::ILLIGAL,
+                        String::from(self.ch),
+                        self.curr_line,
+                    );
+                }
+            }
+            '|' => {
+                if self.peek_char() == '|' {
+                    self.read_char();
+                    tok = self.create_new_token(
+                        token::TokenType::OR,
+                        String::from("||"),
+                        self.curr_line,
+                    );
+                } else {
+                    tok = self.create_new_token(
+                        token::TokenType::PIPE,
+                        String::from(self.ch),
+                        self.curr_line,
+                    );
+                }
+            }
             '*' => {
                 tok = self.create_new_token(
                     token::TokenType::ASTERISK,
@@ -217,6 +327,11 @@ impl<'a> Lexer<'a> {
                 }
             }
             '\0' => {
+                if let Some((_, line)) = self.templates.pop() {
+                    self.templates.clear();
+                    let message = format!("Unterminated string starting at line {}", line);
+                    return self.error_token(message, String::new(), self.curr_line);
+                }
                 tok = self.create_new_token(
                     token::TokenType::EOF,
                     String::from(self.ch),
@@ -224,39 +339,28 @@ impl<'a> Lexer<'a> {
                 );
             }
             '"' => {
-                let start = self.read_position;
-                while self.peek_char() != '"' && !self.is_at_end() {
-                    if self.ch == '\n' {
-                        self.curr_line += 1;
-                    }
-                    self.read_char();
-                }
-                //advance to move next to quote
-                self.read_char();
-                let value = &self.input[start..self.position];
-                tok = self.create_new_token(
-                    token::TokenType::STRING,
-                    String::from(value),
-                    self.curr_line,
-                );
+                return self.read_string();
             }
             _ => {
                 // Find if its a letter or digit
-                if self.is_letter(self.ch) {
+                if self.is_raw_string_start() {
+                    return self.read_raw_string();
+                } else if self.is_letter(self.ch) {
                     let literal = self.lookup_identifier();
-                    let token = token::lookup_indentifier(literal);
+                    let token = token::lookup_indentifier(&literal);
                     tok = token::Token {
                         token_type: token,
-                        literal: String::from(literal),
+                        literal: literal,
                         line: self.curr_line,
                     };
                     return tok;
                 } else if self.is_digit(self.ch) {
-                    let literal = self.read_number();
+                    let line = self.curr_line;
+                    let (literal, token_type) = self.read_number();
                     tok = token::Token {
-                        token_type: token::TokenType::INT,
-                        literal: String::from(literal),
-                        line: self.curr_line,
+                        token_type: token_type,
+                        literal: literal,
+                        line: line,
                     };
                     return tok;
                 } else {
@@ -282,13 +386,13 @@ impl<'a> Lexer<'a> {
         }
     }
 
+    //Identifiers follow the Unicode XID rules, with _ also allowed as the first character
     fn is_letter(&self, ch: char) -> bool {
-        //Allowing _ as words can have _ in them
-        let letter_pattern = Regex::new(r"[a-zA-Z_]").unwrap();
-        if letter_pattern.is_match(&ch.to_string()) {
-            return true;
-        }
-        false
+        return ch == '_' || is_xid_start(ch);
+    }
+
+    fn is_identifier_char(&self, ch: char) -> bool {
+        return is_xid_continue(ch);
     }
 
     fn is_digit(&mut self, ch: char) -> bool {
@@ -299,32 +403,333 @@ impl<'a> Lexer<'a> {
         false
     }
 
-    fn peek_char(&mut self) -> char {
-        if self.read_position >= self.input.len() {
-            return '\0';
+    fn peek_char(&self) -> char {
+        return self.peek_char_at(0);
+    }
+
+    //Character `offset` places after the peeked one
+    fn peek_char_at(&self, offset: usize) -> char {
+        return self
+            .input
+            .get(self.read_position..)
+            .and_then(|rest| rest.chars().nth(offset))
+            .unwrap_or('\0');
+    }
+
+    //Positions are byte offsets that always sit on a char boundary, the end may be past the input
+    fn slice(&self, start: usize, end: usize) -> String {
+        let end = end.min(self.input.len());
+        return String::from(&self.input[start.min(end)..end]);
+    }
+
+    fn lookup_identifier(&mut self) -> String {
+        let position = self.position;
+        while self.is_identifier_char(self.ch) {
+            self.read_char();
+        }
+        return self.slice(position, self.position);
+    }
+
+    //Block comments nest, so every /* needs its own */
+    fn skip_block_comment(&mut self) -> Result<(), token::Token> {
+        let line = self.curr_line;
+        let mut depth = 0;
+        loop {
+            if self.is_at_end() {
+                let message = format!("Unterminated block comment starting at line {}", line);
+                return Err(self.error_token(message, String::new(), line));
+            }
+            if self.ch == '/' && self.peek_char() == '*' {
+                depth += 1;
+                self.read_char();
+            } else if self.ch == '*' && self.peek_char() == '/' {
+                depth -= 1;
+                self.read_char();
+                if depth == 0 {
+                    self.read_char();
+                    return Ok(());
+                }
+            }
+            self.read_char();
+        }
+    }
+
+    fn error_token(&mut self, message: String, literal: String, line: usize) -> token::Token {
+        self.errors.push(ParseError::new(message, None, Some(line)));
+        return self.create_new_token(token::TokenType::ILLIGAL, literal, line);
+    }
+
+    /*
+    Strings can span several lines and support the escapes \n, \t, \r, \0,
+    \\, \", \$ and \u{...} with up to six hex digits. The token holds the
+    decoded value and the line the string started on
+    */
+    fn read_string(&mut self) -> token::Token {
+        let line = self.curr_line;
+        self.read_char();
+        return self.read_string_segment(false, line);
+    }
+
+    /*
+    Reads up to the closing quote or the next ${. A string with interpolations is
+    split into a TEMPLATE token and TEMPLATEMIDDLE tokens, each followed by the
+    tokens of its expression, and a final TEMPLATEEND token. `continuation` is set for the segments that
+    follow the } of an interpolation
+    */
+    fn read_string_segment(&mut self, continuation: bool, line: usize) -> token::Token {
+        let segment_line = self.curr_line;
+        let mut value = String::new();
+        let mut error = None;
+        let mut interpolation = false;
+        loop {
+            match self.ch {
+                '"' => break,
+                '$' if self.peek_char() == '{' => {
+                    interpolation = true;
+                    break;
+                }
+                '\0' if self.is_at_end() => {
+                    let message = format!("Unterminated string starting at line {}", line);
+                    return self.error_token(message, value, segment_line);
+                }
+                '\\' => {
+                    let escape_line = self.curr_line;
+                    match self.read_escape() {
+                        Ok(ch) => value.push(ch),
+                        Err(escape) => {
+                            //Keep going so the whole string is consumed
+                            if error.is_none() {
+                                error = Some(format!(
+                                    "Invalid escape sequence {} in string at line {}",
+                                    escape, escape_line
+                                ));
+                            }
+                        }
+                    }
+                }
+                ch => value.push(ch),
+            }
+            self.read_char();
+        }
+        let token_type = if interpolation {
+            //Move past the ${
+            self.read_char();
+            self.templates.push((0, line));
+            if continuation {
+                token::TokenType::TEMPLATEMIDDLE
+            } else {
+                token::TokenType::TEMPLATE
+            }
+        } else if continuation {
+            token::TokenType::TEMPLATEEND
         } else {
-            return *self.input_chars.get(self.read_position).unwrap();
+            token::TokenType::STRING
+        };
+        //Move past the closing quote or the {
+        self.read_char();
+        if let Some(message) = error {
+            return self.error_token(message, value, segment_line);
         }
+        return self.create_new_token(token_type, value, segment_line);
     }
 
-    fn lookup_identifier(&mut self) -> &str {
-        let position = self.position;
-        while self.is_letter(self.ch) {
+    //Decodes the escape starting at the current '\\', on error returns the escape as written
+    fn read_escape(&mut self) -> Result<char, String> {
+        self.read_char();
+        match self.ch {
+            'n' => return Ok('\n'),
+            't' => return Ok('\t'),
+            'r' => return Ok('\r'),
+            '0' => return Ok('\0'),
+            '\\' => return Ok('\\'),
+            '"' => return Ok('"'),
+            '$' => return Ok('$'),
+            'u' => {
+                if self.peek_char() != '{' {
+                    return Err(String::from("\\u"));
+                }
+                self.read_char();
+                let mut digits = String::new();
+                while self.peek_char() != '}' && self.peek_char() != '"' && !self.is_at_end() {
+                    self.read_char();
+                    digits.push(self.ch);
+                }
+                let escape = format!("\\u{{{}}}", digits);
+                if self.peek_char() != '}' {
+                    return Err(escape);
+                }
+                self.read_char();
+                if digits.is_empty() || digits.len() > 6 {
+                    return Err(escape);
+                }
+                match u32::from_str_radix(&digits, 16)
+                    .ok()
+                    .and_then(char::from_u32)
+                {
+                    Some(ch) => return Ok(ch),
+                    None => return Err(escape),
+                }
+            }
+            '\0' if self.is_at_end() => return Err(String::from("\\")),
+            ch => return Err(format!("\\{}", ch)),
+        }
+    }
+
+    //r"..." or r#"..."# with any number of #, the latter can contain quotes
+    fn is_raw_string_start(&self) -> bool {
+        if self.ch != 'r' {
+            return false;
+        }
+        let mut offset = 0;
+        while self.peek_char_at(offset) == '#' {
+            offset += 1;
+        }
+        return self.peek_char_at(offset) == '"';
+    }
+
+    //Raw strings have no escapes, everything up to the closing quote and #s is kept as is
+    fn read_raw_string(&mut self) -> token::Token {
+        let line = self.curr_line;
+        let mut hashes = 0;
+        self.read_char();
+        while self.ch == '#' {
+            hashes += 1;
+            self.read_char();
+        }
+        self.read_char();
+        let start = self.position;
+        loop {
+            if self.is_at_end() {
+                let literal = self.slice(start, self.position);
+                let message = format!("Unterminated raw string starting at line {}", line);
+                return self.error_token(message, literal, line);
+            }
+            if self.ch == '"' && (0..hashes).all(|offset| self.peek_char_at(offset) == '#') {
+                break;
+            }
+            self.read_char();
+        }
+        let value = self.slice(start, self.position);
+        for _ in 0..=hashes {
             self.read_char();
         }
-        return &self.input[position..self.position];
+        return self.create_new_token(token::TokenType::STRING, value, line);
     }
 
-    fn read_number(&mut self) -> &str {
+    /*
+    Numbers are decimal integers, floats with a fraction and/or an exponent or
+    integers with a 0x, 0b or 0o prefix. Digits can be grouped with '_' as long
+    as it sits between two digits. A malformed number is consumed completely and
+    returned as an ILLIGAL token, the error is kept in the lexer
+    */
+    fn read_number(&mut self) -> (String, token::TokenType) {
         let position = self.position;
-        //Adding '.' for float
-        while self.is_digit(self.ch) || self.ch == '.' {
+        let line = self.curr_line;
+        let result = self.scan_number();
+        if result.is_err() {
+            while self.is_letter(self.ch) || self.is_digit(self.ch) {
+                self.read_char();
+            }
+        }
+        let literal = self.slice(position, self.position);
+        match result {
+            Ok(token_type) => return (literal, token_type),
+            Err(message) => {
+                self.errors.push(ParseError::new(
+                    format!("{} {} at line {}", message, literal, line),
+                    None,
+                    Some(line),
+                ));
+                return (literal, token::TokenType::ILLIGAL);
+            }
+        }
+    }
+
+    fn scan_number(&mut self) -> Result<token::TokenType, String> {
+        if self.ch == '0' {
+            let radix = match self.peek_char() {
+                'x' | 'X' => Some((16, "hexadecimal")),
+                'o' | 'O' => Some((8, "octal")),
+                'b' | 'B' => Some((2, "binary")),
+                _ => None,
+            };
+            if let Some((radix, name)) = radix {
+                self.read_char();
+                self.read_char();
+                self.read_digits(radix, name)?;
+                self.check_number_end()?;
+                return Ok(token::TokenType::INT);
+            }
+        }
+
+        let mut token_type = token::TokenType::INT;
+        let first = self.ch;
+        let digits = self.read_digits(10, "number")?;
+        if first == '0' && digits > 1 {
+            return Err(String::from(
+                "Leading zeros are not allowed in number literal",
+            ));
+        }
+        //A '.' without a digit after it is left for property access
+        let next = self.peek_char();
+        if self.ch == '.' && self.is_digit(next) {
+            token_type = token::TokenType::FLOAT;
+            self.read_char();
+            self.read_digits(10, "number")?;
+        }
+        if self.ch == 'e' || self.ch == 'E' {
+            token_type = token::TokenType::FLOAT;
+            self.read_char();
+            if self.ch == '+' || self.ch == '-' {
+                self.read_char();
+            }
+            if !self.is_digit(self.ch) {
+                return Err(String::from("Missing exponent digits in number literal"));
+            }
+            self.read_digits(10, "number")?;
+        }
+        self.check_number_end()?;
+        return Ok(token_type);
+    }
+
+    //Reads digits of the radix and '_' separators, returns the number of digits
+    fn read_digits(&mut self, radix: u32, name: &str) -> Result<usize, String> {
+        let mut digits = 0;
+        let mut separator = false;
+        loop {
+            if self.ch == '_' {
+                if digits == 0 || separator {
+                    return Err(format!("Misplaced _ in {} literal", name));
+                }
+                separator = true;
+            } else if self.ch.is_digit(radix) {
+                digits += 1;
+                separator = false;
+            } else if radix != 10 && self.ch.is_ascii_alphanumeric() {
+                return Err(format!("Invalid digit {} in {} literal", self.ch, name));
+            } else {
+                break;
+            }
             self.read_char();
         }
-        return &self.input[position..self.position];
+        if separator {
+            return Err(format!("Misplaced _ in {} literal", name));
+        }
+        if digits == 0 {
+            return Err(format!("Missing digits in {} literal", name));
+        }
+        return Ok(digits);
+    }
+
+    //Numbers can not run into letters, `12ab` is an error and not 12 followed by ab
+    fn check_number_end(&mut self) -> Result<(), String> {
+        if self.is_letter(self.ch) || self.is_digit(self.ch) {
+            return Err(format!("Invalid character {} in number literal", self.ch));
+        }
+        return Ok(());
     }
 
     fn is_at_end(&self) -> bool {
-        return self.position == self.input.len();
+        return self.position >= self.input.len();
     }
 }
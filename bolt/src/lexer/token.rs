@@ -5,11 +5,16 @@ use std::collections::HashMap;
 pub enum TokenType {
     EOF,
     ILLIGAL,
+    DOC, //Text of a /// comment
 
     //Identifiers
     IDENTIFIER,
     STRING,
+    TEMPLATE,       //First segment of an interpolated string, followed by ${
+    TEMPLATEMIDDLE, //Segment between } and the next ${
+    TEMPLATEEND,    //Last segment of an interpolated string
     INT,
+    FLOAT,
 
     //Operators
     ASSIGN,
@@ -20,6 +25,10 @@ pub enum TokenType {
     BANG,
     ASTERISK,
     SLASH,
+    AND,
+    OR,
+    PIPE,
+    ARROW,
 
     LT,
     GT,
@@ -29,11 +38,15 @@ pub enum TokenType {
     //Delimeters
     COMMA,
     SEMICOLON,
+    COLON,
+    DOT,
 
     LPAREN,
     RPAREN,
     LBRACE,
     RBRACE,
+    LBRACKET,
+    RBRACKET,
 
     //Keyworks
     LET,
@@ -44,6 +57,23 @@ pub enum TokenType {
     IF,
     ELSE,
     RETURN,
+    WHILE,
+    BREAK,
+    CONTINUE,
+    IMPORT,
+    EXPORT,
+    AS,
+    CLASS,
+    THIS,
+    SUPER,
+    MATCH,
+    THROW,
+    TRY,
+    CATCH,
+    FINALLY,
+    YIELD,
+    ASYNC,
+    AWAIT,
 }
 
 /*
@@ -57,6 +87,23 @@ pub fn lookup_indentifier(indent: &str) -> TokenType {
         ("if", TokenType::IF),
         ("else", TokenType::ELSE),
         ("return", TokenType::RETURN),
+        ("while", TokenType::WHILE),
+        ("break", TokenType::BREAK),
+        ("continue", TokenType::CONTINUE),
+        ("import", TokenType::IMPORT),
+        ("export", TokenType::EXPORT),
+        ("as", TokenType::AS),
+        ("class", TokenType::CLASS),
+        ("this", TokenType::THIS),
+        ("super", TokenType::SUPER),
+        ("match", TokenType::MATCH),
+        ("throw", TokenType::THROW),
+        ("try", TokenType::TRY),
+        ("catch", TokenType::CATCH),
+        ("finally", TokenType::FINALLY),
+        ("yield", TokenType::YIELD),
+        ("async", TokenType::ASYNC),
+        ("await", TokenType::AWAIT),
         ("true", TokenType::TRUE),
         ("false", TokenType::FALSE),
     ]);
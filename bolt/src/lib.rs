@@ -1,8 +1,10 @@
 mod compiler;
 pub mod error;
 pub mod evaluator;
+pub mod gc;
 pub mod lexer;
 pub mod object;
 pub mod parser;
+pub mod resolver;
 
 pub use compiler::{Compiler, CompilerBackend, Factory};
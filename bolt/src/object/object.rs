@@ -1,111 +1,409 @@
 use crate::{
-    evaluator::environment::Environment,
+    compiler::CompiledFunction,
+    error::{BoltError, EvaluatorError},
+    evaluator::{environment::Environment, event_loop::Task, generator::Coroutine},
     parser::ast::{BlockStatement, Identifier},
 };
 
-use super::types::ObjectType;
-use core::fmt::Debug;
-use std::{any::Any, cell::RefCell, rc::Rc};
+use super::types::{HashKey, ObjectType};
+use std::{
+    cell::{Cell, RefCell},
+    collections::HashMap,
+    rc::Rc,
+};
 
-pub trait Object
-where
-    Self: Debug,
-{
-    fn get_type(&self) -> ObjectType;
-    fn inspect(&self) -> String;
-    fn as_any(&self) -> &dyn Any;
+/*
+Runtime value produced by the evaluator. Numbers, booleans and null are stored
+inline so they never allocate, everything else is shared through an Rc and
+cloning a value only bumps the reference count
+*/
+#[derive(Debug, Clone)]
+pub enum Value {
+    Integer(i64),
+    Float(f64),
+    Boolean(bool),
+    Null,
+    Str(Rc<String>),
+    Function(Rc<Function>),
+    Closure(Rc<Closure>),
+    Builtin(&'static Builtin),
+    BuiltinMethod(Rc<BuiltinMethod>),
+    Array(Rc<Array>),
+    Hash(Rc<Hash>),
+    Class(Rc<Class>),
+    Instance(Rc<Instance>),
+    BoundMethod(Rc<BoundMethod>),
+    Error(Rc<ErrorObj>),
+    Module(Rc<Module>),
+    Generator(Rc<Generator>),
+    Promise(Rc<Promise>),
+    Return(Box<Value>), //Unwinds blocks until the enclosing function call unwraps it
+    Break,              //Signals the enclosing loop to stop, unwinds blocks the same way as Return
+    Continue,           //Signals the enclosing loop to skip to the next iteration
+    Uninitialized,      //Held by a local of the virtual machine until its declaration runs
 }
 
-#[derive(Debug)]
-pub struct Interger {
-    pub value: f64,
-}
+impl Value {
+    pub fn string(value: String) -> Self {
+        return Value::Str(Rc::new(value));
+    }
 
-impl Object for Interger {
-    fn get_type(&self) -> ObjectType {
-        ObjectType::INTERGER
+    pub fn get_type(&self) -> ObjectType {
+        match self {
+            Value::Integer(_) => ObjectType::INTERGER,
+            Value::Float(_) => ObjectType::FLOAT,
+            Value::Boolean(_) => ObjectType::BOOLEAN,
+            Value::Null => ObjectType::NULL,
+            Value::Str(_) => ObjectType::STRING,
+            Value::Function(_) | Value::Closure(_) | Value::BoundMethod(_) => ObjectType::FUNCTION,
+            Value::Builtin(_) | Value::BuiltinMethod(_) => ObjectType::BUILTIN,
+            Value::Array(_) => ObjectType::ARRAY,
+            Value::Hash(_) => ObjectType::HASH,
+            Value::Class(_) => ObjectType::CLASS,
+            Value::Instance(_) => ObjectType::INSTANCE,
+            Value::Error(_) => ObjectType::ERROR,
+            Value::Module(_) => ObjectType::MODULE,
+            Value::Generator(_) => ObjectType::GENERATOR,
+            Value::Promise(_) => ObjectType::PROMISE,
+            Value::Return(_) => ObjectType::RETURN,
+            Value::Break => ObjectType::BREAK,
+            Value::Continue => ObjectType::CONTINUE,
+            Value::Uninitialized => ObjectType::UNINITIALIZED,
+        }
     }
-    fn inspect(&self) -> String {
-        self.value.to_string()
+
+    pub fn inspect(&self) -> String {
+        return self.inspect_nested(&mut vec![]);
     }
-    fn as_any(&self) -> &dyn Any {
-        self
+
+    //Visited holds the arrays and hashes being printed, a value that contains itself prints as [...] or {...}
+    fn inspect_nested(&self, visited: &mut Vec<*const ()>) -> String {
+        match self {
+            Value::Integer(value) => return value.to_string(),
+            Value::Float(value) => {
+                //Whole floats keep a decimal point or an exponent so they read differently from integers
+                if value.fract() == 0.0 && value.abs() < 1e16 {
+                    return format!("{:.1}", value);
+                } else if value.fract() == 0.0 {
+                    return format!("{:e}", value);
+                }
+                return value.to_string();
+            }
+            Value::Boolean(value) => return value.to_string(),
+            Value::Null => return String::from("null"),
+            Value::Str(value) => return value.as_ref().clone(),
+            Value::Function(function) => return function.inspect(),
+            Value::Closure(closure) => return closure.function.inspect(),
+            Value::Builtin(builtin) => return format!("builtin {}", builtin.name),
+            Value::BuiltinMethod(method) => return format!("builtin {}", method.name),
+            Value::Array(array) => return array.inspect_nested(visited),
+            Value::Hash(hash) => return hash.inspect_nested(visited),
+            Value::Class(class) => return format!("class {}", class.name),
+            Value::Instance(instance) => return format!("{} instance", instance.class.name),
+            Value::BoundMethod(bound) => return bound.method.inspect(),
+            Value::Error(error) => return format!("{}: {}", error.kind, error.message),
+            Value::Module(module) => return format!("module {}", module.name),
+            Value::Generator(_) => return String::from("generator"),
+            Value::Promise(promise) => return promise.inspect_nested(visited),
+            Value::Return(value) => return value.inspect_nested(visited),
+            Value::Break => return String::from("break"),
+            Value::Continue => return String::from("continue"),
+            Value::Uninitialized => return String::from("uninitialized"),
+        }
+    }
+
+    //Values that can be used as hash keys return the key, others return None
+    pub fn hash_key(&self) -> Option<HashKey> {
+        match self {
+            Value::Integer(value) => return Some(HashKey::Integer(*value)),
+            Value::Float(value) => {
+                //1.0 == 1 so whole floats share the key of the integer, this also maps -0 to 0
+                if value.fract() == 0.0 && value.abs() < 9.2e18 {
+                    return Some(HashKey::Integer(*value as i64));
+                }
+                return Some(HashKey::Float(value.to_bits()));
+            }
+            Value::Boolean(value) => return Some(HashKey::Boolean(*value)),
+            Value::Str(value) => return Some(HashKey::Str(value.as_ref().clone())),
+            _ => return None,
+        }
     }
 }
 
 #[derive(Debug)]
-pub struct BooleanObj {
-    pub value: bool,
+pub struct Function {
+    pub parameters: Rc<Vec<Identifier>>,
+    pub body: Rc<Box<BlockStatement>>,
+    pub env: Rc<RefCell<Environment>>,
+    pub is_generator: bool,
+    pub is_async: bool,
 }
 
-impl Object for BooleanObj {
-    fn get_type(&self) -> ObjectType {
-        ObjectType::BOOLEAN
-    }
-    fn inspect(&self) -> String {
-        self.value.to_string()
-    }
-    fn as_any(&self) -> &dyn Any {
-        self
+impl Function {
+    pub fn inspect(&self) -> String {
+        let mut params = vec![];
+        for param in self.parameters.as_ref() {
+            params.push(param.value.clone());
+        }
+        return format!("fn({})", params.join(","));
     }
 }
 
+//Function compiled to bytecode, run by the virtual machine
+#[derive(Debug)]
+pub struct Closure {
+    pub function: Rc<CompiledFunction>,
+    pub upvalues: Vec<Rc<RefCell<Value>>>, //Variables captured from the enclosing functions
+    pub globals: Rc<RefCell<Environment>>, //Top level environment of the module it was created in
+}
+
+pub type BuiltinFunction =
+    fn(Vec<Value>, Rc<RefCell<Environment>>) -> Result<Value, EvaluatorError>;
+
+//Function implemented in Rust, receives the evaluated arguments in order and the caller's environment
+#[derive(Debug)]
+pub struct Builtin {
+    pub name: &'static str,
+    pub function: BuiltinFunction,
+}
+
+pub type BuiltinMethodFunction = fn(Value, Vec<Value>) -> Result<Value, EvaluatorError>;
+
+//Method implemented in Rust, receives the value it was looked up on and the arguments
+#[derive(Debug)]
+pub struct BuiltinMethod {
+    pub name: String,
+    pub receiver: Value,
+    pub function: BuiltinMethodFunction,
+}
+
+//Returned by calling a generator function, the body runs one yield at a time on next()
+#[derive(Debug)]
+pub struct Generator {
+    pub execution: RefCell<Box<dyn Coroutine>>,
+}
+
+#[derive(Debug)]
+pub enum PromiseState {
+    Pending(Vec<Rc<Task>>), //Tasks waiting on the promise, resumed in order once it settles
+    Fulfilled(Value),
+    Rejected(EvaluatorError),
+}
+
+//Result of a task or timer run by the event loop, awaiting it gives the value or raises the error
 #[derive(Debug)]
-pub struct Return {
-    pub value: Rc<Box<dyn Object>>,
+pub struct Promise {
+    pub state: RefCell<PromiseState>,
+    pub handled: Cell<bool>, //Set once awaited, a rejection nobody awaited is reported
 }
 
-impl Object for Return {
-    fn get_type(&self) -> ObjectType {
-        ObjectType::RETURN
+impl Promise {
+    pub fn new() -> Self {
+        Self {
+            state: RefCell::new(PromiseState::Pending(vec![])),
+            handled: Cell::new(false),
+        }
     }
-    fn inspect(&self) -> String {
-        return self.value.inspect();
+
+    pub fn inspect(&self) -> String {
+        return self.inspect_nested(&mut vec![]);
     }
-    fn as_any(&self) -> &dyn Any {
-        self
+
+    fn inspect_nested(&self, visited: &mut Vec<*const ()>) -> String {
+        match &*self.state.borrow() {
+            PromiseState::Pending(_) => return String::from("promise pending"),
+            PromiseState::Fulfilled(value) => {
+                return format!("promise fulfilled {}", value.inspect_nested(visited))
+            }
+            PromiseState::Rejected(error) => {
+                return format!(
+                    "promise rejected {}: {}",
+                    error.get_type().name(),
+                    error.get_message()
+                )
+            }
+        }
     }
 }
 
+//Calling a class creates an instance and runs its init method
 #[derive(Debug)]
-pub struct Function {
-    pub parameters: Rc<Vec<Identifier>>,
-    pub body: Rc<Box<BlockStatement>>,
+pub struct Class {
+    pub name: String,
+    pub superclass: Option<Rc<Class>>,
+    pub methods: HashMap<String, Value>, //Functions or closures depending on what declared the class
+}
+
+impl Class {
+    //Methods are looked up on the class first and then through the superclass chain
+    pub fn find_method(&self, name: &str) -> Option<Value> {
+        if let Some(method) = self.methods.get(name) {
+            return Some(method.clone());
+        }
+        match self.superclass.as_ref() {
+            Some(superclass) => return superclass.find_method(name),
+            None => return None,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Instance {
+    pub class: Rc<Class>,
+    pub fields: RefCell<HashMap<String, Value>>,
+}
+
+//Method looked up on an instance, this refers to the receiver when it is called
+#[derive(Debug)]
+pub struct BoundMethod {
+    pub receiver: Value,
+    pub method: Value,
+}
+
+//Value bound by catch, created by throw or from an error raised by the evaluator
+#[derive(Debug)]
+pub struct ErrorObj {
+    pub message: String,
+    pub kind: String,
+    pub line: Option<usize>,
+}
+
+//Result of evaluating an imported file, only exported bindings are visible
+#[derive(Debug)]
+pub struct Module {
+    pub name: String,
     pub env: Rc<RefCell<Environment>>,
+    pub exports: Vec<String>,
 }
 
-impl Object for Function {
-    fn get_type(&self) -> ObjectType {
-        ObjectType::FUNCTION
+impl Module {
+    pub fn get(&self, name: &str) -> Option<Value> {
+        if !self.exports.iter().any(|export| export == name) {
+            return None;
+        }
+        return self.env.borrow().get(name);
     }
+}
 
-    #[allow(unused_assignments)]
-    fn inspect(&self) -> String {
-        let mut result = String::new();
-        let mut params = vec![];
-        for param in self.parameters.as_ref() {
-            params.push(param.value.clone());
+#[derive(Debug)]
+pub struct Array {
+    pub elements: RefCell<Vec<Value>>,
+}
+
+impl Array {
+    pub fn new(elements: Vec<Value>) -> Self {
+        Self {
+            elements: RefCell::new(elements),
         }
-        result = format!("fn({})", params.join(","));
-        return result;
     }
 
-    fn as_any(&self) -> &dyn Any {
-        self
+    pub fn inspect(&self) -> String {
+        return self.inspect_nested(&mut vec![]);
+    }
+
+    fn inspect_nested(&self, visited: &mut Vec<*const ()>) -> String {
+        let pointer = self as *const Self as *const ();
+        if visited.contains(&pointer) {
+            return String::from("[...]");
+        }
+        visited.push(pointer);
+        let mut elements = vec![];
+        for element in self.elements.borrow().iter() {
+            elements.push(element.inspect_nested(visited));
+        }
+        visited.pop();
+        format!("[{}]", elements.join(", "))
     }
 }
 
 #[derive(Debug)]
-pub struct Null {}
+pub struct HashPair {
+    pub key: Value,
+    pub value: Value,
+}
 
-impl Object for Null {
-    fn get_type(&self) -> ObjectType {
-        ObjectType::NULL
+#[derive(Debug)]
+pub struct Hash {
+    pairs: RefCell<Vec<HashPair>>, //Pairs in insertion order
+    index: RefCell<HashMap<HashKey, usize>>,
+}
+
+impl Hash {
+    pub fn new() -> Self {
+        Self {
+            pairs: RefCell::new(vec![]),
+            index: RefCell::new(HashMap::new()),
+        }
+    }
+
+    pub fn get(&self, key: &HashKey) -> Option<Value> {
+        match self.index.borrow().get(key) {
+            Some(position) => Some(self.pairs.borrow()[*position].value.clone()),
+            None => None,
+        }
+    }
+
+    pub fn set(&self, hash_key: HashKey, pair: HashPair) {
+        let position = self.index.borrow().get(&hash_key).copied();
+        match position {
+            Some(position) => {
+                self.pairs.borrow_mut()[position] = pair;
+            }
+            None => {
+                let mut pairs = self.pairs.borrow_mut();
+                self.index.borrow_mut().insert(hash_key, pairs.len());
+                pairs.push(pair);
+            }
+        }
+    }
+
+    //Keys and values of every pair, None while the hash is being changed
+    pub fn try_values(&self) -> Option<Vec<Value>> {
+        let pairs = self.pairs.try_borrow().ok()?;
+        let mut values = vec![];
+        for pair in pairs.iter() {
+            values.push(pair.key.clone());
+            values.push(pair.value.clone());
+        }
+        Some(values)
+    }
+
+    pub fn clear(&self) {
+        if let (Ok(mut pairs), Ok(mut index)) =
+            (self.pairs.try_borrow_mut(), self.index.try_borrow_mut())
+        {
+            pairs.clear();
+            index.clear();
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.pairs.borrow().len()
     }
-    fn inspect(&self) -> String {
-        String::from("null")
+
+    pub fn is_empty(&self) -> bool {
+        self.pairs.borrow().is_empty()
+    }
+
+    pub fn inspect(&self) -> String {
+        return self.inspect_nested(&mut vec![]);
     }
-    fn as_any(&self) -> &dyn Any {
-        self
+
+    fn inspect_nested(&self, visited: &mut Vec<*const ()>) -> String {
+        let pointer = self as *const Self as *const ();
+        if visited.contains(&pointer) {
+            return String::from("{...}");
+        }
+        visited.push(pointer);
+        let mut pairs = vec![];
+        for pair in self.pairs.borrow().iter() {
+            pairs.push(format!(
+                "{}: {}",
+                pair.key.inspect_nested(visited),
+                pair.value.inspect_nested(visited)
+            ));
+        }
+        visited.pop();
+        format!("{{{}}}", pairs.join(", "))
     }
 }
@@ -1,9 +1,34 @@
-#[derive(PartialEq)]
+#[derive(PartialEq, Debug, Clone)]
 pub enum ObjectType {
     INTERGER,
+    FLOAT,
     BOOLEAN,
     NULL,
     STRING,
     FUNCTION,
+    BUILTIN,
+    ARRAY,
+    HASH,
+    MODULE,
+    CLASS,
+    INSTANCE,
+    ERROR,
+    GENERATOR,
+    PROMISE,
     RETURN,
+    BREAK,
+    CONTINUE,
+    UNINITIALIZED,
+}
+
+/*
+Key used to store objects in a hash, only numbers, booleans and strings
+can be used as keys and two keys are equal when their values are equal
+*/
+#[derive(PartialEq, Eq, Hash, Debug, Clone)]
+pub enum HashKey {
+    Integer(i64),
+    Float(u64),
+    Boolean(bool),
+    Str(String),
 }
@@ -1,23 +1,173 @@
 use crate::lexer::token;
-use core::fmt::Debug;
-use std::{any::Any, rc::Rc};
+use core::fmt::{self, Debug};
+use std::{cell::Cell, rc::Rc};
 
 pub trait Node {
     fn token_literal(&self) -> String;
-    fn as_any(&self) -> &dyn Any;
 }
 
-pub trait Statement: Node + Debug {
-    fn print(&self) -> String {
-        let result = format!("{:?}", self);
-        result
+/*
+Statements and expressions are closed sets of nodes. Code walking the tree
+matches on them, so the compiler points out every place a new node has to be
+handled
+*/
+pub enum Statement {
+    Let(LetStatement),
+    Return(ReturnStatement),
+    While(WhileStatement),
+    Class(ClassStatement),
+    Import(ImportStatement),
+    Export(ExportStatement),
+    Throw(ThrowStatement),
+    Try(TryStatement),
+    Break(BreakStatement),
+    Continue(ContinueStatement),
+    Block(BlockStatement),
+    Expression(ExpressionStatement),
+}
+
+impl Statement {
+    //Line the statement starts on, used to locate runtime errors
+    pub fn line(&self) -> usize {
+        match self {
+            Statement::Let(statement) => return statement.token.line,
+            Statement::Return(statement) => return statement.token.line,
+            Statement::While(statement) => return statement.token.line,
+            Statement::Class(statement) => return statement.token.line,
+            Statement::Import(statement) => return statement.token.line,
+            Statement::Export(statement) => return statement.token.line,
+            Statement::Throw(statement) => return statement.token.line,
+            Statement::Try(statement) => return statement.token.line,
+            Statement::Break(statement) => return statement.token.line,
+            Statement::Continue(statement) => return statement.token.line,
+            Statement::Block(statement) => return statement.token.line,
+            Statement::Expression(statement) => return statement.token.line,
+        }
+    }
+}
+
+impl Node for Statement {
+    fn token_literal(&self) -> String {
+        match self {
+            Statement::Let(statement) => return statement.token_literal(),
+            Statement::Return(statement) => return statement.token_literal(),
+            Statement::While(statement) => return statement.token_literal(),
+            Statement::Class(statement) => return statement.token_literal(),
+            Statement::Import(statement) => return statement.token_literal(),
+            Statement::Export(statement) => return statement.token_literal(),
+            Statement::Throw(statement) => return statement.token_literal(),
+            Statement::Try(statement) => return statement.token_literal(),
+            Statement::Break(statement) => return statement.token_literal(),
+            Statement::Continue(statement) => return statement.token_literal(),
+            Statement::Block(statement) => return statement.token_literal(),
+            Statement::Expression(statement) => return statement.token_literal(),
+        }
+    }
+}
+
+//The variant only tags the node, so a statement prints as the node it holds
+impl Debug for Statement {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Statement::Let(statement) => return statement.fmt(f),
+            Statement::Return(statement) => return statement.fmt(f),
+            Statement::While(statement) => return statement.fmt(f),
+            Statement::Class(statement) => return statement.fmt(f),
+            Statement::Import(statement) => return statement.fmt(f),
+            Statement::Export(statement) => return statement.fmt(f),
+            Statement::Throw(statement) => return statement.fmt(f),
+            Statement::Try(statement) => return statement.fmt(f),
+            Statement::Break(statement) => return statement.fmt(f),
+            Statement::Continue(statement) => return statement.fmt(f),
+            Statement::Block(statement) => return statement.fmt(f),
+            Statement::Expression(statement) => return statement.fmt(f),
+        }
+    }
+}
+
+pub enum Expression {
+    Identifier(Identifier),
+    Integer(IntegerLiteral),
+    Float(FloatLiteral),
+    String(StringLiteral),
+    InterpolatedString(InterpolatedString),
+    Null(NullLiteral),
+    Boolean(Boolean),
+    Prefix(PrefixExpression),
+    Binary(BinaryExpression),
+    Logical(LogicalExpression),
+    If(IfExpression),
+    Match(MatchExpression),
+    Function(FunctionLiteral),
+    Call(CallExpression),
+    Array(ArrayLiteral),
+    Hash(HashLiteral),
+    Index(IndexExpression),
+    Assign(AssignExpression),
+    Get(GetExpression),
+    This(ThisExpression),
+    Super(SuperExpression),
+    Yield(YieldExpression),
+    Await(AwaitExpression),
+}
+
+impl Node for Expression {
+    fn token_literal(&self) -> String {
+        match self {
+            Expression::Identifier(expression) => return expression.token_literal(),
+            Expression::Integer(expression) => return expression.token_literal(),
+            Expression::Float(expression) => return expression.token_literal(),
+            Expression::String(expression) => return expression.token_literal(),
+            Expression::InterpolatedString(expression) => return expression.token_literal(),
+            Expression::Null(expression) => return expression.token_literal(),
+            Expression::Boolean(expression) => return expression.token_literal(),
+            Expression::Prefix(expression) => return expression.token_literal(),
+            Expression::Binary(expression) => return expression.token_literal(),
+            Expression::Logical(expression) => return expression.token_literal(),
+            Expression::If(expression) => return expression.token_literal(),
+            Expression::Match(expression) => return expression.token_literal(),
+            Expression::Function(expression) => return expression.token_literal(),
+            Expression::Call(expression) => return expression.token_literal(),
+            Expression::Array(expression) => return expression.token_literal(),
+            Expression::Hash(expression) => return expression.token_literal(),
+            Expression::Index(expression) => return expression.token_literal(),
+            Expression::Assign(expression) => return expression.token_literal(),
+            Expression::Get(expression) => return expression.token_literal(),
+            Expression::This(expression) => return expression.token_literal(),
+            Expression::Super(expression) => return expression.token_literal(),
+            Expression::Yield(expression) => return expression.token_literal(),
+            Expression::Await(expression) => return expression.token_literal(),
+        }
     }
 }
 
-pub trait Expression: Node + Debug {
-    fn print(&self) -> String {
-        let result = format!("{:?}", self);
-        result
+impl Debug for Expression {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Expression::Identifier(expression) => return expression.fmt(f),
+            Expression::Integer(expression) => return expression.fmt(f),
+            Expression::Float(expression) => return expression.fmt(f),
+            Expression::String(expression) => return expression.fmt(f),
+            Expression::InterpolatedString(expression) => return expression.fmt(f),
+            Expression::Null(expression) => return expression.fmt(f),
+            Expression::Boolean(expression) => return expression.fmt(f),
+            Expression::Prefix(expression) => return expression.fmt(f),
+            Expression::Binary(expression) => return expression.fmt(f),
+            Expression::Logical(expression) => return expression.fmt(f),
+            Expression::If(expression) => return expression.fmt(f),
+            Expression::Match(expression) => return expression.fmt(f),
+            Expression::Function(expression) => return expression.fmt(f),
+            Expression::Call(expression) => return expression.fmt(f),
+            Expression::Array(expression) => return expression.fmt(f),
+            Expression::Hash(expression) => return expression.fmt(f),
+            Expression::Index(expression) => return expression.fmt(f),
+            Expression::Assign(expression) => return expression.fmt(f),
+            Expression::Get(expression) => return expression.fmt(f),
+            Expression::This(expression) => return expression.fmt(f),
+            Expression::Super(expression) => return expression.fmt(f),
+            Expression::Yield(expression) => return expression.fmt(f),
+            Expression::Await(expression) => return expression.fmt(f),
+        }
     }
 }
 
@@ -25,105 +175,241 @@ pub trait Expression: Node + Debug {
 pub struct LetStatement {
     pub token: token::Token,
     pub identifier: Identifier,
-    pub value: Box<dyn Expression>,
+    pub value: Box<Expression>,
+    pub doc: Option<String>, //Text of the /// comments right before the statement
 }
 
 impl Node for LetStatement {
     fn token_literal(&self) -> String {
         return self.token.literal.clone();
     }
-    fn as_any(&self) -> &dyn Any {
-        self
-    }
 }
 
-impl Statement for LetStatement {}
-
 #[derive(Debug)]
 pub struct ReturnStatement {
     pub token: token::Token,
-    pub value: Box<dyn Expression>,
+    pub value: Box<Expression>,
 }
 
 impl Node for ReturnStatement {
     fn token_literal(&self) -> String {
         return self.token.literal.clone();
     }
-    fn as_any(&self) -> &dyn Any {
-        self
+}
+
+#[derive(Debug)]
+pub struct WhileStatement {
+    pub token: token::Token,
+    pub condition: Box<Expression>,
+    pub body: Rc<Box<BlockStatement>>,
+}
+
+impl Node for WhileStatement {
+    fn token_literal(&self) -> String {
+        return self.token.literal.clone();
     }
 }
 
-impl Statement for ReturnStatement {}
+#[derive(Debug)]
+pub struct Method {
+    pub name: Identifier,
+    pub function: FunctionLiteral,
+    pub doc: Option<String>,
+}
 
 #[derive(Debug)]
-pub struct BlockStatement {
+pub struct ClassStatement {
     pub token: token::Token,
-    pub statements: Vec<Box<dyn Statement>>,
+    pub name: Identifier,
+    pub superclass: Option<Identifier>,
+    pub methods: Vec<Method>,
 }
 
-impl Node for BlockStatement {
+impl Node for ClassStatement {
+    fn token_literal(&self) -> String {
+        return self.token.literal.clone();
+    }
+}
+
+#[derive(Debug)]
+pub struct ImportStatement {
+    pub token: token::Token,
+    pub path: String,
+    pub alias: Option<Identifier>,
+}
+
+impl Node for ImportStatement {
     fn token_literal(&self) -> String {
         return self.token.literal.clone();
     }
-    fn as_any(&self) -> &dyn Any {
-        self
+}
+
+#[derive(Debug)]
+pub struct ExportStatement {
+    pub token: token::Token,
+    pub statement: Box<Statement>, //LetStatement being exported
+}
+
+impl Node for ExportStatement {
+    fn token_literal(&self) -> String {
+        return self.token.literal.clone();
     }
 }
 
-impl Statement for BlockStatement {}
+#[derive(Debug)]
+pub struct ThrowStatement {
+    pub token: token::Token,
+    pub value: Box<Expression>,
+}
+
+impl Node for ThrowStatement {
+    fn token_literal(&self) -> String {
+        return self.token.literal.clone();
+    }
+}
+
+#[derive(Debug)]
+pub struct TryStatement {
+    pub token: token::Token,
+    pub body: Rc<Box<BlockStatement>>,
+    pub catch_parameter: Option<Identifier>,
+    pub catch_body: Option<Rc<Box<BlockStatement>>>,
+    pub finally_body: Option<Rc<Box<BlockStatement>>>,
+}
+
+impl Node for TryStatement {
+    fn token_literal(&self) -> String {
+        return self.token.literal.clone();
+    }
+}
+
+#[derive(Debug)]
+pub struct BreakStatement {
+    pub token: token::Token,
+}
+
+impl Node for BreakStatement {
+    fn token_literal(&self) -> String {
+        return self.token.literal.clone();
+    }
+}
+
+#[derive(Debug)]
+pub struct ContinueStatement {
+    pub token: token::Token,
+}
+
+impl Node for ContinueStatement {
+    fn token_literal(&self) -> String {
+        return self.token.literal.clone();
+    }
+}
+
+#[derive(Debug)]
+pub struct BlockStatement {
+    pub token: token::Token,
+    pub statements: Vec<Statement>,
+}
+
+impl Node for BlockStatement {
+    fn token_literal(&self) -> String {
+        return self.token.literal.clone();
+    }
+}
 
 #[derive(Debug)]
 pub struct ExpressionStatement {
     pub token: token::Token,
-    pub value: Box<dyn Expression>,
+    pub value: Box<Expression>,
 }
 
 impl Node for ExpressionStatement {
     fn token_literal(&self) -> String {
         return self.token.literal.clone();
     }
-    fn as_any(&self) -> &dyn Any {
-        self
-    }
 }
 
-impl Statement for ExpressionStatement {}
+/*
+Where the variable a name refers to is stored, filled in by the resolver.
+Locals live in numbered slots of the environment of their scope, everything
+else is looked up by name
+*/
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Binding {
+    Global,                              //Top level declarations, imported names and builtins
+    Local { depth: usize, slot: usize }, //Slot of the environment depth scopes out from the current one
+}
 
 #[derive(Debug)]
 pub struct Identifier {
     pub token: token::Token,
     pub value: String,
+    pub binding: Cell<Binding>,
 }
 
 impl Node for Identifier {
     fn token_literal(&self) -> String {
         return self.token.literal.clone();
     }
-    fn as_any(&self) -> &dyn Any {
-        self
-    }
 }
 
-impl Expression for Identifier {}
-
 //Expression Nodes
 #[derive(Debug)]
 pub struct IntegerLiteral {
     pub token: token::Token,
-    pub value: f64,
+    pub value: i64,
 }
 
 impl Node for IntegerLiteral {
     fn token_literal(&self) -> String {
         return self.token.literal.clone();
     }
-    fn as_any(&self) -> &dyn Any {
-        self
+}
+
+//Expression Nodes
+#[derive(Debug)]
+pub struct FloatLiteral {
+    pub token: token::Token,
+    pub value: f64,
+}
+
+impl Node for FloatLiteral {
+    fn token_literal(&self) -> String {
+        return self.token.literal.clone();
     }
 }
 
-impl Expression for IntegerLiteral {}
+//Expression Nodes
+#[derive(Debug)]
+pub struct StringLiteral {
+    pub token: token::Token,
+    pub value: String,
+}
+
+impl Node for StringLiteral {
+    fn token_literal(&self) -> String {
+        return self.token.literal.clone();
+    }
+}
+
+#[derive(Debug)]
+pub enum StringPart {
+    Literal(String),
+    Expression(Box<Expression>),
+}
+
+//Expression Nodes
+#[derive(Debug)]
+pub struct InterpolatedString {
+    pub token: token::Token,
+    pub parts: Vec<StringPart>,
+}
+
+impl Node for InterpolatedString {
+    fn token_literal(&self) -> String {
+        return self.token.literal.clone();
+    }
+}
 
 //Expression Nodes
 #[derive(Debug)]
@@ -133,13 +419,8 @@ impl Node for NullLiteral {
     fn token_literal(&self) -> String {
         return String::from("null");
     }
-    fn as_any(&self) -> &dyn Any {
-        self
-    }
 }
 
-impl Expression for NullLiteral {}
-
 //Expression Nodes
 #[derive(Debug)]
 pub struct Boolean {
@@ -151,70 +432,140 @@ impl Node for Boolean {
     fn token_literal(&self) -> String {
         return self.token.literal.clone();
     }
-    fn as_any(&self) -> &dyn Any {
-        self
-    }
 }
 
-impl Expression for Boolean {}
-
 #[derive(Debug)]
 pub struct PrefixExpression {
     pub token: token::Token,
     pub operator: String,
-    pub right: Box<dyn Expression>,
+    pub right: Box<Expression>,
 }
 
 impl Node for PrefixExpression {
     fn token_literal(&self) -> String {
         return self.token.literal.clone();
     }
-
-    fn as_any(&self) -> &dyn Any {
-        self
-    }
 }
 
-impl Expression for PrefixExpression {}
-
 #[derive(Debug)]
 pub struct IfExpression {
     pub token: token::Token,
-    pub condition: Box<dyn Expression>,
-    pub consequence: Box<BlockStatement>,
-    pub alternate: Option<Box<BlockStatement>>,
+    pub condition: Box<Expression>,
+    pub consequence: Rc<Box<BlockStatement>>,
+    pub alternate: Option<Rc<Box<BlockStatement>>>,
 }
 
 impl Node for IfExpression {
     fn token_literal(&self) -> String {
         return self.token.literal.clone();
     }
-    fn as_any(&self) -> &dyn Any {
-        self
-    }
 }
 
-impl Expression for IfExpression {}
-
 //Expression Nodes
 #[derive(Debug)]
 pub struct BinaryExpression {
     pub token: token::Token,
     pub operator: String,
-    pub left: Rc<Box<dyn Expression>>,
-    pub right: Box<dyn Expression>,
+    pub left: Rc<Expression>,
+    pub right: Box<Expression>,
 }
 
 impl Node for BinaryExpression {
     fn token_literal(&self) -> String {
         return self.token.literal.clone();
     }
-    fn as_any(&self) -> &dyn Any {
-        self
+}
+
+//Expression Nodes
+#[derive(Debug)]
+pub struct ThisExpression {
+    pub token: token::Token,
+    pub binding: Cell<Binding>,
+}
+
+impl Node for ThisExpression {
+    fn token_literal(&self) -> String {
+        return self.token.literal.clone();
     }
 }
 
-impl Expression for BinaryExpression {}
+/*
+Patterns of a match arm, literals are compared by value, bindings always match
+and make the value available to the arm body
+*/
+#[derive(Debug)]
+pub enum Pattern {
+    Wildcard,
+    Literal(Box<Expression>),
+    Binding(Identifier),
+    Alternative(Vec<Pattern>),
+    Array(Vec<Pattern>),
+    Hash(Vec<(Box<Expression>, Pattern)>),
+}
+
+#[derive(Debug)]
+pub struct MatchArm {
+    pub pattern: Pattern,
+    pub body: Rc<Box<BlockStatement>>,
+}
+
+//Expression Nodes
+#[derive(Debug)]
+pub struct MatchExpression {
+    pub token: token::Token,
+    pub value: Box<Expression>,
+    pub arms: Vec<MatchArm>,
+}
+
+impl Node for MatchExpression {
+    fn token_literal(&self) -> String {
+        return self.token.literal.clone();
+    }
+}
+
+//Expression Nodes
+#[derive(Debug)]
+pub struct SuperExpression {
+    pub token: token::Token,
+    pub method: Identifier,
+    pub binding: Cell<Binding>,      //The superclass
+    pub this_binding: Cell<Binding>, //The instance the method is bound to
+}
+
+impl Node for SuperExpression {
+    fn token_literal(&self) -> String {
+        return self.token.literal.clone();
+    }
+}
+
+//Expression Nodes
+#[derive(Debug)]
+pub struct GetExpression {
+    pub token: token::Token,
+    pub object: Rc<Expression>,
+    pub name: Identifier,
+}
+
+impl Node for GetExpression {
+    fn token_literal(&self) -> String {
+        return self.token.literal.clone();
+    }
+}
+
+//Expression Nodes
+#[derive(Debug)]
+pub struct LogicalExpression {
+    pub token: token::Token,
+    pub operator: String,
+    pub left: Rc<Expression>,
+    pub right: Box<Expression>,
+}
+
+impl Node for LogicalExpression {
+    fn token_literal(&self) -> String {
+        return self.token.literal.clone();
+    }
+}
 
 //Expression Nodes
 #[derive(Debug)]
@@ -222,41 +573,113 @@ pub struct FunctionLiteral {
     pub token: token::Token,
     pub parameters: Rc<Vec<Identifier>>,
     pub body: Rc<Box<BlockStatement>>,
+    pub is_generator: bool, //A function with yield in its body returns a generator when called
+    pub is_async: bool,     //Calling an async function schedules its body and returns a promise
 }
 
 impl Node for FunctionLiteral {
     fn token_literal(&self) -> String {
         return self.token.literal.clone();
     }
-    fn as_any(&self) -> &dyn Any {
-        self
+}
+
+//Expression Nodes
+#[derive(Debug)]
+pub struct YieldExpression {
+    pub token: token::Token,
+    pub value: Option<Box<Expression>>,
+}
+
+impl Node for YieldExpression {
+    fn token_literal(&self) -> String {
+        return self.token.literal.clone();
     }
 }
 
-impl Expression for FunctionLiteral {}
+//Expression Nodes
+#[derive(Debug)]
+pub struct AwaitExpression {
+    pub token: token::Token,
+    pub value: Box<Expression>,
+}
+
+impl Node for AwaitExpression {
+    fn token_literal(&self) -> String {
+        return self.token.literal.clone();
+    }
+}
 
 //Expression Nodes
 #[derive(Debug)]
 pub struct CallExpression {
     pub token: token::Token,
-    pub funtion: Rc<Box<dyn Expression>>, //Identifier or FunctionLiteral
-    pub parameters: Rc<Vec<Box<dyn Expression>>>,
+    pub funtion: Rc<Expression>, //Identifier or FunctionLiteral
+    pub parameters: Rc<Vec<Box<Expression>>>,
 }
 
 impl Node for CallExpression {
     fn token_literal(&self) -> String {
         return self.token.literal.clone();
     }
-    fn as_any(&self) -> &dyn Any {
-        self
+}
+
+//Expression Nodes
+#[derive(Debug)]
+pub struct ArrayLiteral {
+    pub token: token::Token,
+    pub elements: Vec<Box<Expression>>,
+}
+
+impl Node for ArrayLiteral {
+    fn token_literal(&self) -> String {
+        return self.token.literal.clone();
+    }
+}
+
+//Expression Nodes
+#[derive(Debug)]
+pub struct IndexExpression {
+    pub token: token::Token,
+    pub left: Rc<Expression>,
+    pub index: Box<Expression>,
+}
+
+impl Node for IndexExpression {
+    fn token_literal(&self) -> String {
+        return self.token.literal.clone();
     }
 }
 
-impl Expression for CallExpression {}
+//Expression Nodes
+#[derive(Debug)]
+pub struct AssignExpression {
+    pub token: token::Token,
+    pub target: Rc<Expression>, //Identifier or IndexExpression
+    pub value: Box<Expression>,
+}
+
+impl Node for AssignExpression {
+    fn token_literal(&self) -> String {
+        return self.token.literal.clone();
+    }
+}
+
+//Expression Nodes
+#[derive(Debug)]
+pub struct HashLiteral {
+    pub token: token::Token,
+    pub pairs: Vec<(Box<Expression>, Box<Expression>)>,
+}
+
+impl Node for HashLiteral {
+    fn token_literal(&self) -> String {
+        return self.token.literal.clone();
+    }
+}
 
 #[derive(Debug)]
 pub struct Program {
-    pub stmts: Vec<Box<dyn Statement>>,
+    pub stmts: Vec<Statement>,
 }
 
 impl Program {}
@@ -0,0 +1,628 @@
+use std::{cell::Cell, fmt::Debug, rc::Rc};
+
+use crate::lexer::token::{Token, TokenType};
+
+use super::ast::{
+    AssignExpression, Binding, BlockStatement, Boolean, BreakStatement, CallExpression, Expression,
+    ExpressionStatement, Identifier, IfExpression, LetStatement, LogicalExpression, NullLiteral,
+    Statement, StringPart,
+};
+
+//Temporaries are named so no identifier the lexer produces can clash with them
+const TEMPORARY_PREFIX: &str = "suspend#";
+
+/*
+The evaluators can only pause a function at an await or a yield that is a
+whole statement or the value of a let or an assignment. One anywhere else is
+moved into a temporary let right before its statement and the expression reads
+the temporary instead. Operands that run before it are stored in temporaries
+too, so they are still evaluated first
+*/
+pub(crate) fn hoist_suspensions(body: BlockStatement) -> BlockStatement {
+    let mut hoister = Hoister {
+        temporaries: 0,
+        line: body.token.line,
+    };
+    return hoister.block(body);
+}
+
+struct Hoister {
+    temporaries: usize, //Temporaries declared so far in the function
+    line: usize,        //Line of the statement being rewritten, given to the nodes it adds
+}
+
+impl Hoister {
+    fn block(&mut self, block: BlockStatement) -> BlockStatement {
+        if !block_contains_suspension(&block) {
+            return block;
+        }
+        let mut statements = vec![];
+        for statement in block.statements {
+            self.statement(statement, &mut statements);
+        }
+        return BlockStatement {
+            token: block.token,
+            statements: statements,
+        };
+    }
+
+    fn shared_block(&mut self, block: Rc<Box<BlockStatement>>) -> Rc<Box<BlockStatement>> {
+        if !block_contains_suspension(&block) {
+            return block;
+        }
+        return Rc::new(Box::new(self.block(*unshare(block))));
+    }
+
+    //Push the statement and the temporaries it needs to out
+    fn statement(&mut self, statement: Statement, out: &mut Vec<Statement>) {
+        if !statement_contains_suspension(&statement) {
+            out.push(statement);
+            return;
+        }
+        self.line = statement.line();
+        match statement {
+            Statement::Let(mut let_statement) => {
+                let_statement.value = self.suspension(let_statement.value, out);
+                out.push(Statement::Let(let_statement));
+            }
+            Statement::Return(mut return_statement) => {
+                return_statement.value = self.expression(return_statement.value, out);
+                out.push(Statement::Return(return_statement));
+            }
+            Statement::Throw(mut throw_statement) => {
+                throw_statement.value = self.expression(throw_statement.value, out);
+                out.push(Statement::Throw(throw_statement));
+            }
+            Statement::While(mut while_statement) => {
+                let body = self.block(*unshare(while_statement.body));
+                if contains_suspension(&while_statement.condition) {
+                    //The condition moves to the start of the body, which leaves the loop when it is false
+                    let condition = std::mem::replace(
+                        &mut while_statement.condition,
+                        Box::new(Expression::Boolean(Boolean {
+                            token: self.token(TokenType::TRUE, "true"),
+                            value: true,
+                        })),
+                    );
+                    let mut statements = vec![];
+                    let condition = self.expression(condition, &mut statements);
+                    let exit = self.block_of(vec![Statement::Break(BreakStatement {
+                        token: self.token(TokenType::BREAK, "break"),
+                    })]);
+                    let check = Expression::If(IfExpression {
+                        token: self.token(TokenType::IF, "if"),
+                        condition: condition,
+                        consequence: Rc::new(Box::new(self.block_of(vec![]))),
+                        alternate: Some(Rc::new(Box::new(exit))),
+                    });
+                    statements.push(self.expression_statement(check));
+                    statements.extend(body.statements);
+                    while_statement.body = Rc::new(Box::new(BlockStatement {
+                        token: body.token,
+                        statements: statements,
+                    }));
+                } else {
+                    while_statement.body = Rc::new(Box::new(body));
+                }
+                out.push(Statement::While(while_statement));
+            }
+            Statement::Try(mut try_statement) => {
+                try_statement.body = self.shared_block(try_statement.body);
+                try_statement.catch_body = try_statement
+                    .catch_body
+                    .map(|catch_body| self.shared_block(catch_body));
+                try_statement.finally_body = try_statement
+                    .finally_body
+                    .map(|finally_body| self.shared_block(finally_body));
+                out.push(Statement::Try(try_statement));
+            }
+            Statement::Block(block) => out.push(Statement::Block(self.block(block))),
+            Statement::Expression(mut expression_statement) => {
+                expression_statement.value = self.discarded(expression_statement.value, out);
+                out.push(Statement::Expression(expression_statement));
+            }
+            statement => out.push(statement),
+        }
+    }
+
+    //An expression statement, the evaluators can pause its branches and its outermost await or yield
+    fn discarded(&mut self, value: Box<Expression>, out: &mut Vec<Statement>) -> Box<Expression> {
+        match *value {
+            Expression::Await(_) | Expression::Yield(_) => return self.suspension(value, out),
+            Expression::Assign(mut assign) if !contains_suspension(&assign.target) => {
+                assign.value = self.suspension(assign.value, out);
+                return Box::new(Expression::Assign(assign));
+            }
+            Expression::If(mut if_expression) => {
+                if_expression.condition = self.expression(if_expression.condition, out);
+                if_expression.consequence = self.shared_block(if_expression.consequence);
+                if_expression.alternate = if_expression
+                    .alternate
+                    .map(|alternate| self.shared_block(alternate));
+                return Box::new(Expression::If(if_expression));
+            }
+            Expression::Match(mut match_expression) => {
+                match_expression.value = self.expression(match_expression.value, out);
+                for arm in match_expression.arms.iter_mut() {
+                    let body =
+                        std::mem::replace(&mut arm.body, Rc::new(Box::new(self.block_of(vec![]))));
+                    arm.body = self.shared_block(body);
+                }
+                return Box::new(Expression::Match(match_expression));
+            }
+            value => return self.expression(Box::new(value), out),
+        }
+    }
+
+    //The value of a let or an assignment, which can be an await or a yield itself
+    fn suspension(&mut self, value: Box<Expression>, out: &mut Vec<Statement>) -> Box<Expression> {
+        match *value {
+            Expression::Await(mut await_expression) => {
+                await_expression.value = self.expression(await_expression.value, out);
+                return Box::new(Expression::Await(await_expression));
+            }
+            Expression::Yield(mut yield_expression) => {
+                yield_expression.value = yield_expression
+                    .value
+                    .map(|value| self.expression(value, out));
+                return Box::new(Expression::Yield(yield_expression));
+            }
+            _ => return self.expression(value, out),
+        }
+    }
+
+    //Rewrite the expression so it has no await or yield left, they are pushed to out
+    fn expression(&mut self, value: Box<Expression>, out: &mut Vec<Statement>) -> Box<Expression> {
+        if !contains_suspension(&value) {
+            return value;
+        }
+        match *value {
+            Expression::Await(_) | Expression::Yield(_) => {
+                let value = self.suspension(value, out);
+                return self.temporary(value, out);
+            }
+            Expression::Prefix(mut prefix) => {
+                prefix.right = self.expression(prefix.right, out);
+                return Box::new(Expression::Prefix(prefix));
+            }
+            Expression::Binary(mut binary) => {
+                let left = Box::new(unshare(binary.left));
+                let mut operands = self.operands(vec![left, binary.right], out);
+                binary.right = operands.pop().unwrap();
+                binary.left = Rc::new(*operands.pop().unwrap());
+                return Box::new(Expression::Binary(binary));
+            }
+            Expression::Logical(logical) => return self.logical(logical, out),
+            Expression::If(mut if_expression) => {
+                if_expression.condition = self.expression(if_expression.condition, out);
+                let consequence = &if_expression.consequence;
+                let alternate = &if_expression.alternate;
+                if !block_contains_suspension(consequence)
+                    && !alternate
+                        .as_ref()
+                        .is_some_and(|alternate| block_contains_suspension(alternate))
+                {
+                    return Box::new(Expression::If(if_expression));
+                }
+                let result = self.temporary(Box::new(Expression::Null(NullLiteral {})), out);
+                if_expression.consequence = self.result_block(if_expression.consequence, &result);
+                if_expression.alternate = if_expression
+                    .alternate
+                    .map(|alternate| self.result_block(alternate, &result));
+                out.push(self.expression_statement(Expression::If(if_expression)));
+                return result;
+            }
+            Expression::Match(mut match_expression) => {
+                match_expression.value = self.expression(match_expression.value, out);
+                if !match_expression
+                    .arms
+                    .iter()
+                    .any(|arm| block_contains_suspension(&arm.body))
+                {
+                    return Box::new(Expression::Match(match_expression));
+                }
+                let result = self.temporary(Box::new(Expression::Null(NullLiteral {})), out);
+                for arm in match_expression.arms.iter_mut() {
+                    let body =
+                        std::mem::replace(&mut arm.body, Rc::new(Box::new(self.block_of(vec![]))));
+                    arm.body = self.result_block(body, &result);
+                }
+                out.push(self.expression_statement(Expression::Match(match_expression)));
+                return result;
+            }
+            Expression::Call(call) => return self.call(call, out),
+            Expression::Array(mut array) => {
+                array.elements = self.operands(array.elements, out);
+                return Box::new(Expression::Array(array));
+            }
+            Expression::Hash(mut hash) => {
+                let mut values = vec![];
+                for (key, value) in hash.pairs {
+                    values.push(key);
+                    values.push(value);
+                }
+                let mut values = self.operands(values, out).into_iter();
+                hash.pairs = vec![];
+                while let (Some(key), Some(value)) = (values.next(), values.next()) {
+                    hash.pairs.push((key, value));
+                }
+                return Box::new(Expression::Hash(hash));
+            }
+            Expression::Index(mut index) => {
+                let left = Box::new(unshare(index.left));
+                let mut operands = self.operands(vec![left, index.index], out);
+                index.index = operands.pop().unwrap();
+                index.left = Rc::new(*operands.pop().unwrap());
+                return Box::new(Expression::Index(index));
+            }
+            Expression::Get(mut get) => {
+                get.object = Rc::new(*self.expression(Box::new(unshare(get.object)), out));
+                return Box::new(Expression::Get(get));
+            }
+            Expression::Assign(assign) => return self.assign(assign, out),
+            Expression::InterpolatedString(mut string) => {
+                let mut values = vec![];
+                for part in string.parts.iter_mut() {
+                    if let StringPart::Expression(value) = part {
+                        let placeholder = Box::new(Expression::Null(NullLiteral {}));
+                        values.push(std::mem::replace(value, placeholder));
+                    }
+                }
+                let mut values = self.operands(values, out).into_iter();
+                for part in string.parts.iter_mut() {
+                    if let StringPart::Expression(value) = part {
+                        *value = values.next().unwrap();
+                    }
+                }
+                return Box::new(Expression::InterpolatedString(string));
+            }
+            value => return Box::new(value),
+        }
+    }
+
+    /*
+    Rewrite expressions that run one after another. The ones before the last
+    await or yield are stored in temporaries first, unless they always give the
+    same value, and the ones after it are left where they are
+    */
+    fn operands(
+        &mut self,
+        values: Vec<Box<Expression>>,
+        out: &mut Vec<Statement>,
+    ) -> Vec<Box<Expression>> {
+        let last = match values.iter().rposition(|value| contains_suspension(value)) {
+            Some(last) => last,
+            None => return values,
+        };
+        let mut operands = vec![];
+        for (index, value) in values.into_iter().enumerate() {
+            if index < last {
+                let value = self.expression(value, out);
+                if is_settled(&value) {
+                    operands.push(value);
+                } else {
+                    operands.push(self.temporary(value, out));
+                }
+            } else if index == last {
+                operands.push(self.expression(value, out));
+            } else {
+                operands.push(value);
+            }
+        }
+        return operands;
+    }
+
+    //The right side only runs when the left one does not decide the result
+    fn logical(
+        &mut self,
+        mut logical: LogicalExpression,
+        out: &mut Vec<Statement>,
+    ) -> Box<Expression> {
+        let left = self.expression(Box::new(unshare(logical.left)), out);
+        if !contains_suspension(&logical.right) {
+            logical.left = Rc::new(*left);
+            return Box::new(Expression::Logical(logical));
+        }
+        let result = self.temporary(left, out);
+        let assign = self.assignment(&result, logical.right);
+        let right = self.block_of(vec![self.expression_statement(assign)]);
+        let right = Rc::new(Box::new(self.block(right)));
+        let (consequence, alternate) = if logical.operator == "&&" {
+            (right, None)
+        } else {
+            (Rc::new(Box::new(self.block_of(vec![]))), Some(right))
+        };
+        let check = Expression::If(IfExpression {
+            token: self.token(TokenType::IF, "if"),
+            condition: result_reference(&result),
+            consequence: consequence,
+            alternate: alternate,
+        });
+        out.push(self.expression_statement(check));
+        return result;
+    }
+
+    //A method call keeps its receiver, so only the object it is called on is stored
+    fn call(&mut self, mut call: CallExpression, out: &mut Vec<Statement>) -> Box<Expression> {
+        let callee = unshare(call.funtion);
+        let mut values = unshare(call.parameters);
+        match callee {
+            Expression::Get(mut get) => {
+                values.insert(0, Box::new(unshare(get.object)));
+                let mut operands = self.operands(values, out);
+                get.object = Rc::new(*operands.remove(0));
+                call.funtion = Rc::new(Expression::Get(get));
+                call.parameters = Rc::new(operands);
+            }
+            Expression::Identifier(_) => {
+                call.funtion = Rc::new(callee);
+                call.parameters = Rc::new(self.operands(values, out));
+            }
+            callee => {
+                values.insert(0, Box::new(callee));
+                let mut operands = self.operands(values, out);
+                call.funtion = Rc::new(*operands.remove(0));
+                call.parameters = Rc::new(operands);
+            }
+        }
+        return Box::new(Expression::Call(call));
+    }
+
+    fn assign(
+        &mut self,
+        mut assign: AssignExpression,
+        out: &mut Vec<Statement>,
+    ) -> Box<Expression> {
+        match unshare(assign.target) {
+            Expression::Index(mut index) => {
+                let left = Box::new(unshare(index.left));
+                let mut operands = self.operands(vec![left, index.index, assign.value], out);
+                assign.value = operands.pop().unwrap();
+                index.index = operands.pop().unwrap();
+                index.left = Rc::new(*operands.pop().unwrap());
+                assign.target = Rc::new(Expression::Index(index));
+            }
+            Expression::Get(mut get) => {
+                let object = Box::new(unshare(get.object));
+                let mut operands = self.operands(vec![object, assign.value], out);
+                assign.value = operands.pop().unwrap();
+                get.object = Rc::new(*operands.pop().unwrap());
+                assign.target = Rc::new(Expression::Get(get));
+            }
+            target => {
+                assign.value = self.expression(assign.value, out);
+                assign.target = Rc::new(target);
+            }
+        }
+        return Box::new(Expression::Assign(assign));
+    }
+
+    //The last value of the branch is stored in result, which the rewritten expression reads
+    fn result_block(
+        &mut self,
+        block: Rc<Box<BlockStatement>>,
+        result: &Expression,
+    ) -> Rc<Box<BlockStatement>> {
+        let mut block = *unshare(block);
+        match block.statements.pop() {
+            Some(Statement::Expression(expression_statement)) => {
+                let assign = self.assignment(result, expression_statement.value);
+                block
+                    .statements
+                    .push(Statement::Expression(ExpressionStatement {
+                        token: expression_statement.token,
+                        value: Box::new(assign),
+                    }));
+            }
+            Some(Statement::Let(let_statement)) => {
+                let name = let_statement.identifier.value.clone();
+                block.statements.push(Statement::Let(let_statement));
+                let assign = self.assignment(result, Box::new(self.identifier(&name)));
+                block.statements.push(self.expression_statement(assign));
+            }
+            Some(statement) => block.statements.push(statement),
+            None => {}
+        }
+        return Rc::new(Box::new(self.block(block)));
+    }
+
+    //Declare a new temporary holding value and return an expression reading it
+    fn temporary(&mut self, value: Box<Expression>, out: &mut Vec<Statement>) -> Box<Expression> {
+        let name = format!("{}{}", TEMPORARY_PREFIX, self.temporaries);
+        self.temporaries += 1;
+        out.push(Statement::Let(LetStatement {
+            token: self.token(TokenType::LET, "let"),
+            identifier: self.identifier_node(&name),
+            value: value,
+            doc: None,
+        }));
+        return Box::new(self.identifier(&name));
+    }
+
+    fn assignment(&self, target: &Expression, value: Box<Expression>) -> Expression {
+        return Expression::Assign(AssignExpression {
+            token: self.token(TokenType::ASSIGN, "="),
+            target: Rc::new(*result_reference(target)),
+            value: value,
+        });
+    }
+
+    fn expression_statement(&self, value: Expression) -> Statement {
+        return Statement::Expression(ExpressionStatement {
+            token: self.token(TokenType::IDENTIFIER, ""),
+            value: Box::new(value),
+        });
+    }
+
+    fn block_of(&self, statements: Vec<Statement>) -> BlockStatement {
+        return BlockStatement {
+            token: self.token(TokenType::LBRACE, "{"),
+            statements: statements,
+        };
+    }
+
+    fn identifier(&self, name: &str) -> Expression {
+        return Expression::Identifier(self.identifier_node(name));
+    }
+
+    fn identifier_node(&self, name: &str) -> Identifier {
+        return Identifier {
+            token: self.token(TokenType::IDENTIFIER, name),
+            value: String::from(name),
+            binding: Cell::new(Binding::Global),
+        };
+    }
+
+    fn token(&self, token_type: TokenType, literal: &str) -> Token {
+        return Token {
+            token_type: token_type,
+            literal: String::from(literal),
+            line: self.line,
+        };
+    }
+}
+
+//Another read of a temporary, every read needs its own node for the resolver to fill in
+fn result_reference(result: &Expression) -> Box<Expression> {
+    match result {
+        Expression::Identifier(identifier) => {
+            return Box::new(Expression::Identifier(Identifier {
+                token: identifier.token.clone(),
+                value: identifier.value.clone(),
+                binding: Cell::new(Binding::Global),
+            }));
+        }
+        _ => unreachable!("Temporaries are read through identifiers"),
+    }
+}
+
+//Nodes are only shared once the function is parsed, so its body still owns all of them
+fn unshare<T: Debug>(node: Rc<T>) -> T {
+    return Rc::try_unwrap(node).expect("Syntax tree node shared while parsing");
+}
+
+//Expressions whose value does not change when other code runs before them
+fn is_settled(value: &Expression) -> bool {
+    match value {
+        Expression::Integer(_)
+        | Expression::Float(_)
+        | Expression::String(_)
+        | Expression::Null(_)
+        | Expression::Boolean(_)
+        | Expression::Function(_)
+        | Expression::This(_)
+        | Expression::Super(_) => return true,
+        Expression::Identifier(identifier) => {
+            return identifier.value.starts_with(TEMPORARY_PREFIX)
+        }
+        _ => return false,
+    }
+}
+
+//Functions inside are left out, their awaits and yields pause them and not the enclosing function
+fn contains_suspension(value: &Expression) -> bool {
+    match value {
+        Expression::Await(_) | Expression::Yield(_) => return true,
+        Expression::Identifier(_)
+        | Expression::Integer(_)
+        | Expression::Float(_)
+        | Expression::String(_)
+        | Expression::Null(_)
+        | Expression::Boolean(_)
+        | Expression::Function(_)
+        | Expression::This(_)
+        | Expression::Super(_) => return false,
+        Expression::InterpolatedString(string) => {
+            return string.parts.iter().any(|part| match part {
+                StringPart::Expression(value) => contains_suspension(value),
+                StringPart::Literal(_) => false,
+            });
+        }
+        Expression::Prefix(prefix) => return contains_suspension(&prefix.right),
+        Expression::Binary(binary) => {
+            return contains_suspension(&binary.left) || contains_suspension(&binary.right)
+        }
+        Expression::Logical(logical) => {
+            return contains_suspension(&logical.left) || contains_suspension(&logical.right)
+        }
+        Expression::If(if_expression) => {
+            return contains_suspension(&if_expression.condition)
+                || block_contains_suspension(&if_expression.consequence)
+                || if_expression
+                    .alternate
+                    .as_ref()
+                    .is_some_and(|alternate| block_contains_suspension(alternate));
+        }
+        Expression::Match(match_expression) => {
+            return contains_suspension(&match_expression.value)
+                || match_expression
+                    .arms
+                    .iter()
+                    .any(|arm| block_contains_suspension(&arm.body));
+        }
+        Expression::Call(call) => {
+            return contains_suspension(&call.funtion)
+                || call
+                    .parameters
+                    .iter()
+                    .any(|value| contains_suspension(value));
+        }
+        Expression::Array(array) => {
+            return array
+                .elements
+                .iter()
+                .any(|value| contains_suspension(value))
+        }
+        Expression::Hash(hash) => {
+            return hash
+                .pairs
+                .iter()
+                .any(|(key, value)| contains_suspension(key) || contains_suspension(value));
+        }
+        Expression::Index(index) => {
+            return contains_suspension(&index.left) || contains_suspension(&index.index)
+        }
+        Expression::Assign(assign) => {
+            return contains_suspension(&assign.target) || contains_suspension(&assign.value)
+        }
+        Expression::Get(get) => return contains_suspension(&get.object),
+    }
+}
+
+fn block_contains_suspension(block: &BlockStatement) -> bool {
+    return block.statements.iter().any(statement_contains_suspension);
+}
+
+fn statement_contains_suspension(statement: &Statement) -> bool {
+    match statement {
+        Statement::Let(let_statement) => return contains_suspension(&let_statement.value),
+        Statement::Return(return_statement) => return contains_suspension(&return_statement.value),
+        Statement::Throw(throw_statement) => return contains_suspension(&throw_statement.value),
+        Statement::While(while_statement) => {
+            return contains_suspension(&while_statement.condition)
+                || block_contains_suspension(&while_statement.body);
+        }
+        Statement::Try(try_statement) => {
+            return block_contains_suspension(&try_statement.body)
+                || try_statement
+                    .catch_body
+                    .as_ref()
+                    .is_some_and(|body| block_contains_suspension(body))
+                || try_statement
+                    .finally_body
+                    .as_ref()
+                    .is_some_and(|body| block_contains_suspension(body));
+        }
+        Statement::Export(export_statement) => {
+            return statement_contains_suspension(&export_statement.statement)
+        }
+        Statement::Block(block) => return block_contains_suspension(block),
+        Statement::Expression(expression_statement) => {
+            return contains_suspension(&expression_statement.value)
+        }
+        Statement::Class(_)
+        | Statement::Import(_)
+        | Statement::Break(_)
+        | Statement::Continue(_) => return false,
+    }
+}
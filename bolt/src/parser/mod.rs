@@ -1,3 +1,4 @@
 pub mod ast;
+mod hoist;
 pub mod parser;
 mod types;
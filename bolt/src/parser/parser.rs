@@ -1,8 +1,10 @@
+use std::cell::Cell;
 use std::collections::HashMap;
 use std::rc::Rc;
 
-use super::ast::{self, BlockStatement};
+use super::ast::{self, Binding, BlockStatement};
 use super::ast::{Expression, Statement};
+use super::hoist::hoist_suspensions;
 use super::types::{PrecedenceValue, Precedences};
 use crate::error::{BoltError, BoltErrorType, ParseError};
 use crate::lexer::lexer;
@@ -14,6 +16,13 @@ pub struct Parser<'a> {
     curr_token: Option<token::Token>,
     peek_token: Option<token::Token>,
     precedences: HashMap<token::TokenType, PrecedenceValue>,
+    loop_depth: usize, //Number of enclosing loops, used to validate break and continue
+    block_depth: usize, //Number of enclosing blocks, exports are only allowed at the top level
+    classes: Vec<bool>, //Enclosing classes and whether they have a superclass, used to validate this and super
+    curr_doc: Option<String>, //Doc comment written right before the current token
+    peek_doc: Option<String>,
+    generators: Vec<bool>, //Enclosing functions and whether their body contains a yield
+    async_functions: Vec<bool>, //Enclosing functions and whether they are async
 }
 
 impl<'a> Parser<'a> {
@@ -23,7 +32,9 @@ impl<'a> Parser<'a> {
             curr_token: None,
             peek_token: None,
             precedences: HashMap::from([
-                (TokenType::ASSIGN, Precedences::EQUALS as PrecedenceValue),
+                (TokenType::ASSIGN, Precedences::ASSIGN as PrecedenceValue),
+                (TokenType::OR, Precedences::OR as PrecedenceValue),
+                (TokenType::AND, Precedences::AND as PrecedenceValue),
                 (TokenType::NOTEQ, Precedences::EQUALS as PrecedenceValue),
                 (TokenType::EQ, Precedences::EQUALS as PrecedenceValue),
                 (TokenType::LT, Precedences::LESSGREATER as PrecedenceValue),
@@ -35,7 +46,16 @@ impl<'a> Parser<'a> {
                 (TokenType::SLASH, Precedences::PRODUCT as PrecedenceValue),
                 (TokenType::ASTERISK, Precedences::PRODUCT as PrecedenceValue),
                 (TokenType::LPAREN, Precedences::CALL as PrecedenceValue),
+                (TokenType::LBRACKET, Precedences::INDEX as PrecedenceValue),
+                (TokenType::DOT, Precedences::CALL as PrecedenceValue),
             ]),
+            loop_depth: 0,
+            block_depth: 0,
+            classes: vec![],
+            curr_doc: None,
+            peek_doc: None,
+            generators: vec![],
+            async_functions: vec![],
         };
         //Move 2 steps to make the first token as current token
         parser.next_token();
@@ -50,19 +70,40 @@ impl<'a> Parser<'a> {
             None => peek_token = None,
         }
         self.curr_token = peek_token;
-        self.peek_token = Some(self.lexer.next_token());
+        self.curr_doc = self.peek_doc.take();
+
+        //Doc comments are not part of the grammar, they are kept for the token that follows them
+        let mut doc: Vec<String> = vec![];
+        let mut token = self.lexer.next_token();
+        while token.token_type == TokenType::DOC {
+            doc.push(token.literal);
+            token = self.lexer.next_token();
+        }
+        if !doc.is_empty() {
+            self.peek_doc = Some(doc.join("\n"));
+        }
+        self.peek_token = Some(token);
     }
 
     pub fn parse_program(&mut self) -> Result<ast::Program, ParseError> {
         let mut program = ast::Program { stmts: vec![] };
         loop {
-            let statement: Result<Box<dyn Statement>, ParseError> = self.parse_statement();
+            let statement: Result<Statement, ParseError> = self.parse_statement();
 
             match statement {
                 Ok(value) => {
                     program.stmts.push(value);
                 }
                 Err(e) => match e.get_type() {
+                    //A malformed literal explains the failure better than the parser can
+                    _ if !self.lexer.get_errors().is_empty() => {
+                        let error = &self.lexer.get_errors()[0];
+                        return Err(ParseError::new(
+                            error.get_message(),
+                            Some(error.get_type()),
+                            error.get_line(),
+                        ));
+                    }
                     BoltErrorType::EOF => {
                         //Break the loop for EOF
                         break;
@@ -76,30 +117,458 @@ impl<'a> Parser<'a> {
         Ok(program)
     }
 
-    fn parse_statement(&mut self) -> Result<Box<dyn ast::Statement>, ParseError> {
+    fn parse_statement(&mut self) -> Result<Statement, ParseError> {
         let curr_token = self.get_current_token()?;
         match curr_token.token_type {
             token::TokenType::LET => return self.parse_let_statement(),
             token::TokenType::RETURN => return self.parse_return_statement(),
+            token::TokenType::WHILE => return self.parse_while_statement(),
+            token::TokenType::CLASS => return self.parse_class_statement(),
+            token::TokenType::THROW => return self.parse_throw_statement(),
+            token::TokenType::TRY => return self.parse_try_statement(),
+            token::TokenType::IMPORT => return self.parse_import_statement(),
+            token::TokenType::EXPORT => return self.parse_export_statement(),
+            token::TokenType::BREAK | token::TokenType::CONTINUE => {
+                return self.parse_loop_control_statement()
+            }
             _ => return self.parse_expression_statement(),
         }
     }
 
-    fn parse_return_statement(&mut self) -> Result<Box<dyn ast::Statement>, ParseError> {
+    fn parse_return_statement(&mut self) -> Result<Statement, ParseError> {
         //Get the Return Token and skip it
         let current_token = self.get_current_token_and_skip()?;
         let expr = self.parse_expression(self.get_precedence_value("LOWEST"))?;
 
-        self.skip_peek_semicolon_token();
+        //Move past the expression and the optional semicolon
+        self.next_token();
+        self.skip_current_semicolon_token();
 
-        Ok(Box::new(ast::ReturnStatement {
+        Ok(Statement::Return(ast::ReturnStatement {
             token: current_token.clone(),
             value: expr,
         }))
     }
 
-    fn parse_let_statement(&mut self) -> Result<Box<dyn ast::Statement>, ParseError> {
+    fn parse_while_statement(&mut self) -> Result<Statement, ParseError> {
+        let current_token = self.get_current_token()?;
+        if !self.expect_peek_token_with_type(TokenType::LPAREN) {
+            return Err(ParseError::new(
+                String::from(format!("Expected to have ( at line {}", current_token.line)),
+                None,
+                Some(current_token.line),
+            ));
+        };
+
+        //Skip the LPAREN Token
+        self.next_token();
+
+        let condition = self.parse_expression(self.get_precedence_value("LOWEST"))?;
+
+        self.next_token();
+
+        if !self.check_current_token_match(TokenType::RPAREN) {
+            return Err(ParseError::new(
+                String::from(format!("Expected to have ) at line {}", current_token.line)),
+                None,
+                Some(current_token.line),
+            ));
+        };
+
+        if !self.expect_peek_token_with_type(TokenType::LBRACE) {
+            return Err(ParseError::new(
+                String::from(format!(
+                    "Expected to have L Brace at line {} but found something else",
+                    current_token.line
+                )),
+                None,
+                Some(current_token.line),
+            ));
+        };
+
+        //Skip the LEFTBRACE Token
+        self.next_token();
+        self.loop_depth += 1;
+        let body = self.parse_block_statement();
+        self.loop_depth -= 1;
+        let body = body?;
+
+        //Move past the closing brace and the optional semicolon
+        self.next_token();
+        self.skip_current_semicolon_token();
+
+        Ok(Statement::While(ast::WhileStatement {
+            token: current_token,
+            condition: condition,
+            body: Rc::new(body),
+        }))
+    }
+
+    fn parse_throw_statement(&mut self) -> Result<Statement, ParseError> {
+        //Get the Throw Token and skip it
+        let current_token = self.get_current_token_and_skip()?;
+        let value = self.parse_expression(self.get_precedence_value("LOWEST"))?;
+
+        //Move past the expression and the optional semicolon
+        self.next_token();
+        self.skip_current_semicolon_token();
+
+        Ok(Statement::Throw(ast::ThrowStatement {
+            token: current_token,
+            value: value,
+        }))
+    }
+
+    fn parse_try_statement(&mut self) -> Result<Statement, ParseError> {
+        let current_token = self.get_current_token()?;
+        let body = self.parse_braced_block(&current_token)?;
+
+        let mut catch_parameter = None;
+        let mut catch_body = None;
+        if self.expect_peek_token_with_type(TokenType::CATCH) {
+            let catch_token = self.get_current_token()?;
+            if !self.expect_peek_token_with_type(TokenType::LPAREN)
+                || !self.expect_peek_token_with_type(TokenType::IDENTIFIER)
+            {
+                return Err(ParseError::new(
+                    String::from(format!(
+                        "Expected (name) after catch at line {}",
+                        catch_token.line
+                    )),
+                    None,
+                    Some(catch_token.line),
+                ));
+            }
+            let parameter_token = self.get_current_token()?;
+            catch_parameter = Some(ast::Identifier {
+                token: parameter_token.clone(),
+                value: parameter_token.literal.clone(),
+                binding: Cell::new(Binding::Global),
+            });
+            if !self.expect_peek_token_with_type(TokenType::RPAREN) {
+                return Err(ParseError::new(
+                    String::from(format!("Expected to have ) at line {}", catch_token.line)),
+                    None,
+                    Some(catch_token.line),
+                ));
+            }
+            catch_body = Some(self.parse_braced_block(&catch_token)?);
+        }
+
+        let mut finally_body = None;
+        if self.expect_peek_token_with_type(TokenType::FINALLY) {
+            let finally_token = self.get_current_token()?;
+            finally_body = Some(self.parse_braced_block(&finally_token)?);
+        }
+
+        if catch_body.is_none() && finally_body.is_none() {
+            return Err(ParseError::new(
+                String::from(format!(
+                    "Expected catch or finally after try at line {}",
+                    current_token.line
+                )),
+                None,
+                Some(current_token.line),
+            ));
+        }
+
+        //Move past the closing brace and the optional semicolon
+        self.next_token();
+        self.skip_current_semicolon_token();
+
+        Ok(Statement::Try(ast::TryStatement {
+            token: current_token,
+            body: Rc::new(body),
+            catch_parameter: catch_parameter,
+            catch_body: catch_body.map(Rc::new),
+            finally_body: finally_body.map(Rc::new),
+        }))
+    }
+
+    //Parse the block following the current token, the block ends on the closing brace
+    fn parse_braced_block(
+        &mut self,
+        current_token: &token::Token,
+    ) -> Result<Box<BlockStatement>, ParseError> {
+        if !self.expect_peek_token_with_type(TokenType::LBRACE) {
+            return Err(ParseError::new(
+                String::from(format!(
+                    "Expected to have L Brace at line {} but found something else",
+                    current_token.line
+                )),
+                None,
+                Some(current_token.line),
+            ));
+        }
+        //Skip the LEFTBRACE Token
+        self.next_token();
+        return self.parse_block_statement();
+    }
+
+    fn parse_class_statement(&mut self) -> Result<Statement, ParseError> {
+        let current_token = self.get_current_token()?;
+        if !self.expect_peek_token_with_type(TokenType::IDENTIFIER) {
+            return Err(ParseError::new(
+                String::from(format!(
+                    "Expected a class name at line {}",
+                    current_token.line
+                )),
+                None,
+                Some(current_token.line),
+            ));
+        }
+        let name_token = self.get_current_token()?;
+        let name = ast::Identifier {
+            token: name_token.clone(),
+            value: name_token.literal.clone(),
+            binding: Cell::new(Binding::Global),
+        };
+
+        let mut superclass = None;
+        if self.expect_peek_token_with_type(TokenType::LT) {
+            if !self.expect_peek_token_with_type(TokenType::IDENTIFIER) {
+                return Err(ParseError::new(
+                    String::from(format!(
+                        "Expected a superclass name at line {}",
+                        current_token.line
+                    )),
+                    None,
+                    Some(current_token.line),
+                ));
+            }
+            let superclass_token = self.get_current_token()?;
+            if superclass_token.literal == name.value {
+                return Err(ParseError::new(
+                    String::from(format!(
+                        "A class can not inherit from itself at line {}",
+                        current_token.line
+                    )),
+                    None,
+                    Some(current_token.line),
+                ));
+            }
+            superclass = Some(ast::Identifier {
+                token: superclass_token.clone(),
+                value: superclass_token.literal.clone(),
+                binding: Cell::new(Binding::Global),
+            });
+        }
+
+        if !self.expect_peek_token_with_type(TokenType::LBRACE) {
+            return Err(ParseError::new(
+                String::from(format!(
+                    "Expected to have L Brace at line {} but found something else",
+                    current_token.line
+                )),
+                None,
+                Some(current_token.line),
+            ));
+        }
+        //Skip the LBRACE Token
+        self.next_token();
+
+        self.classes.push(superclass.is_some());
+        let methods = self.parse_class_methods();
+        self.classes.pop();
+        let methods = methods?;
+
+        //Move past the closing brace and the optional semicolon
+        self.next_token();
+        self.skip_current_semicolon_token();
+
+        Ok(Statement::Class(ast::ClassStatement {
+            token: current_token,
+            name: name,
+            superclass: superclass,
+            methods: methods,
+        }))
+    }
+
+    /*
+    Methods are declared as name(parameters) { body }, optionally prefixed with
+    async, and the list ends on the closing brace
+    */
+    fn parse_class_methods(&mut self) -> Result<Vec<ast::Method>, ParseError> {
+        let mut methods = vec![];
+        loop {
+            self.skip_current_semicolon_token();
+            let mut current_token = self.get_current_token()?;
+            let doc = self.curr_doc.take();
+            let is_async = current_token.token_type == TokenType::ASYNC;
+            if is_async {
+                if !self.expect_peek_token_with_type(TokenType::IDENTIFIER) {
+                    return Err(ParseError::new(
+                        String::from(format!(
+                            "Expected a method name after async at line {}",
+                            current_token.line
+                        )),
+                        None,
+                        Some(current_token.line),
+                    ));
+                }
+                current_token = self.get_current_token()?;
+            }
+            match current_token.token_type {
+                TokenType::RBRACE => break,
+                TokenType::IDENTIFIER => {
+                    let function = self.parse_function(current_token.clone(), is_async)?;
+                    if current_token.literal == "init" && (function.is_generator || is_async) {
+                        let kind = if is_async { "async" } else { "a generator" };
+                        return Err(ParseError::new(
+                            String::from(format!(
+                                "init can not be {} at line {}",
+                                kind, current_token.line
+                            )),
+                            None,
+                            Some(current_token.line),
+                        ));
+                    }
+                    methods.push(ast::Method {
+                        name: ast::Identifier {
+                            token: current_token.clone(),
+                            value: current_token.literal.clone(),
+                            binding: Cell::new(Binding::Global),
+                        },
+                        function: function,
+                        doc: doc,
+                    });
+                    //Skip the closing brace of the method body
+                    self.next_token();
+                }
+                TokenType::EOF => {
+                    return Err(ParseError::new(
+                        String::from(format!(
+                            "Expected }} at the end of the class at line {}",
+                            current_token.line
+                        )),
+                        None,
+                        Some(current_token.line),
+                    ));
+                }
+                _ => {
+                    return Err(ParseError::new(
+                        String::from(format!(
+                            "Expected a method declaration at line {}",
+                            current_token.line
+                        )),
+                        None,
+                        Some(current_token.line),
+                    ));
+                }
+            }
+        }
+        Ok(methods)
+    }
+
+    fn parse_import_statement(&mut self) -> Result<Statement, ParseError> {
+        let current_token = self.get_current_token()?;
+        if !self.expect_peek_token_with_type(TokenType::STRING) {
+            return Err(ParseError::new(
+                String::from(format!(
+                    "Expected a module path after import at line {}",
+                    current_token.line
+                )),
+                None,
+                Some(current_token.line),
+            ));
+        }
+        let path = self.get_current_token()?.literal;
+
+        let mut alias = None;
+        if self.expect_peek_token_with_type(TokenType::AS) {
+            if !self.expect_peek_token_with_type(TokenType::IDENTIFIER) {
+                return Err(ParseError::new(
+                    String::from(format!(
+                        "Expected a name after as at line {}",
+                        current_token.line
+                    )),
+                    None,
+                    Some(current_token.line),
+                ));
+            }
+            let identifier_token = self.get_current_token()?;
+            alias = Some(ast::Identifier {
+                token: identifier_token.clone(),
+                value: identifier_token.literal.clone(),
+                binding: Cell::new(Binding::Global),
+            });
+        }
+
+        //Move past the path or alias and the optional semicolon
+        self.next_token();
+        self.skip_current_semicolon_token();
+
+        Ok(Statement::Import(ast::ImportStatement {
+            token: current_token,
+            path: path,
+            alias: alias,
+        }))
+    }
+
+    fn parse_export_statement(&mut self) -> Result<Statement, ParseError> {
+        let current_token = self.get_current_token()?;
+        if self.block_depth != 0 {
+            return Err(ParseError::new(
+                String::from(format!(
+                    "export is only allowed at the top level at line {}",
+                    current_token.line
+                )),
+                None,
+                Some(current_token.line),
+            ));
+        }
+        //A doc comment before export belongs to the exported let
+        let doc = self.curr_doc.take();
+        if !self.expect_peek_token_with_type(TokenType::LET) {
+            return Err(ParseError::new(
+                String::from(format!(
+                    "Expected let after export at line {}",
+                    current_token.line
+                )),
+                None,
+                Some(current_token.line),
+            ));
+        }
+        if self.curr_doc.is_none() {
+            self.curr_doc = doc;
+        }
+        let statement = self.parse_let_statement()?;
+        Ok(Statement::Export(ast::ExportStatement {
+            token: current_token,
+            statement: Box::new(statement),
+        }))
+    }
+
+    fn parse_loop_control_statement(&mut self) -> Result<Statement, ParseError> {
+        let current_token = self.get_current_token()?;
+        if self.loop_depth == 0 {
+            return Err(ParseError::new(
+                String::from(format!(
+                    "{} outside of a loop at line {}",
+                    current_token.literal, current_token.line
+                )),
+                None,
+                Some(current_token.line),
+            ));
+        }
+
+        //Move past the keyword and the optional semicolon
+        self.next_token();
+        self.skip_current_semicolon_token();
+
+        if current_token.token_type == TokenType::BREAK {
+            return Ok(Statement::Break(ast::BreakStatement {
+                token: current_token,
+            }));
+        }
+        Ok(Statement::Continue(ast::ContinueStatement {
+            token: current_token,
+        }))
+    }
+
+    fn parse_let_statement(&mut self) -> Result<Statement, ParseError> {
         let token = self.get_current_token()?;
+        let doc = self.curr_doc.take();
         if !self.expect_peek_token_with_type(token::TokenType::IDENTIFIER) {
             return Err(ParseError::new(
                 String::from(format!(
@@ -115,6 +584,7 @@ impl<'a> Parser<'a> {
         let identifier = ast::Identifier {
             token: identifier_token.clone(),
             value: identifier_token.literal.clone(),
+            binding: Cell::new(Binding::Global),
         };
         if !self.expect_peek_token_with_type(token::TokenType::ASSIGN) {
             return Err(ParseError::new(
@@ -131,18 +601,23 @@ impl<'a> Parser<'a> {
             token: token,
             identifier: identifier,
             value: expression,
+            doc: doc,
         };
+        //Move past the expression and the optional semicolon
+        self.next_token();
         self.skip_current_semicolon_token();
 
-        Ok(Box::new(stmt))
+        Ok(Statement::Let(stmt))
     }
 
-    fn parse_expression_statement(&mut self) -> Result<Box<dyn ast::Statement>, ParseError> {
+    fn parse_expression_statement(&mut self) -> Result<Statement, ParseError> {
         let current_token = self.get_current_token()?;
         match self.parse_expression(self.get_precedence_value("LOWEST")) {
             Ok(expr) => {
+                //Move past the expression and the optional semicolon
                 self.next_token();
-                return Ok(Box::new(ast::ExpressionStatement {
+                self.skip_current_semicolon_token();
+                return Ok(Statement::Expression(ast::ExpressionStatement {
                     token: current_token,
                     value: expr,
                 }));
@@ -153,29 +628,76 @@ impl<'a> Parser<'a> {
         }
     }
 
-    fn parse_expression(
-        &mut self,
-        precedence: usize,
-    ) -> Result<Box<dyn ast::Expression>, ParseError> {
+    fn parse_expression(&mut self, precedence: usize) -> Result<Box<Expression>, ParseError> {
         let current_token = self.get_current_token()?;
 
         //Parse all prefix expresssions
-        let mut left_expr: Box<dyn Expression> = match current_token.token_type {
+        let mut left_expr: Box<Expression> = match current_token.token_type {
             TokenType::INT => {
-                if let Ok(float_value) = current_token.literal.parse::<f64>() {
-                    Box::new(ast::IntegerLiteral {
+                if let Some(int_value) = parse_integer(&current_token.literal) {
+                    Box::new(Expression::Integer(ast::IntegerLiteral {
                         token: current_token.clone(),
-                        value: float_value,
-                    })
+                        value: int_value,
+                    }))
                 } else {
+                    return Err(ParseError::new(
+                        String::from(format!(
+                            "Integer literal {} is too large at line {}",
+                            current_token.literal, current_token.line
+                        )),
+                        None,
+                        Some(current_token.line),
+                    ));
+                }
+            }
+            TokenType::FLOAT => match current_token.literal.replace('_', "").parse::<f64>() {
+                Ok(float_value) if float_value.is_finite() => {
+                    Box::new(Expression::Float(ast::FloatLiteral {
+                        token: current_token.clone(),
+                        value: float_value,
+                    }))
+                }
+                Ok(_) => {
+                    return Err(ParseError::new(
+                        String::from(format!(
+                            "Float literal {} is too large at line {}",
+                            current_token.literal, current_token.line
+                        )),
+                        None,
+                        Some(current_token.line),
+                    ));
+                }
+                Err(_) => {
                     return Err(ParseError::new(
                         String::from("Error converting number to float"),
                         None,
                         Some(current_token.line),
                     ));
                 }
+            },
+            TokenType::STRING => Box::new(Expression::String(ast::StringLiteral {
+                token: current_token.clone(),
+                value: current_token.literal.clone(),
+            })),
+            TokenType::TEMPLATE => self.parse_interpolated_string()?,
+            TokenType::NULL => Box::new(Expression::Null(ast::NullLiteral {})),
+            TokenType::THIS => {
+                if self.classes.is_empty() {
+                    return Err(ParseError::new(
+                        String::from(format!(
+                            "this outside of a class at line {}",
+                            current_token.line
+                        )),
+                        None,
+                        Some(current_token.line),
+                    ));
+                }
+                Box::new(Expression::This(ast::ThisExpression {
+                    token: current_token.clone(),
+                    binding: Cell::new(Binding::Global),
+                }))
             }
-            TokenType::NULL => Box::new(ast::NullLiteral {}),
+            TokenType::SUPER => self.parse_super_expression()?,
             TokenType::IDENTIFIER => match self.parse_identifier_expression() {
                 Ok(identifier_expression) => identifier_expression,
                 Err(e) => {
@@ -196,18 +718,33 @@ impl<'a> Parser<'a> {
                 Ok(boolean_expression) => boolean_expression,
                 Err(e) => return Err(e),
             },
-            TokenType::IF => match self.parse_if_expression() {
-                Ok(expr) => expr,
-                Err(e) => {
-                    return Err(e);
+            TokenType::YIELD => self.parse_yield_expression()?,
+            TokenType::AWAIT => self.parse_await_expression()?,
+            TokenType::ASYNC => {
+                if !self.expect_peek_token_with_type(TokenType::FUNCTION) {
+                    return Err(ParseError::new(
+                        String::from(format!(
+                            "Expected fn after async at line {}",
+                            current_token.line
+                        )),
+                        None,
+                        Some(current_token.line),
+                    ));
                 }
-            },
+                Box::new(Expression::Function(
+                    self.parse_function(current_token.clone(), true)?,
+                ))
+            }
+            TokenType::MATCH => self.parse_match_expression()?,
+            TokenType::IF => self.parse_if_expression()?,
             TokenType::FUNCTION => match self.parse_function_literal() {
                 Ok(expr) => expr,
                 Err(e) => {
                     return Err(e);
                 }
             },
+            TokenType::LBRACKET => self.parse_array_literal()?,
+            TokenType::LBRACE => self.parse_hash_literal()?,
             TokenType::EOF => {
                 return Err(ParseError::new(
                     String::from("EOF"),
@@ -227,6 +764,10 @@ impl<'a> Parser<'a> {
             }
         };
         loop {
+            // An if expression can end on the semicolon before its else block
+            if self.check_current_token_match(TokenType::SEMICOLON) {
+                break;
+            }
             let peek_precedence = self.peek_precedence()?;
             if !self.expect_peek_token_with_type_and_no_advance(token::TokenType::SEMICOLON)
                 && precedence < peek_precedence
@@ -245,10 +786,22 @@ impl<'a> Parser<'a> {
                         | TokenType::NOTEQ
                         | TokenType::GT
                         | TokenType::LT => {
-                            left_expr = self.parse_infix_expression(Rc::new(left_expr))?;
+                            left_expr = self.parse_infix_expression(Rc::new(*left_expr))?;
+                        }
+                        TokenType::DOT => {
+                            left_expr = self.parse_get_expression(Rc::new(*left_expr))?
+                        }
+                        TokenType::AND | TokenType::OR => {
+                            left_expr = self.parse_logical_expression(Rc::new(*left_expr))?
                         }
                         TokenType::LPAREN => {
-                            left_expr = self.parse_call_expression(Rc::new(left_expr))?
+                            left_expr = self.parse_call_expression(Rc::new(*left_expr))?
+                        }
+                        TokenType::LBRACKET => {
+                            left_expr = self.parse_index_expression(Rc::new(*left_expr))?
+                        }
+                        TokenType::ASSIGN => {
+                            left_expr = self.parse_assign_expression(Rc::new(*left_expr))?
                         }
                         _ => {
                             break;
@@ -265,125 +818,510 @@ impl<'a> Parser<'a> {
                 break;
             }
         }
-
-        self.skip_current_semicolon_token();
-        self.skip_peek_semicolon_token();
-
-        Ok(left_expr)
+
+        Ok(left_expr)
+    }
+
+    fn parse_function_literal(&mut self) -> Result<Box<Expression>, ParseError> {
+        let curren_token = self.get_current_token()?;
+        let function = self.parse_function(curren_token, false)?;
+        Ok(Box::new(Expression::Function(function)))
+    }
+
+    /*
+    A yield suspends the enclosing function, which makes it a generator. Like an
+    await it can be used in any expression, the function body is rewritten
+    afterwards so every yield is a statement or the value of a let
+    */
+    fn parse_yield_expression(&mut self) -> Result<Box<Expression>, ParseError> {
+        let current_token = self.get_current_token()?;
+        match self.generators.last_mut() {
+            Some(is_generator) => *is_generator = true,
+            None => {
+                return Err(ParseError::new(
+                    String::from(format!(
+                        "yield outside of a function at line {}",
+                        current_token.line
+                    )),
+                    None,
+                    Some(current_token.line),
+                ));
+            }
+        }
+        if self.async_functions.last() == Some(&true) {
+            return Err(ParseError::new(
+                String::from(format!(
+                    "yield can not be used in an async function at line {}",
+                    current_token.line
+                )),
+                None,
+                Some(current_token.line),
+            ));
+        }
+
+        //The value is optional, a bare yield produces null
+        let mut value = None;
+        if !self.check_peek_token_match(TokenType::SEMICOLON)
+            && !self.check_peek_token_match(TokenType::RBRACE)
+            && !self.check_peek_token_match(TokenType::RPAREN)
+            && !self.check_peek_token_match(TokenType::RBRACKET)
+            && !self.check_peek_token_match(TokenType::COMMA)
+            && !self.check_peek_token_match(TokenType::EOF)
+        {
+            self.next_token();
+            value = Some(self.parse_expression(self.get_precedence_value("LOWEST"))?);
+        }
+        Ok(Box::new(Expression::Yield(ast::YieldExpression {
+            token: current_token,
+            value: value,
+        })))
+    }
+
+    /*
+    An await pauses the enclosing async function until the promise settles. It can
+    be used in any expression, the function body is rewritten afterwards so every
+    await is a statement or the value of a let. At the top level it runs the event
+    loop instead
+    */
+    fn parse_await_expression(&mut self) -> Result<Box<Expression>, ParseError> {
+        let current_token = self.get_current_token()?;
+        match self.async_functions.last() {
+            None | Some(true) => {}
+            Some(false) => {
+                return Err(ParseError::new(
+                    String::from(format!(
+                        "await outside of an async function at line {}",
+                        current_token.line
+                    )),
+                    None,
+                    Some(current_token.line),
+                ));
+            }
+        }
+        if self.check_peek_token_match(TokenType::SEMICOLON)
+            || self.check_peek_token_match(TokenType::RBRACE)
+            || self.check_peek_token_match(TokenType::EOF)
+        {
+            return Err(ParseError::new(
+                String::from(format!(
+                    "Expected an expression after await at line {}",
+                    current_token.line
+                )),
+                None,
+                Some(current_token.line),
+            ));
+        }
+        self.next_token();
+        let value = self.parse_expression(self.get_precedence_value("LOWEST"))?;
+        Ok(Box::new(Expression::Await(ast::AwaitExpression {
+            token: current_token,
+            value: value,
+        })))
+    }
+
+    /*
+    Parse the parameters and body of a function, the current token is the one
+    before the opening paren and the function ends on the closing brace
+    */
+    fn parse_function(
+        &mut self,
+        curren_token: token::Token,
+        is_async: bool,
+    ) -> Result<ast::FunctionLiteral, ParseError> {
+        if !self.expect_peek_token_with_type(TokenType::LPAREN) {
+            return Err(ParseError::new(String::from("Expected ("), None, None));
+        }
+        //Skip the LPAREN Token
+        self.next_token();
+
+        //parse parameters
+        let mut parameters: Vec<ast::Identifier> = vec![];
+        loop {
+            let curr_token = self.get_current_token()?;
+            if curr_token.token_type == TokenType::RPAREN {
+                break;
+            }
+            // We are not caring if the function params start with , we skip it
+            if curr_token.token_type == TokenType::COMMA {
+                self.next_token();
+                continue;
+            }
+            let ident = ast::Identifier {
+                token: curr_token.clone(),
+                value: curr_token.literal,
+                binding: Cell::new(Binding::Global),
+            };
+            parameters.push(ident);
+            self.next_token();
+        }
+
+        if !self.expect_peek_token_with_type(TokenType::LBRACE) {
+            return Err(ParseError::new(String::from("Expected LBRACE"), None, None));
+        }
+        // Skip the LBRACE Token
+        self.next_token();
+
+        //Loops outside of the function body can not be controlled from inside it
+        let loop_depth = self.loop_depth;
+        self.loop_depth = 0;
+        self.generators.push(false);
+        self.async_functions.push(is_async);
+        let body = self.parse_block_statement();
+        let is_generator = self.generators.pop() == Some(true);
+        self.async_functions.pop();
+        self.loop_depth = loop_depth;
+        let mut body = body?;
+        if is_async || is_generator {
+            body = Box::new(hoist_suspensions(*body));
+        }
+
+        Ok(ast::FunctionLiteral {
+            token: curren_token,
+            parameters: Rc::new(parameters),
+            body: Rc::new(body),
+            is_generator: is_generator,
+            is_async: is_async,
+        })
+    }
+
+    fn parse_group_expression(&mut self) -> Result<Box<Expression>, ParseError> {
+        self.next_token();
+        let exp = self.parse_expression(self.get_precedence_value("LOWEST"));
+        if !self.expect_peek_token_with_type(TokenType::RPAREN) {
+            return Err(ParseError::new(
+                String::from("Error parsing group"),
+                None,
+                None,
+            ));
+        }
+        return exp;
+    }
+
+    fn parse_infix_expression(
+        &mut self,
+        left: Rc<Expression>,
+    ) -> Result<Box<Expression>, ParseError> {
+        let current_token = self.get_current_token()?;
+        let literal = current_token.literal.clone();
+        let precedence = self.current_precedence()?;
+        // Skip the operator token
+        self.next_token();
+
+        let right = self.parse_expression(precedence)?;
+        let expression = ast::BinaryExpression {
+            token: current_token,
+            operator: literal,
+            left: left,
+            right: right,
+        };
+        return Ok(Box::new(Expression::Binary(expression)));
+    }
+
+    fn parse_super_expression(&mut self) -> Result<Box<Expression>, ParseError> {
+        let current_token = self.get_current_token()?;
+        //Only the innermost class decides whether super is available
+        if self.classes.last() != Some(&true) {
+            return Err(ParseError::new(
+                String::from(format!(
+                    "super outside of a subclass at line {}",
+                    current_token.line
+                )),
+                None,
+                Some(current_token.line),
+            ));
+        }
+        if !self.expect_peek_token_with_type(TokenType::DOT)
+            || !self.expect_peek_token_with_type(TokenType::IDENTIFIER)
+        {
+            return Err(ParseError::new(
+                String::from(format!(
+                    "Expected a method name after super at line {}",
+                    current_token.line
+                )),
+                None,
+                Some(current_token.line),
+            ));
+        }
+        let method_token = self.get_current_token()?;
+        return Ok(Box::new(Expression::Super(ast::SuperExpression {
+            token: current_token,
+            method: ast::Identifier {
+                token: method_token.clone(),
+                value: method_token.literal.clone(),
+                binding: Cell::new(Binding::Global),
+            },
+            binding: Cell::new(Binding::Global),
+            this_binding: Cell::new(Binding::Global),
+        })));
+    }
+
+    fn parse_get_expression(
+        &mut self,
+        object: Rc<Expression>,
+    ) -> Result<Box<Expression>, ParseError> {
+        let current_token = self.get_current_token()?;
+        if !self.expect_peek_token_with_type(TokenType::IDENTIFIER) {
+            return Err(ParseError::new(
+                String::from(format!(
+                    "Expected a property name after . at line {}",
+                    current_token.line
+                )),
+                None,
+                Some(current_token.line),
+            ));
+        }
+        let name_token = self.get_current_token()?;
+        return Ok(Box::new(Expression::Get(ast::GetExpression {
+            token: current_token,
+            object: object,
+            name: ast::Identifier {
+                token: name_token.clone(),
+                value: name_token.literal.clone(),
+                binding: Cell::new(Binding::Global),
+            },
+        })));
+    }
+
+    fn parse_logical_expression(
+        &mut self,
+        left: Rc<Expression>,
+    ) -> Result<Box<Expression>, ParseError> {
+        let current_token = self.get_current_token()?;
+        let literal = current_token.literal.clone();
+        let precedence = self.current_precedence()?;
+        // Skip the operator token
+        self.next_token();
+
+        let right = self.parse_expression(precedence)?;
+        return Ok(Box::new(Expression::Logical(ast::LogicalExpression {
+            token: current_token,
+            operator: literal,
+            left: left,
+            right: right,
+        })));
+    }
+
+    fn parse_call_expression(
+        &mut self,
+        left: Rc<Expression>,
+    ) -> Result<Box<Expression>, ParseError> {
+        let curr_token = self.get_current_token()?;
+        let parameters = self.parse_expression_list(TokenType::RPAREN)?;
+
+        let call_expression = ast::CallExpression {
+            token: curr_token,
+            funtion: left.clone(),
+            parameters: Rc::new(parameters),
+        };
+
+        return Ok(Box::new(Expression::Call(call_expression)));
+    }
+
+    fn parse_array_literal(&mut self) -> Result<Box<Expression>, ParseError> {
+        let curr_token = self.get_current_token()?;
+        let elements = self.parse_expression_list(TokenType::RBRACKET)?;
+        Ok(Box::new(Expression::Array(ast::ArrayLiteral {
+            token: curr_token,
+            elements: elements,
+        })))
+    }
+
+    /*
+    The lexer splits "a ${x} b ${y} c" into TEMPLATE and TEMPLATEMIDDLE segments
+    each followed by the tokens of an expression, and a closing TEMPLATEEND segment
+    */
+    fn parse_interpolated_string(&mut self) -> Result<Box<Expression>, ParseError> {
+        let curr_token = self.get_current_token()?;
+        let mut parts = vec![];
+        let mut segment = curr_token.clone();
+        loop {
+            if !segment.literal.is_empty() {
+                parts.push(ast::StringPart::Literal(segment.literal.clone()));
+            }
+            if segment.token_type == TokenType::TEMPLATEEND {
+                break;
+            }
+            if self.check_peek_token_match(TokenType::TEMPLATEMIDDLE)
+                || self.check_peek_token_match(TokenType::TEMPLATEEND)
+            {
+                return Err(ParseError::new(
+                    String::from(format!(
+                        "Expected an expression inside ${{}} at line {}",
+                        segment.line
+                    )),
+                    None,
+                    Some(segment.line),
+                ));
+            }
+            self.next_token();
+            let expression = self.parse_expression(self.get_precedence_value("LOWEST"))?;
+            parts.push(ast::StringPart::Expression(expression));
+            if !self.check_peek_token_match(TokenType::TEMPLATEMIDDLE)
+                && !self.check_peek_token_match(TokenType::TEMPLATEEND)
+            {
+                return Err(ParseError::new(
+                    String::from(format!(
+                        "Expected }} to close the interpolation at line {}",
+                        segment.line
+                    )),
+                    None,
+                    Some(segment.line),
+                ));
+            }
+            self.next_token();
+            segment = self.get_current_token()?;
+        }
+        Ok(Box::new(Expression::InterpolatedString(
+            ast::InterpolatedString {
+                token: curr_token,
+                parts: parts,
+            },
+        )))
     }
 
-    fn parse_function_literal(&mut self) -> Result<Box<dyn Expression>, ParseError> {
-        let curren_token = self.get_current_token()?;
-        if !self.expect_peek_token_with_type(TokenType::LPAREN) {
-            return Err(ParseError::new(String::from("Expected ("), None, None));
-        }
-        //Skip the LPAREN Token
+    fn parse_hash_literal(&mut self) -> Result<Box<Expression>, ParseError> {
+        let curr_token = self.get_current_token()?;
+        //Skip the LBRACE Token
         self.next_token();
 
-        //parse parameters
-        let mut parameters: Vec<ast::Identifier> = vec![];
+        let mut pairs: Vec<(Box<Expression>, Box<Expression>)> = vec![];
         loop {
-            let curr_token = self.get_current_token()?;
-            if curr_token.token_type == TokenType::RPAREN {
+            let token = self.get_current_token()?;
+            if token.token_type == TokenType::RBRACE {
                 break;
             }
-            // We are not caring if the function params start with , we skip it
-            if curr_token.token_type == TokenType::COMMA {
+            if token.token_type == TokenType::EOF {
+                return Err(ParseError::new(
+                    String::from(format!(
+                        "Expected to have }} for hash at line {} but reached end of input",
+                        curr_token.line
+                    )),
+                    None,
+                    Some(curr_token.line),
+                ));
+            }
+            if token.token_type == TokenType::COMMA {
                 self.next_token();
                 continue;
             }
-            let ident = ast::Identifier {
-                token: curr_token.clone(),
-                value: curr_token.literal,
-            };
-            parameters.push(ident);
+            let key = self.parse_expression(self.get_precedence_value("LOWEST"))?;
+            if !self.expect_peek_token_with_type(TokenType::COLON) {
+                return Err(ParseError::new(
+                    String::from(format!(
+                        "Expected to have : after hash key at line {}",
+                        token.line
+                    )),
+                    None,
+                    Some(token.line),
+                ));
+            }
+            //Skip the COLON Token
             self.next_token();
+            let value = self.parse_expression(self.get_precedence_value("LOWEST"))?;
+            self.next_token();
+            pairs.push((key, value));
         }
 
-        if !self.expect_peek_token_with_type(TokenType::LBRACE) {
-            return Err(ParseError::new(String::from("Expected LBRACE"), None, None));
-        }
-        // Skip the LBRACE Token
-        self.next_token();
-
-        let body = self.parse_block_statement()?;
-
-        Ok(Box::new(ast::FunctionLiteral {
-            token: curren_token,
-            parameters: Rc::new(parameters),
-            body: Rc::new(body),
-        }))
+        Ok(Box::new(Expression::Hash(ast::HashLiteral {
+            token: curr_token,
+            pairs: pairs,
+        })))
     }
 
-    fn parse_group_expression(&mut self) -> Result<Box<dyn Expression>, ParseError> {
+    fn parse_index_expression(
+        &mut self,
+        left: Rc<Expression>,
+    ) -> Result<Box<Expression>, ParseError> {
+        let curr_token = self.get_current_token()?;
+        //Skip the LBRACKET Token
         self.next_token();
-        let exp = self.parse_expression(self.get_precedence_value("LOWEST"));
-        if !self.expect_peek_token_with_type(TokenType::RPAREN) {
+
+        let index = self.parse_expression(self.get_precedence_value("LOWEST"))?;
+        if !self.expect_peek_token_with_type(TokenType::RBRACKET) {
             return Err(ParseError::new(
-                String::from("Error parsing group"),
-                None,
+                String::from(format!("Expected to have ] at line {}", curr_token.line)),
                 None,
+                Some(curr_token.line),
             ));
         }
-        return exp;
+
+        Ok(Box::new(Expression::Index(ast::IndexExpression {
+            token: curr_token,
+            left: left,
+            index: index,
+        })))
     }
 
-    fn parse_infix_expression(
+    fn parse_assign_expression(
         &mut self,
-        left: Rc<Box<dyn Expression>>,
-    ) -> Result<Box<dyn Expression>, ParseError> {
-        let current_token = self.get_current_token()?;
-        let literal = current_token.literal.clone();
-        let precedence = self.current_precedence()?;
-        // Skip the operator token
+        target: Rc<Expression>,
+    ) -> Result<Box<Expression>, ParseError> {
+        let curr_token = self.get_current_token()?;
+        if !matches!(
+            *target,
+            Expression::Identifier(_) | Expression::Index(_) | Expression::Get(_)
+        ) {
+            return Err(ParseError::new(
+                String::from(format!(
+                    "Invalid assignment target at line {}",
+                    curr_token.line
+                )),
+                None,
+                Some(curr_token.line),
+            ));
+        }
+        //Skip the ASSIGN Token
         self.next_token();
 
-        let right = self.parse_expression(precedence)?;
-        let expression = ast::BinaryExpression {
-            token: current_token,
-            operator: literal,
-            left: left,
-            right: right,
-        };
-        return Ok(Box::new(expression));
+        //Parse with the lowest precedence so that assignment is right associative
+        let value = self.parse_expression(self.get_precedence_value("LOWEST"))?;
+        Ok(Box::new(Expression::Assign(ast::AssignExpression {
+            token: curr_token,
+            target: target,
+            value: value,
+        })))
     }
 
-    fn parse_call_expression(
+    /*
+    Parse comma separated expressions until the end token, the current token is
+    expected to be the opening delimiter and the list ends on the end token
+    */
+    fn parse_expression_list(
         &mut self,
-        left: Rc<Box<dyn Expression>>,
-    ) -> Result<Box<dyn Expression>, ParseError> {
-        let curr_token = self.get_current_token()?;
+        end: TokenType,
+    ) -> Result<Vec<Box<Expression>>, ParseError> {
+        let start_token = self.get_current_token()?;
         self.next_token();
 
-        let mut parameters: Vec<Box<dyn Expression>> = vec![];
+        let mut expressions: Vec<Box<Expression>> = vec![];
         loop {
             let curr_token = self.get_current_token()?;
-            if curr_token.token_type == TokenType::RPAREN {
+            if curr_token.token_type == end {
                 break;
             }
-            // We are not caring if the function params start with , we skip it
+            if curr_token.token_type == TokenType::EOF {
+                return Err(ParseError::new(
+                    String::from(format!(
+                        "Expected to have {:?} for {} at line {} but reached end of input",
+                        end, start_token.literal, start_token.line
+                    )),
+                    None,
+                    Some(start_token.line),
+                ));
+            }
+            // We are not caring if the list starts with , we skip it
             if curr_token.token_type == TokenType::COMMA {
                 self.next_token();
                 continue;
             }
-            if curr_token.token_type == TokenType::LPAREN {
-                self.next_token();
-            }
-            let ident = self.parse_expression(self.get_precedence_value("LOWEST"))?;
+            let expression = self.parse_expression(self.get_precedence_value("LOWEST"))?;
             self.next_token();
-            parameters.push(ident);
+            expressions.push(expression);
         }
-
-        let call_expression = ast::CallExpression {
-            token: curr_token,
-            funtion: left.clone(),
-            parameters: Rc::new(parameters),
-        };
-
-        return Ok(Box::new(call_expression));
+        Ok(expressions)
     }
 
-    fn parse_if_expression(&mut self) -> Result<Box<ast::IfExpression>, ParseError> {
+    fn parse_if_expression(&mut self) -> Result<Box<Expression>, ParseError> {
         let current_token = self.get_current_token()?;
         //Skip if token
         if !self.expect_peek_token_with_type(TokenType::LPAREN) {
@@ -423,11 +1361,12 @@ impl<'a> Parser<'a> {
         //Skip the LEFTBRACE Token
         self.next_token();
         let consequence = self.parse_block_statement()?;
-        self.skip_current_semicolon_token();
+        //Allow a semicolon between the consequence and the else block
+        self.skip_peek_semicolon_token();
 
         let mut alternate = None;
         //Parse the else condition as well
-        if self.check_current_token_match(TokenType::ELSE) {
+        if self.expect_peek_token_with_type(TokenType::ELSE) {
             if !self.expect_peek_token_with_type(TokenType::LBRACE) {
                 return Err(ParseError::new(
                     String::from(format!(
@@ -443,71 +1382,362 @@ impl<'a> Parser<'a> {
             alternate = Some(self.parse_block_statement()?);
         };
 
-        self.skip_current_semicolon_token();
-
-        Ok(Box::new(ast::IfExpression {
+        Ok(Box::new(Expression::If(ast::IfExpression {
             token: current_token,
             condition: condition,
-            consequence: consequence,
-            alternate: alternate,
-        }))
+            consequence: Rc::new(consequence),
+            alternate: alternate.map(Rc::new),
+        })))
+    }
+
+    fn parse_match_expression(&mut self) -> Result<Box<Expression>, ParseError> {
+        let current_token = self.get_current_token()?;
+        //Skip the match token
+        self.next_token();
+        let value = self.parse_expression(self.get_precedence_value("LOWEST"))?;
+
+        if !self.expect_peek_token_with_type(TokenType::LBRACE) {
+            return Err(ParseError::new(
+                String::from(format!(
+                    "Expected to have L Brace at line {} but found something else",
+                    current_token.line
+                )),
+                None,
+                Some(current_token.line),
+            ));
+        };
+        //Skip the LBRACE Token
+        self.next_token();
+
+        let mut arms = vec![];
+        loop {
+            let arm_token = self.get_current_token()?;
+            match arm_token.token_type {
+                TokenType::RBRACE => break,
+                TokenType::EOF => {
+                    return Err(ParseError::new(
+                        String::from(format!(
+                            "Expected }} at the end of the match at line {}",
+                            current_token.line
+                        )),
+                        None,
+                        Some(current_token.line),
+                    ));
+                }
+                _ => {}
+            }
+
+            let pattern = self.parse_pattern()?;
+            //Each name binds once per arm, like parameters of a function
+            let mut identifiers = vec![];
+            pattern_identifiers(&pattern, &mut identifiers);
+            for (index, identifier) in identifiers.iter().enumerate() {
+                if identifiers[..index]
+                    .iter()
+                    .any(|previous| previous.value == identifier.value)
+                {
+                    return Err(ParseError::new(
+                        String::from(format!(
+                            "Variable {} is already declared in this scope at line {}",
+                            identifier.value, identifier.token.line
+                        )),
+                        None,
+                        Some(identifier.token.line),
+                    ));
+                }
+            }
+            if !self.expect_peek_token_with_type(TokenType::ARROW) {
+                return Err(ParseError::new(
+                    String::from(format!(
+                        "Expected => after the pattern at line {}",
+                        arm_token.line
+                    )),
+                    None,
+                    Some(arm_token.line),
+                ));
+            }
+            //Skip the ARROW Token
+            self.next_token();
+
+            //A block body ends on its closing brace so the comma after it is optional
+            let body_token = self.get_current_token()?;
+            let is_block = body_token.token_type == TokenType::LBRACE;
+            let body = if is_block {
+                self.next_token();
+                self.parse_block_statement()?
+            } else {
+                let expression = self.parse_expression(self.get_precedence_value("LOWEST"))?;
+                Box::new(BlockStatement {
+                    token: body_token.clone(),
+                    statements: vec![Statement::Expression(ast::ExpressionStatement {
+                        token: body_token,
+                        value: expression,
+                    })],
+                })
+            };
+            arms.push(ast::MatchArm {
+                pattern: pattern,
+                body: Rc::new(body),
+            });
+
+            if self.expect_peek_token_with_type(TokenType::COMMA) {
+                self.next_token();
+            } else if is_block || self.check_peek_token_match(TokenType::RBRACE) {
+                self.next_token();
+            } else {
+                return Err(ParseError::new(
+                    String::from(format!(
+                        "Expected , or }} after the match arm at line {}",
+                        arm_token.line
+                    )),
+                    None,
+                    Some(arm_token.line),
+                ));
+            }
+        }
+
+        Ok(Box::new(Expression::Match(ast::MatchExpression {
+            token: current_token,
+            value: value,
+            arms: arms,
+        })))
+    }
+
+    //Parse alternatives separated by |, the pattern ends on its last token
+    fn parse_pattern(&mut self) -> Result<ast::Pattern, ParseError> {
+        let current_token = self.get_current_token()?;
+        let mut alternatives = vec![self.parse_single_pattern()?];
+        while self.expect_peek_token_with_type(TokenType::PIPE) {
+            self.next_token();
+            alternatives.push(self.parse_single_pattern()?);
+        }
+        if alternatives.len() == 1 {
+            return Ok(alternatives.remove(0));
+        }
+        //Every alternative has to bind the same names, so bindings are not allowed
+        if alternatives
+            .iter()
+            .any(|pattern| pattern_has_bindings(pattern))
+        {
+            return Err(ParseError::new(
+                String::from(format!(
+                    "Alternative patterns can not bind variables at line {}",
+                    current_token.line
+                )),
+                None,
+                Some(current_token.line),
+            ));
+        }
+        Ok(ast::Pattern::Alternative(alternatives))
+    }
+
+    fn parse_single_pattern(&mut self) -> Result<ast::Pattern, ParseError> {
+        let current_token = self.get_current_token()?;
+        match current_token.token_type {
+            TokenType::IDENTIFIER => {
+                if current_token.literal == "_" {
+                    return Ok(ast::Pattern::Wildcard);
+                }
+                return Ok(ast::Pattern::Binding(ast::Identifier {
+                    token: current_token.clone(),
+                    value: current_token.literal.clone(),
+                    binding: Cell::new(Binding::Global),
+                }));
+            }
+            TokenType::INT
+            | TokenType::FLOAT
+            | TokenType::STRING
+            | TokenType::TRUE
+            | TokenType::FALSE
+            | TokenType::NULL
+            | TokenType::MINUS => {
+                return Ok(ast::Pattern::Literal(self.parse_literal_pattern()?));
+            }
+            TokenType::LBRACKET => {
+                let mut elements = vec![];
+                self.next_token();
+                while !self.check_current_token_match(TokenType::RBRACKET) {
+                    elements.push(self.parse_pattern()?);
+                    self.next_token();
+                    if self.check_current_token_match(TokenType::COMMA) {
+                        self.next_token();
+                    } else if !self.check_current_token_match(TokenType::RBRACKET) {
+                        return Err(ParseError::new(
+                            String::from(format!(
+                                "Expected , or ] in array pattern at line {}",
+                                current_token.line
+                            )),
+                            None,
+                            Some(current_token.line),
+                        ));
+                    }
+                }
+                return Ok(ast::Pattern::Array(elements));
+            }
+            TokenType::LBRACE => {
+                let mut entries = vec![];
+                self.next_token();
+                while !self.check_current_token_match(TokenType::RBRACE) {
+                    let key = self.parse_literal_pattern()?;
+                    if !self.expect_peek_token_with_type(TokenType::COLON) {
+                        return Err(ParseError::new(
+                            String::from(format!(
+                                "Expected : in hash pattern at line {}",
+                                current_token.line
+                            )),
+                            None,
+                            Some(current_token.line),
+                        ));
+                    }
+                    self.next_token();
+                    entries.push((key, self.parse_pattern()?));
+                    self.next_token();
+                    if self.check_current_token_match(TokenType::COMMA) {
+                        self.next_token();
+                    } else if !self.check_current_token_match(TokenType::RBRACE) {
+                        return Err(ParseError::new(
+                            String::from(format!(
+                                "Expected , or }} in hash pattern at line {}",
+                                current_token.line
+                            )),
+                            None,
+                            Some(current_token.line),
+                        ));
+                    }
+                }
+                return Ok(ast::Pattern::Hash(entries));
+            }
+            _ => {
+                return Err(ParseError::new(
+                    String::from(format!(
+                        "Invalid pattern {} at line {}",
+                        current_token.literal, current_token.line
+                    )),
+                    None,
+                    Some(current_token.line),
+                ));
+            }
+        }
+    }
+
+    //Literal patterns are numbers, negative numbers, strings, booleans and null
+    fn parse_literal_pattern(&mut self) -> Result<Box<Expression>, ParseError> {
+        let current_token = self.get_current_token()?;
+        let is_literal = match current_token.token_type {
+            TokenType::INT
+            | TokenType::FLOAT
+            | TokenType::STRING
+            | TokenType::TRUE
+            | TokenType::FALSE
+            | TokenType::NULL => true,
+            TokenType::MINUS => {
+                self.check_peek_token_match(TokenType::INT)
+                    || self.check_peek_token_match(TokenType::FLOAT)
+            }
+            _ => false,
+        };
+        if !is_literal {
+            return Err(ParseError::new(
+                String::from(format!(
+                    "Expected a literal pattern at line {}",
+                    current_token.line
+                )),
+                None,
+                Some(current_token.line),
+            ));
+        }
+        //Parse with the highest precedence so no operator can follow the literal
+        return self.parse_expression(self.get_precedence_value("INDEX"));
     }
 
-    fn parse_identifier_expression(&mut self) -> Result<Box<dyn ast::Expression>, ParseError> {
+    fn parse_identifier_expression(&mut self) -> Result<Box<Expression>, ParseError> {
         let current_token = self.get_current_token()?;
         let literal = current_token.literal.clone();
-        return Ok(Box::new(ast::Identifier {
+        return Ok(Box::new(Expression::Identifier(ast::Identifier {
             token: current_token,
             value: literal,
-        }));
+            binding: Cell::new(Binding::Global),
+        })));
     }
 
-    fn parse_boolean_expression(&mut self) -> Result<Box<dyn ast::Expression>, ParseError> {
+    fn parse_boolean_expression(&mut self) -> Result<Box<Expression>, ParseError> {
         let current_token = self.get_current_token()?;
         let current_token_type = current_token.token_type;
-        Ok(Box::new(ast::Boolean {
+        Ok(Box::new(Expression::Boolean(ast::Boolean {
             token: current_token,
             value: current_token_type == TokenType::TRUE,
-        }))
+        })))
     }
 
-    fn parse_prefix_expression(&mut self) -> Result<Box<dyn ast::Expression>, ParseError> {
+    fn parse_prefix_expression(&mut self) -> Result<Box<Expression>, ParseError> {
         let curr_token = self.get_current_token()?;
         let operator = curr_token.literal.clone();
 
         self.next_token();
 
+        //i64::MIN only fits once negated, so fold it into a single literal
+        if operator == "-" {
+            let current_token = self.get_current_token()?;
+            if current_token.token_type == TokenType::INT
+                && parse_integer(&current_token.literal).is_none()
+                && self.peek_precedence()? <= self.get_precedence_value("PREFIX")
+            {
+                if let Some(int_value) = parse_negated_integer(&current_token.literal) {
+                    return Ok(Box::new(Expression::Integer(ast::IntegerLiteral {
+                        token: Token {
+                            token_type: TokenType::INT,
+                            literal: format!("-{}", current_token.literal),
+                            line: current_token.line,
+                        },
+                        value: int_value,
+                    })));
+                }
+            }
+        }
+
         let right = self.parse_expression(self.get_precedence_value("PREFIX"))?;
-        Ok(Box::new(ast::PrefixExpression {
+        Ok(Box::new(Expression::Prefix(ast::PrefixExpression {
             token: curr_token,
             operator: operator,
             right: right,
-        }))
+        })))
     }
 
     fn parse_block_statement(&mut self) -> Result<Box<BlockStatement>, ParseError> {
         let current_token = self.get_current_token()?;
-        let mut stmts: Vec<Box<dyn Statement>> = vec![];
+        let mut stmts: Vec<Statement> = vec![];
+        self.block_depth += 1;
+        let result = self.parse_block_statements(&mut stmts);
+        self.block_depth -= 1;
+        result?;
+        Ok(Box::new(BlockStatement {
+            token: current_token,
+            statements: stmts,
+        }))
+    }
+
+    fn parse_block_statements(&mut self, stmts: &mut Vec<Statement>) -> Result<(), ParseError> {
         loop {
             self.skip_current_semicolon_token();
             let current_token_type = self.get_current_token()?.token_type;
+            //The block ends on the closing brace, the caller moves past it
             if current_token_type == TokenType::RBRACE || current_token_type == TokenType::EOF {
-                self.next_token();
                 break;
             }
             let stmt = self.parse_statement()?;
             stmts.push(stmt);
         }
-        Ok(Box::new(BlockStatement {
-            token: current_token,
-            statements: stmts,
-        }))
+        Ok(())
     }
 
     fn get_precedence_value(&self, precedence: &str) -> usize {
         let precedence = match precedence {
             "LOWEST" => Precedences::LOWEST,
+            "ASSIGN" => Precedences::ASSIGN,
+            "OR" => Precedences::OR,
+            "AND" => Precedences::AND,
             "CALL" => Precedences::CALL,
+            "INDEX" => Precedences::INDEX,
             "EQUALS" => Precedences::EQUALS,
             "LESSGREATER" => Precedences::LESSGREATER,
             "PREFIX" => Precedences::PREFIX,
@@ -660,3 +1890,54 @@ impl<'a> Parser<'a> {
         }
     }
 }
+
+fn pattern_has_bindings(pattern: &ast::Pattern) -> bool {
+    match pattern {
+        ast::Pattern::Binding(_) => true,
+        ast::Pattern::Wildcard | ast::Pattern::Literal(_) => false,
+        ast::Pattern::Alternative(patterns) | ast::Pattern::Array(patterns) => {
+            patterns.iter().any(|pattern| pattern_has_bindings(pattern))
+        }
+        ast::Pattern::Hash(entries) => entries
+            .iter()
+            .any(|(_, pattern)| pattern_has_bindings(pattern)),
+    }
+}
+
+fn pattern_identifiers<'a>(pattern: &'a ast::Pattern, identifiers: &mut Vec<&'a ast::Identifier>) {
+    match pattern {
+        ast::Pattern::Binding(identifier) => identifiers.push(identifier),
+        ast::Pattern::Wildcard | ast::Pattern::Literal(_) => {}
+        ast::Pattern::Alternative(patterns) | ast::Pattern::Array(patterns) => {
+            for pattern in patterns {
+                pattern_identifiers(pattern, identifiers);
+            }
+        }
+        ast::Pattern::Hash(entries) => {
+            for (_, pattern) in entries {
+                pattern_identifiers(pattern, identifiers);
+            }
+        }
+    }
+}
+
+//Value of an integer literal, the lexer has already validated its digits
+fn parse_integer(literal: &str) -> Option<i64> {
+    return i64::try_from(parse_wide_integer(literal)?).ok();
+}
+
+//Value of an integer literal preceded by a minus sign
+fn parse_negated_integer(literal: &str) -> Option<i64> {
+    return i64::try_from(-parse_wide_integer(literal)?).ok();
+}
+
+fn parse_wide_integer(literal: &str) -> Option<i128> {
+    let digits = literal.replace('_', "");
+    let (radix, digits) = match digits.get(..2) {
+        Some("0x") | Some("0X") => (16, &digits[2..]),
+        Some("0o") | Some("0O") => (8, &digits[2..]),
+        Some("0b") | Some("0B") => (2, &digits[2..]),
+        _ => (10, digits.as_str()),
+    };
+    return i128::from_str_radix(digits, radix).ok();
+}
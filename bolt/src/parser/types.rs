@@ -1,11 +1,15 @@
 pub enum Precedences {
     LOWEST = 0,
-    EQUALS = 1,
-    LESSGREATER = 2,
-    SUM = 3,
-    PRODUCT = 4,
-    PREFIX = 5,
-    CALL = 6,
+    ASSIGN = 1,
+    OR = 2,
+    AND = 3,
+    EQUALS = 4,
+    LESSGREATER = 5,
+    SUM = 6,
+    PRODUCT = 7,
+    PREFIX = 8,
+    CALL = 9,
+    INDEX = 10,
 }
 
 pub type PrecedenceValue = usize;
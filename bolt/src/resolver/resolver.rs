@@ -0,0 +1,402 @@
+use crate::{
+    error::{BoltError, BoltErrorType, EvaluatorError},
+    parser::ast::{
+        Binding, ClassStatement, Expression, FunctionLiteral, Identifier, MatchExpression, Pattern,
+        Program, Statement, StringPart, TryStatement,
+    },
+};
+
+use super::scan::{declared_names, pattern_names};
+
+type Resolution = Result<(), EvaluatorError>;
+
+#[derive(PartialEq)]
+enum ScopeKind {
+    Global,   //Top level of a program or module, its variables are looked up by name
+    Function, //Parameters and locals of a function body
+    Block,    //Catch block, match arm or the superclass of a class, runs with the code around it
+}
+
+struct Local {
+    name: String,
+    declared: bool, //Code of the same function can only use it after its declaration
+}
+
+//One scope for every environment the evaluator creates, a local's slot is its position
+struct Scope {
+    kind: ScopeKind,
+    locals: Vec<Local>,
+}
+
+/*
+Works out where every variable of a program is stored before it runs. A name
+declared in a function, catch block or match arm gets a slot in the
+environment of that scope and each use records how many scopes out it is, so
+the evaluator indexes into the environment instead of looking the name up.
+Top level names stay in a map since imports and the REPL add to them while
+the program runs. Other blocks share the environment of their scope, so a
+name declared in two blocks of a scope has one slot. Reading a variable of
+the same function before its declaration and declaring a name twice in a
+block are errors
+*/
+pub struct Resolver {
+    scopes: Vec<Scope>,
+    blocks: Vec<Vec<String>>, //Names declared so far in each block being resolved
+}
+
+pub fn resolve_program(program: &Program) -> Resolution {
+    let mut resolver = Resolver {
+        scopes: vec![],
+        blocks: vec![],
+    };
+    resolver.begin_scope(ScopeKind::Global, vec![], &program.stmts);
+    return resolver.statements(&program.stmts);
+}
+
+fn resolve_error(message: String, line: usize) -> EvaluatorError {
+    return EvaluatorError::new(message, Some(BoltErrorType::RESOLVE), Some(line));
+}
+
+fn duplicate_error(identifier: &Identifier) -> EvaluatorError {
+    return resolve_error(
+        format!(
+            "Variable {} is already declared in this scope",
+            identifier.value
+        ),
+        identifier.token.line,
+    );
+}
+
+impl Resolver {
+    //Bound names hold a value from the start, the rest are declared somewhere in the statements
+    fn begin_scope(&mut self, kind: ScopeKind, bound: Vec<String>, statements: &[Statement]) {
+        self.blocks.push(bound.clone());
+        let mut locals: Vec<Local> = bound
+            .into_iter()
+            .map(|name| Local {
+                name: name,
+                declared: true,
+            })
+            .collect();
+        for name in declared_names(statements) {
+            if !locals.iter().any(|local| local.name == name) {
+                locals.push(Local {
+                    name: name,
+                    declared: false,
+                });
+            }
+        }
+        self.scopes.push(Scope {
+            kind: kind,
+            locals: locals,
+        });
+    }
+
+    fn end_scope(&mut self) {
+        self.scopes.pop();
+        self.blocks.pop();
+    }
+
+    //Blocks of if, while, try and finally run in the environment of the scope around them
+    fn block(&mut self, statements: &[Statement]) -> Resolution {
+        self.blocks.push(vec![]);
+        let result = self.statements(statements);
+        self.blocks.pop();
+        return result;
+    }
+
+    fn declare(&mut self, identifier: &Identifier) -> Resolution {
+        let block = self.blocks.last_mut().unwrap();
+        if block.contains(&identifier.value) {
+            return Err(duplicate_error(identifier));
+        }
+        block.push(identifier.value.clone());
+        let scope = self.scopes.last_mut().unwrap();
+        let slot = match scope
+            .locals
+            .iter()
+            .position(|local| local.name == identifier.value)
+        {
+            Some(slot) => slot,
+            None => {
+                scope.locals.push(Local {
+                    name: identifier.value.clone(),
+                    declared: false,
+                });
+                scope.locals.len() - 1
+            }
+        };
+        scope.locals[slot].declared = true;
+        identifier.binding.set(match scope.kind {
+            ScopeKind::Global => Binding::Global,
+            _ => Binding::Local {
+                depth: 0,
+                slot: slot,
+            },
+        });
+        return Ok(());
+    }
+
+    /*
+    Find the scope declaring the name, names that no scope declares are looked
+    up by name when they are used. Functions declared in a scope can use its
+    variables before their declaration, they only run later
+    */
+    fn lookup(&self, name: &str, line: usize) -> Result<Binding, EvaluatorError> {
+        let mut nested = false;
+        for (depth, scope) in self.scopes.iter().rev().enumerate() {
+            if let Some(slot) = scope.locals.iter().position(|local| local.name == name) {
+                if !scope.locals[slot].declared && !nested {
+                    return Err(resolve_error(
+                        format!("Cannot use variable {} before its declaration", name),
+                        line,
+                    ));
+                }
+                if scope.kind == ScopeKind::Global {
+                    return Ok(Binding::Global);
+                }
+                return Ok(Binding::Local {
+                    depth: depth,
+                    slot: slot,
+                });
+            }
+            if scope.kind == ScopeKind::Function {
+                nested = true;
+            }
+        }
+        return Ok(Binding::Global);
+    }
+
+    fn read(&self, identifier: &Identifier) -> Resolution {
+        let binding = self.lookup(&identifier.value, identifier.token.line)?;
+        identifier.binding.set(binding);
+        return Ok(());
+    }
+
+    fn statements(&mut self, statements: &[Statement]) -> Resolution {
+        for statement in statements {
+            self.statement(statement)?;
+        }
+        return Ok(());
+    }
+
+    fn statement(&mut self, statement: &Statement) -> Resolution {
+        match statement {
+            Statement::Let(let_statement) => {
+                self.expression(&let_statement.value)?;
+                return self.declare(&let_statement.identifier);
+            }
+            Statement::Return(return_statement) => return self.expression(&return_statement.value),
+            Statement::While(while_statement) => {
+                self.expression(&while_statement.condition)?;
+                return self.block(&while_statement.body.statements);
+            }
+            Statement::Class(class_statement) => return self.class(class_statement),
+            //Names imported without an alias are only known once the module has run
+            Statement::Import(import) => match import.alias.as_ref() {
+                Some(alias) => return self.declare(alias),
+                None => return Ok(()),
+            },
+            Statement::Export(export) => return self.statement(&export.statement),
+            Statement::Throw(throw) => return self.expression(&throw.value),
+            Statement::Try(try_statement) => return self.try_statement(try_statement),
+            Statement::Block(block) => return self.block(&block.statements),
+            Statement::Expression(expression) => return self.expression(&expression.value),
+            Statement::Break(_) | Statement::Continue(_) => return Ok(()),
+        }
+    }
+
+    fn class(&mut self, class_statement: &ClassStatement) -> Resolution {
+        //Methods of a subclass are closed over a scope holding the superclass as super
+        if let Some(superclass) = class_statement.superclass.as_ref() {
+            self.read(superclass)?;
+            self.begin_scope(ScopeKind::Block, vec![String::from("super")], &[]);
+        }
+        for method in &class_statement.methods {
+            self.function(&method.function, true)?;
+        }
+        if class_statement.superclass.is_some() {
+            self.end_scope();
+        }
+        return self.declare(&class_statement.name);
+    }
+
+    fn try_statement(&mut self, try_statement: &TryStatement) -> Resolution {
+        self.block(&try_statement.body.statements)?;
+        if let (Some(parameter), Some(catch_body)) = (
+            try_statement.catch_parameter.as_ref(),
+            try_statement.catch_body.as_ref(),
+        ) {
+            //The parameter takes the first slot, like the parameters of a function
+            let bound = vec![parameter.value.clone()];
+            self.begin_scope(ScopeKind::Block, bound, &catch_body.statements);
+            parameter.binding.set(Binding::Local { depth: 0, slot: 0 });
+            self.statements(&catch_body.statements)?;
+            self.end_scope();
+        }
+        if let Some(finally_body) = try_statement.finally_body.as_ref() {
+            self.block(&finally_body.statements)?;
+        }
+        return Ok(());
+    }
+
+    //Parameters take the first slots, methods get this right after them
+    fn function(&mut self, function: &FunctionLiteral, method: bool) -> Resolution {
+        let mut bound: Vec<String> = vec![];
+        for (slot, parameter) in function.parameters.iter().enumerate() {
+            if bound.contains(&parameter.value) {
+                return Err(duplicate_error(parameter));
+            }
+            bound.push(parameter.value.clone());
+            parameter.binding.set(Binding::Local {
+                depth: 0,
+                slot: slot,
+            });
+        }
+        if method {
+            bound.push(String::from("this"));
+        }
+        self.begin_scope(ScopeKind::Function, bound, &function.body.statements);
+        self.statements(&function.body.statements)?;
+        self.end_scope();
+        return Ok(());
+    }
+
+    fn match_expression(&mut self, match_expression: &MatchExpression) -> Resolution {
+        self.expression(&match_expression.value)?;
+        for arm in &match_expression.arms {
+            //Literals and keys of the pattern are evaluated before the arm scope exists
+            self.pattern_expressions(&arm.pattern)?;
+            let mut bound = vec![];
+            pattern_names(&arm.pattern, &mut bound);
+            self.bind_pattern(&arm.pattern, &bound);
+            self.begin_scope(ScopeKind::Block, bound, &arm.body.statements);
+            self.statements(&arm.body.statements)?;
+            self.end_scope();
+        }
+        return Ok(());
+    }
+
+    fn pattern_expressions(&mut self, pattern: &Pattern) -> Resolution {
+        match pattern {
+            Pattern::Literal(literal) => return self.expression(literal),
+            Pattern::Alternative(patterns) | Pattern::Array(patterns) => {
+                for pattern in patterns {
+                    self.pattern_expressions(pattern)?;
+                }
+            }
+            Pattern::Hash(entries) => {
+                for (key, pattern) in entries {
+                    self.expression(key)?;
+                    self.pattern_expressions(pattern)?;
+                }
+            }
+            Pattern::Wildcard | Pattern::Binding(_) => {}
+        }
+        return Ok(());
+    }
+
+    fn bind_pattern(&self, pattern: &Pattern, bound: &[String]) {
+        match pattern {
+            Pattern::Binding(identifier) => {
+                if let Some(slot) = bound.iter().position(|name| *name == identifier.value) {
+                    identifier.binding.set(Binding::Local {
+                        depth: 0,
+                        slot: slot,
+                    });
+                }
+            }
+            Pattern::Array(patterns) => {
+                for pattern in patterns {
+                    self.bind_pattern(pattern, bound);
+                }
+            }
+            Pattern::Hash(entries) => {
+                for (_, pattern) in entries {
+                    self.bind_pattern(pattern, bound);
+                }
+            }
+            //The parser rejects alternatives that bind names
+            Pattern::Wildcard | Pattern::Literal(_) | Pattern::Alternative(_) => {}
+        }
+    }
+
+    fn expression(&mut self, expression: &Expression) -> Resolution {
+        match expression {
+            Expression::Identifier(identifier) => return self.read(identifier),
+            Expression::This(this) => {
+                this.binding.set(self.lookup("this", this.token.line)?);
+            }
+            Expression::Super(super_expression) => {
+                let line = super_expression.token.line;
+                super_expression.binding.set(self.lookup("super", line)?);
+                super_expression
+                    .this_binding
+                    .set(self.lookup("this", line)?);
+            }
+            Expression::InterpolatedString(interpolated) => {
+                for part in &interpolated.parts {
+                    if let StringPart::Expression(expression) = part {
+                        self.expression(expression)?;
+                    }
+                }
+            }
+            Expression::Prefix(prefix) => return self.expression(&prefix.right),
+            Expression::Binary(binary) => {
+                self.expression(&binary.left)?;
+                return self.expression(&binary.right);
+            }
+            Expression::Logical(logical) => {
+                self.expression(&logical.left)?;
+                return self.expression(&logical.right);
+            }
+            Expression::If(if_expression) => {
+                self.expression(&if_expression.condition)?;
+                self.block(&if_expression.consequence.statements)?;
+                if let Some(alternate) = if_expression.alternate.as_ref() {
+                    return self.block(&alternate.statements);
+                }
+            }
+            Expression::Match(match_expression) => return self.match_expression(match_expression),
+            Expression::Function(function) => return self.function(function, false),
+            Expression::Call(call) => {
+                self.expression(&call.funtion)?;
+                for parameter in call.parameters.iter() {
+                    self.expression(parameter)?;
+                }
+            }
+            Expression::Array(array) => {
+                for element in &array.elements {
+                    self.expression(element)?;
+                }
+            }
+            Expression::Hash(hash) => {
+                for (key, value) in &hash.pairs {
+                    self.expression(key)?;
+                    self.expression(value)?;
+                }
+            }
+            Expression::Index(index) => {
+                self.expression(&index.left)?;
+                return self.expression(&index.index);
+            }
+            Expression::Assign(assign) => {
+                self.expression(&assign.target)?;
+                return self.expression(&assign.value);
+            }
+            Expression::Get(get) => return self.expression(&get.object),
+            Expression::Yield(yield_expression) => {
+                if let Some(value) = yield_expression.value.as_ref() {
+                    return self.expression(value);
+                }
+            }
+            Expression::Await(await_expression) => return self.expression(&await_expression.value),
+            Expression::Integer(_)
+            | Expression::Float(_)
+            | Expression::String(_)
+            | Expression::Null(_)
+            | Expression::Boolean(_) => {}
+        }
+        return Ok(());
+    }
+}
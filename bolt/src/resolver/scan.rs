@@ -0,0 +1,253 @@
+use std::collections::HashSet;
+
+use crate::parser::ast::{
+    BlockStatement, Expression, FunctionLiteral, Pattern, Statement, StringPart,
+};
+
+#[derive(PartialEq)]
+enum Mode {
+    Captured, //Every name used inside the nested functions
+    Declared, //Names a let, class or import declares in the scope itself
+}
+
+struct NameScan {
+    mode: Mode,
+    nesting: usize, //Number of function literals the scan is inside of
+    seen: HashSet<String>,
+    names: Vec<String>,
+}
+
+/*
+Names used by the functions nested in a function body, including this and
+super. Locals with these names may outlive their frame so they are kept in
+cells. Any use counts, which is more than needed but never too few
+*/
+pub fn captured_names(statements: &[Statement]) -> HashSet<String> {
+    let mut scan = NameScan::new(Mode::Captured);
+    scan.statements(statements);
+    return scan.seen;
+}
+
+/*
+Names declared in a scope in the order they appear. Blocks do not start a new
+scope, catch blocks, match arms and function bodies do and are skipped
+*/
+pub fn declared_names(statements: &[Statement]) -> Vec<String> {
+    let mut scan = NameScan::new(Mode::Declared);
+    scan.statements(statements);
+    return scan.names;
+}
+
+//Names a pattern binds, in the order they appear
+pub fn pattern_names(pattern: &Pattern, names: &mut Vec<String>) {
+    match pattern {
+        Pattern::Binding(identifier) => names.push(identifier.value.clone()),
+        Pattern::Array(patterns) => {
+            for pattern in patterns {
+                pattern_names(pattern, names);
+            }
+        }
+        Pattern::Hash(entries) => {
+            for (_, pattern) in entries {
+                pattern_names(pattern, names);
+            }
+        }
+        //The parser rejects alternatives that bind names and names bound twice
+        Pattern::Wildcard | Pattern::Literal(_) | Pattern::Alternative(_) => {}
+    }
+}
+
+impl NameScan {
+    fn new(mode: Mode) -> Self {
+        return Self {
+            mode: mode,
+            nesting: 0,
+            seen: HashSet::new(),
+            names: vec![],
+        };
+    }
+
+    fn add(&mut self, name: &str) {
+        if self.seen.insert(String::from(name)) {
+            self.names.push(String::from(name));
+        }
+    }
+
+    fn declare(&mut self, name: &str) {
+        if self.mode == Mode::Declared {
+            self.add(name);
+        }
+    }
+
+    fn reference(&mut self, name: &str) {
+        if self.mode == Mode::Captured && self.nesting > 0 {
+            self.add(name);
+        }
+    }
+
+    fn statements(&mut self, statements: &[Statement]) {
+        for statement in statements {
+            self.statement(statement);
+        }
+    }
+
+    fn block(&mut self, block: &BlockStatement) {
+        self.statements(&block.statements);
+    }
+
+    //Blocks with a scope of their own only matter for captured names
+    fn scoped_block(&mut self, block: &BlockStatement) {
+        if self.mode == Mode::Captured {
+            self.block(block);
+        }
+    }
+
+    fn function(&mut self, function: &FunctionLiteral) {
+        if self.mode == Mode::Captured {
+            self.nesting += 1;
+            self.block(&function.body);
+            self.nesting -= 1;
+        }
+    }
+
+    fn statement(&mut self, statement: &Statement) {
+        match statement {
+            Statement::Let(let_statement) => {
+                self.declare(&let_statement.identifier.value);
+                self.expression(&let_statement.value);
+            }
+            Statement::Return(return_statement) => self.expression(&return_statement.value),
+            Statement::While(while_statement) => {
+                self.expression(&while_statement.condition);
+                self.block(&while_statement.body);
+            }
+            Statement::Class(class_statement) => {
+                self.declare(&class_statement.name.value);
+                if let Some(superclass) = class_statement.superclass.as_ref() {
+                    self.reference(&superclass.value);
+                }
+                for method in &class_statement.methods {
+                    self.function(&method.function);
+                }
+            }
+            Statement::Import(import) => {
+                if let Some(alias) = import.alias.as_ref() {
+                    self.declare(&alias.value);
+                }
+            }
+            Statement::Export(export) => self.statement(&export.statement),
+            Statement::Throw(throw) => self.expression(&throw.value),
+            Statement::Try(try_statement) => {
+                self.block(&try_statement.body);
+                if let Some(catch_body) = try_statement.catch_body.as_ref() {
+                    self.scoped_block(catch_body);
+                }
+                if let Some(finally_body) = try_statement.finally_body.as_ref() {
+                    self.block(finally_body);
+                }
+            }
+            Statement::Block(block) => self.block(block),
+            Statement::Expression(expression) => self.expression(&expression.value),
+            Statement::Break(_) | Statement::Continue(_) => {}
+        }
+    }
+
+    fn expression(&mut self, expression: &Expression) {
+        match expression {
+            Expression::Identifier(identifier) => self.reference(&identifier.value),
+            Expression::This(_) => self.reference("this"),
+            Expression::Super(_) => {
+                self.reference("super");
+                self.reference("this");
+            }
+            Expression::InterpolatedString(interpolated) => {
+                for part in &interpolated.parts {
+                    if let StringPart::Expression(expression) = part {
+                        self.expression(expression);
+                    }
+                }
+            }
+            Expression::Prefix(prefix) => self.expression(&prefix.right),
+            Expression::Binary(binary) => {
+                self.expression(&binary.left);
+                self.expression(&binary.right);
+            }
+            Expression::Logical(logical) => {
+                self.expression(&logical.left);
+                self.expression(&logical.right);
+            }
+            Expression::If(if_expression) => {
+                self.expression(&if_expression.condition);
+                self.block(&if_expression.consequence);
+                if let Some(alternate) = if_expression.alternate.as_ref() {
+                    self.block(alternate);
+                }
+            }
+            Expression::Match(match_expression) => {
+                self.expression(&match_expression.value);
+                for arm in &match_expression.arms {
+                    if self.mode == Mode::Captured {
+                        self.pattern(&arm.pattern);
+                    }
+                    self.scoped_block(&arm.body);
+                }
+            }
+            Expression::Function(function) => self.function(function),
+            Expression::Call(call) => {
+                self.expression(&call.funtion);
+                for parameter in call.parameters.iter() {
+                    self.expression(parameter);
+                }
+            }
+            Expression::Array(array) => {
+                for element in &array.elements {
+                    self.expression(element);
+                }
+            }
+            Expression::Hash(hash) => {
+                for (key, value) in &hash.pairs {
+                    self.expression(key);
+                    self.expression(value);
+                }
+            }
+            Expression::Index(index) => {
+                self.expression(&index.left);
+                self.expression(&index.index);
+            }
+            Expression::Assign(assign) => {
+                self.expression(&assign.target);
+                self.expression(&assign.value);
+            }
+            Expression::Get(get) => self.expression(&get.object),
+            Expression::Yield(yield_expression) => {
+                if let Some(value) = yield_expression.value.as_ref() {
+                    self.expression(value);
+                }
+            }
+            Expression::Await(await_expression) => self.expression(&await_expression.value),
+            Expression::Integer(_)
+            | Expression::Float(_)
+            | Expression::String(_)
+            | Expression::Null(_)
+            | Expression::Boolean(_) => {}
+        }
+    }
+
+    fn pattern(&mut self, pattern: &Pattern) {
+        match pattern {
+            Pattern::Literal(literal) => self.expression(literal),
+            Pattern::Alternative(patterns) | Pattern::Array(patterns) => {
+                for pattern in patterns {
+                    self.pattern(pattern);
+                }
+            }
+            Pattern::Hash(entries) => {
+                for (key, pattern) in entries {
+                    self.expression(key);
+                    self.pattern(pattern);
+                }
+            }
+            Pattern::Wildcard | Pattern::Binding(_) => {}
+        }
+    }
+}
@@ -2,25 +2,49 @@
 use std::{any::Any, rc::Rc};
 
 use bolt::{
-    error::EvaluatorError,
+    error::{BoltError, EvaluatorError},
     evaluator::{
         self,
         environment::Environment,
         evaluator::{evaluate_statement, Evaluator},
     },
-    object::object::{BooleanObj, Interger, Null, Object, Return},
+    object::object::Value,
     parser::{
         ast::{Identifier, Statement},
         parser::Parser,
     },
+    CompilerBackend, Factory,
 };
 
+//Evaluate the input with the tree walking evaluator and on the virtual machine, both have to agree
+fn eval(input: &str, filename: &str) -> Option<Result<Value, EvaluatorError>> {
+    let evaluated = Evaluator::new(input, filename, None, false, None, "").eval();
+    let backend = Some(CompilerBackend::VM);
+    let compiled = Evaluator::new(input, filename, None, false, backend, "").eval();
+    let describe = |result: &Option<Result<Value, EvaluatorError>>| match result {
+        Some(Ok(value)) => value.inspect(),
+        Some(Err(e)) => format!(
+            "{}: {} at {:?}",
+            e.get_type().name(),
+            e.get_message(),
+            e.get_line()
+        ),
+        None => String::from("nothing"),
+    };
+    assert_eq!(
+        describe(&evaluated),
+        describe(&compiled),
+        "Virtual machine disagrees on {}",
+        input
+    );
+    return evaluated;
+}
+
 #[test]
 fn test_eval() {
-    let a = Interger { value: 10.0 };
+    let a = Value::Integer(10);
     let input = "10;";
-    let evaluator = Evaluator::new(input, "test", None, false, None, "");
-    let evaluated = evaluator.eval().unwrap();
+    let evaluated = eval(input, "test").unwrap();
     match evaluated {
         Ok(eval) => {
             assert_eq!(format!("{:?}", eval), format!("{:?}", a));
@@ -35,8 +59,7 @@ fn test_eval() {
 fn test_integer() {
     //The output of this is same as input 10
     let input = "10";
-    let evaluator = Evaluator::new(input, "test", None, false, None, "");
-    let evaluated = evaluator.eval().unwrap();
+    let evaluated = eval(input, "test").unwrap();
 
     match evaluated {
         Ok(eval) => {
@@ -52,8 +75,7 @@ fn test_integer() {
 fn test_boolean() {
     //The output of this is same as input true
     let input = "true";
-    let evaluator = Evaluator::new(input, "test", None, false, None, "");
-    let evaluated = evaluator.eval().unwrap();
+    let evaluated = eval(input, "test").unwrap();
     match evaluated {
         Ok(eval) => {
             assert_eq!(eval.inspect(), input.to_string());
@@ -63,8 +85,7 @@ fn test_boolean() {
         }
     }
     let input = "false";
-    let evaluator = Evaluator::new(input, "test", None, false, None, "");
-    let evaluated = evaluator.eval().unwrap();
+    let evaluated = eval(input, "test").unwrap();
     match evaluated {
         Ok(eval) => {
             assert_eq!(eval.inspect(), input.to_string());
@@ -79,8 +100,7 @@ fn test_boolean() {
 fn test_null() {
     //The output of this is same as input null
     let input = "null";
-    let evaluator = Evaluator::new(input, "test", None, false, None, "");
-    let evaluated = evaluator.eval().unwrap();
+    let evaluated = eval(input, "test").unwrap();
 
     match evaluated {
         Ok(eval) => {
@@ -92,11 +112,30 @@ fn test_null() {
     }
 }
 
+#[test]
+fn test_string() {
+    let input = "\"hello world\"";
+    let evaluated = eval(input, "test").unwrap();
+
+    match evaluated {
+        Ok(eval) => {
+            if let Value::Str(string) = &eval {
+                assert_eq!(string.as_str(), "hello world");
+                assert_eq!(eval.inspect(), "hello world");
+            } else {
+                panic!("Error Downcasting");
+            }
+        }
+        Err(e) => {
+            panic!("{:?}", e);
+        }
+    }
+}
+
 #[test]
 fn test_bool_prefix_evaluation() {
     let mut input = "!false";
-    let evaluator = Evaluator::new(input, "test", None, false, None, "");
-    let evaluated = evaluator.eval().unwrap();
+    let evaluated = eval(input, "test").unwrap();
 
     match evaluated {
         Ok(eval) => {
@@ -108,8 +147,7 @@ fn test_bool_prefix_evaluation() {
     }
 
     input = "!true";
-    let evaluator = Evaluator::new(input, "test", None, false, None, "");
-    let evaluated = evaluator.eval().unwrap();
+    let evaluated = eval(input, "test").unwrap();
     match evaluated {
         Ok(eval) => {
             assert_eq!(eval.inspect().as_str(), "false");
@@ -120,8 +158,7 @@ fn test_bool_prefix_evaluation() {
     }
 
     input = "!null";
-    let evaluator = Evaluator::new(input, "Test", None, false, None, "");
-    let evaluated = evaluator.eval().unwrap();
+    let evaluated = eval(input, "Test").unwrap();
     match evaluated {
         Ok(eval) => {
             assert_eq!(eval.inspect().as_str(), "true");
@@ -135,14 +172,12 @@ fn test_bool_prefix_evaluation() {
 #[test]
 fn test_minus_prefix_evaluation() {
     let input = "-5";
-    let evaluator = Evaluator::new(input, "test", None, false, None, "");
-    let evaluated = evaluator.eval().unwrap();
+    let evaluated = eval(input, "test").unwrap();
 
     match evaluated {
         Ok(eval) => {
-            let value_any = eval.as_any();
-            if let Some(int) = value_any.downcast_ref::<Interger>() {
-                assert_eq!(int.value, -5.0);
+            if let Value::Integer(int) = &eval {
+                assert_eq!(*int, -5);
             } else {
                 panic!("Error Downcasting");
             }
@@ -171,19 +206,15 @@ fn test_number_binary_evaluation() {
         "5 + 2 * 10",
         "2 * 2 * 2 * 2 * 2",
     ];
-    let expected_results = vec![
-        5.0, -5.0, 3.0, 4.0, 2.0, 6.0, 0.0, 6.0, 37.0, 0.0, 0.0, 50.0, 25.0, 32.0,
-    ];
+    let expected_results = vec![5, -5, 3, 4, 2, 6, 0, 6, 37, 0, 0, 50, 25, 32];
     let size = tests.len();
     for i in 0..size {
-        let evaluator = Evaluator::new(tests[i], "test", None, false, None, "");
-        let evaluated = evaluator.eval().unwrap();
+        let evaluated = eval(tests[i], "test").unwrap();
 
         match evaluated {
             Ok(eval) => {
-                let value_any = eval.as_any();
-                if let Some(int) = value_any.downcast_ref::<Interger>() {
-                    assert_eq!(int.value, expected_results[i]);
+                if let Value::Integer(int) = &eval {
+                    assert_eq!(*int, expected_results[i]);
                 } else {
                     panic!("Error Downcasting");
                 }
@@ -220,15 +251,12 @@ fn test_boolean_binary_evaluation() {
     ];
     let size = tests.len();
     for i in 0..size {
-        let evaluator = Evaluator::new(tests[i], "test", None, false, None, "");
-        let evaluated = evaluator.eval().unwrap();
+        let evaluated = eval(tests[i], "test").unwrap();
 
         match evaluated {
             Ok(eval) => {
-                let value_any = eval.as_any();
-
-                if let Some(int) = value_any.downcast_ref::<BooleanObj>() {
-                    assert_eq!(int.value, expected_results[i]);
+                if let Value::Boolean(int) = &eval {
+                    assert_eq!(*int, expected_results[i]);
                 } else {
                     panic!("Error Downcasting");
                 }
@@ -240,6 +268,145 @@ fn test_boolean_binary_evaluation() {
     }
 }
 
+#[test]
+fn test_equality_evaluation() {
+    let tests = [
+        "null == null",
+        "1 == null",
+        "null != 1",
+        "1 == \"1\"",
+        "true == 1",
+        "1 == 1.0",
+        "let f = fn() { 1 }; f == f",
+        "fn() { 1 } == fn() { 1 }",
+        "let a = [1]; a == a",
+        "[1] == [1]",
+        "let h = {}; h == h",
+        "{\"a\": 1}[\"missing\"] == null",
+        "class A {}; let a = A(); let b = A(); [a == a, a == b, a != b]",
+        "class A { m() { 1 } }; let a = A(); [a.m == a.m, a.m == A().m]",
+        "len == len",
+        "len == print",
+        "[1] != \"[1]\"",
+    ];
+    let expected_results = vec![
+        "true",
+        "false",
+        "true",
+        "false",
+        "false",
+        "true",
+        "true",
+        "false",
+        "true",
+        "false",
+        "true",
+        "true",
+        "[true, false, true]",
+        "[true, false]",
+        "true",
+        "false",
+        "true",
+    ];
+    let size = tests.len();
+    for i in 0..size {
+        let evaluated = eval(tests[i], "test").unwrap();
+
+        match evaluated {
+            Ok(eval) => {
+                assert_eq!(eval.inspect(), expected_results[i], "At Test No - {}", i);
+            }
+            Err(e) => {
+                panic!("At Test No - {} - {:?}", i, e);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_truthiness_evaluation() {
+    let tests = [
+        "if (fn() { 1 }) { \"yes\" } else { \"no\" }",
+        "if (len) { \"yes\" } else { \"no\" }",
+        "class A { m() { 1 } }; if (A().m) { \"yes\" } else { \"no\" }",
+        "class A {}; if (A) { \"yes\" } else { \"no\" }",
+        "if (null) { \"yes\" } else { \"no\" }",
+        "if (false) { \"yes\" } else { \"no\" }",
+        "let callback = fn(x) { x * 2 }; callback && callback(2)",
+    ];
+    let expected_results = vec!["yes", "yes", "yes", "yes", "no", "no", "4"];
+    let size = tests.len();
+    for i in 0..size {
+        let evaluated = eval(tests[i], "test").unwrap();
+
+        match evaluated {
+            Ok(eval) => {
+                assert_eq!(eval.inspect(), expected_results[i], "At Test No - {}", i);
+            }
+            Err(e) => {
+                panic!("At Test No - {} - {:?}", i, e);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_string_binary_evaluation() {
+    let tests = [
+        "\"hello\" + \" \" + \"world\"",
+        "let a = \"bo\"; let b = a + \"lt\"; b",
+    ];
+    let expected_results = vec!["hello world", "bolt"];
+    let size = tests.len();
+    for i in 0..size {
+        let evaluated = eval(tests[i], "test").unwrap();
+
+        match evaluated {
+            Ok(eval) => {
+                if let Value::Str(string) = &eval {
+                    assert_eq!(string.as_str(), expected_results[i]);
+                } else {
+                    panic!("Error Downcasting");
+                }
+            }
+            Err(e) => {
+                panic!("At Test No - {} - {:?}", i, e);
+            }
+        }
+    }
+
+    let tests = [
+        "\"a\" == \"a\"",
+        "\"a\" == \"b\"",
+        "\"a\" != \"b\"",
+        "\"a\" < \"b\"",
+        "\"b\" <= \"a\"",
+        "if (\"\") { true } else { false }",
+        "if (\"bolt\") { true } else { false }",
+    ];
+    let expected_results = vec![true, false, true, true, false, false, true];
+    let size = tests.len();
+    for i in 0..size {
+        let evaluated = eval(tests[i], "test").unwrap();
+
+        match evaluated {
+            Ok(eval) => {
+                if let Value::Boolean(boolean) = &eval {
+                    assert_eq!(*boolean, expected_results[i]);
+                } else {
+                    panic!("Error Downcasting {:?}", eval);
+                }
+            }
+            Err(e) => {
+                panic!("At Test No - {} - {:?}", i, e);
+            }
+        }
+    }
+
+    let evaluated = eval("\"a\" - \"b\"", "test").unwrap();
+    assert!(evaluated.is_err());
+}
+
 #[test]
 fn test_conditional_evaluation() {
     let tests = [
@@ -250,18 +417,15 @@ fn test_conditional_evaluation() {
         "if (1 < 2) { 20 }",
         "if (1 < 2) { 10 } else { 20 }",
     ];
-    let expected_results = vec![10.0, 1.0, 10.0, 20.0, 20.0, 10.0];
+    let expected_results = vec![10, 1, 10, 20, 20, 10];
     let size = tests.len();
     for i in 0..size {
-        let evaluator = Evaluator::new(tests[i], "test", None, false, None, "");
-        let evaluated = evaluator.eval().unwrap();
+        let evaluated = eval(tests[i], "test").unwrap();
 
         match evaluated {
             Ok(eval) => {
-                let value_any = eval.as_any();
-
-                if let Some(int) = value_any.downcast_ref::<Interger>() {
-                    assert_eq!(int.value, expected_results[i]);
+                if let Value::Integer(int) = &eval {
+                    assert_eq!(*int, expected_results[i]);
                 } else {
                     panic!("Error Downcasting {:?}", eval);
                 }
@@ -282,14 +446,12 @@ fn test_conditional_evaluation_nil() {
     ];
     let size = tests.len();
     for i in 0..size {
-        let evaluator = Evaluator::new(tests[i], "test", None, false, None, "");
-        let evaluated = evaluator.eval().unwrap();
+        let evaluated = eval(tests[i], "test").unwrap();
 
         match evaluated {
             Ok(eval) => {
-                let value_any = eval.as_any();
-                if let Some(val) = value_any.downcast_ref::<Null>() {
-                    assert_eq!(val.inspect(), String::from("null"));
+                if let Value::Null = &eval {
+                    assert_eq!(eval.inspect(), String::from("null"));
                 } else {
                     panic!("Error Downcasting");
                 }
@@ -309,20 +471,16 @@ fn test_return_evaluation() {
         "if (false) {return 1}else {return 10}",
         "if (true) { if (true) { if (false) {return 1} else {return 10}; return 20}; return 30}",
     ];
-    let expected_results = vec![10.0, 10.0, 10.0, 10.0];
+    let expected_results = vec![10, 10, 10, 10];
     let size = tests.len();
     for i in 0..size {
-        let evaluator = Evaluator::new(tests[i], "test", None, false, None, "");
-        let evaluated = evaluator.eval().unwrap();
+        let evaluated = eval(tests[i], "test").unwrap();
 
         match evaluated {
             Ok(eval) => {
-                let value_any = eval.as_any();
-
-                if let Some(val) = value_any.downcast_ref::<Return>() {
-                    let value_any = val.value.as_any();
-                    if let Some(val) = value_any.downcast_ref::<Interger>() {
-                        assert_eq!(val.value, expected_results[i]);
+                if let Value::Return(val) = &eval {
+                    if let Value::Integer(val) = val.as_ref() {
+                        assert_eq!(*val, expected_results[i]);
                     } else {
                         panic!("Error Integer Downcasting");
                     }
@@ -345,17 +503,15 @@ fn test_environment_evaluation() {
         "let a = 5; let b = a; b;",
         "let a = 5; let b = a; let c = a + b + 5; c;",
     ];
-    let expected_results = vec![10.0, 25.0, 5.0, 15.0];
+    let expected_results = vec![10, 25, 5, 15];
     let size = tests.len();
     for i in 0..size {
-        let evaluator = Evaluator::new(tests[i], "test", None, false, None, "");
-        let evaluated = evaluator.eval().unwrap();
+        let evaluated = eval(tests[i], "test").unwrap();
 
         match evaluated {
             Ok(eval) => {
-                let value_any = eval.as_any();
-                if let Some(val) = value_any.downcast_ref::<Interger>() {
-                    assert_eq!(val.value, expected_results[i]);
+                if let Value::Integer(val) = &eval {
+                    assert_eq!(*val, expected_results[i]);
                 } else {
                     panic!("Error Downcasting");
                 }
@@ -374,18 +530,16 @@ fn test_functional_call_evaluation() {
         "let a = fn(x){let c = x + 10; c;} a(10);",
         "let a = fn(x, y){let c = x + y; c;} a(10, 20);",
     ];
-    let expected_results = vec![10.0, 20.0, 30.0];
+    let expected_results = vec![10, 20, 30];
     let size = tests.len();
     for i in 0..size {
-        let evaluator = Evaluator::new(tests[i], "test", None, false, None, "");
-        let evaluated = evaluator.eval().unwrap();
+        let evaluated = eval(tests[i], "test").unwrap();
 
         match evaluated {
             Ok(eval) => {
                 println!("Val - {:?}", eval);
-                let value_any = eval.as_any();
-                if let Some(val) = value_any.downcast_ref::<Interger>() {
-                    assert_eq!(val.value, expected_results[i]);
+                if let Value::Integer(val) = &eval {
+                    assert_eq!(*val, expected_results[i]);
                 } else {
                     panic!("Error Downcasting");
                 }
@@ -396,3 +550,1422 @@ fn test_functional_call_evaluation() {
         }
     }
 }
+
+#[test]
+fn test_closure_evaluation() {
+    let tests = [
+        "let adder = fn(x){ fn(y){ x + y } }; let addTwo = adder(2); addTwo(3);",
+        "let x = 10; let f = fn(){ x }; f();",
+        "let fact = fn(n){ if (n < 2) { return 1 }; return n * fact(n - 1) }; fact(5);",
+        "let fib = fn(n){ if (n < 2) { return n }; fib(n - 1) + fib(n - 2) }; fib(10);",
+        "let apply = fn(f, x){ f(x) }; apply(fn(x){ x * 2 }, 5);",
+        "let compose = fn(f, g){ fn(x){ g(f(x)) } }; let h = compose(fn(x){ x + 1 }, fn(x){ x * 3 }); h(2);",
+        "let f = fn(x){ return x * 2; 100 }; let a = f(2); a + 1;",
+        "let f = fn(){ let a = 1; fn(){ let a = 2; a } }; f()();",
+    ];
+    let expected_results = vec![5, 10, 120, 55, 10, 9, 5, 2];
+    let size = tests.len();
+    for i in 0..size {
+        let evaluated = eval(tests[i], "test").unwrap();
+
+        match evaluated {
+            Ok(eval) => {
+                if let Value::Integer(val) = &eval {
+                    assert_eq!(*val, expected_results[i]);
+                } else {
+                    panic!("Error Downcasting {:?}", eval);
+                }
+            }
+            Err(e) => {
+                panic!("At Test No - {} - {:?}", i, e);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_statement_separation_evaluation() {
+    let tests = [
+        "1 + 2; 5",
+        "1 + 2; 5 + 1;",
+        "1 + 2; let a = 5; a",
+        "if (true) { 1 }; 7",
+        "let f = fn(){}; f(); 8",
+    ];
+    let expected_results = vec![5, 6, 5, 7, 8];
+    let size = tests.len();
+    for i in 0..size {
+        let evaluated = eval(tests[i], "test").unwrap();
+
+        match evaluated {
+            Ok(eval) => {
+                if let Value::Integer(val) = &eval {
+                    assert_eq!(*val, expected_results[i]);
+                } else {
+                    panic!("Error Downcasting {:?}", eval);
+                }
+            }
+            Err(e) => {
+                panic!("At Test No - {} - {:?}", i, e);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_array_evaluation() {
+    let tests = [
+        "[1, 2 * 2, 3 + 3]",
+        "[]",
+        "[\"bolt\", true, [1]]",
+        "let a = [1, 2, 3]; a[1] = 10; a",
+        "let a = [1]; let b = a; b[0] = 5; a",
+        "let a = [1]; a[0] = a; a",
+        "let a = [1]; [a, [a]]",
+    ];
+    let expected_results = vec![
+        "[1, 4, 6]",
+        "[]",
+        "[bolt, true, [1]]",
+        "[1, 10, 3]",
+        "[5]",
+        "[[...]]",
+        "[[1], [[1]]]",
+    ];
+    let size = tests.len();
+    for i in 0..size {
+        let evaluated = eval(tests[i], "test").unwrap();
+
+        match evaluated {
+            Ok(eval) => {
+                if let Value::Array(_array) = &eval {
+                    assert_eq!(eval.inspect(), expected_results[i]);
+                } else {
+                    panic!("Error Downcasting {:?}", eval);
+                }
+            }
+            Err(e) => {
+                panic!("At Test No - {} - {:?}", i, e);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_array_index_evaluation() {
+    let tests = [
+        "[1, 2, 3][0]",
+        "[1, 2, 3][2]",
+        "let i = 0; [1][i]",
+        "[1, 2, 3][1 + 1]",
+        "let a = [1, 2, 3]; a[0] + a[1] + a[2]",
+        "[[1, 2], [3]][0][1]",
+        "let f = fn(){ [4, 5] }; f()[1]",
+        "let a = [0, 0]; a[0] = a[1] = 7; a[0] + a[1]",
+    ];
+    let expected_results = vec![1, 3, 1, 3, 6, 2, 5, 14];
+    let size = tests.len();
+    for i in 0..size {
+        let evaluated = eval(tests[i], "test").unwrap();
+
+        match evaluated {
+            Ok(eval) => {
+                if let Value::Integer(val) = &eval {
+                    assert_eq!(*val, expected_results[i]);
+                } else {
+                    panic!("Error Downcasting {:?}", eval);
+                }
+            }
+            Err(e) => {
+                panic!("At Test No - {} - {:?}", i, e);
+            }
+        }
+    }
+
+    let tests = [
+        "[1, 2, 3][3]",
+        "[1, 2, 3][-1]",
+        "[1, 2, 3][1.5]",
+        "[1, 2, 3][true]",
+        "let a = [1]; a[1] = 2",
+        "1[0]",
+    ];
+    let expected_errors = vec![
+        "Index 3 out of range for array of length 3",
+        "Index -1 out of range for array of length 3",
+        "Array index must be an integer, found 1.5",
+        "Array index must be an integer, found true",
+        "Index 1 out of range for array of length 1",
+        "Index operator not supported for 1",
+    ];
+    let size = tests.len();
+    for i in 0..size {
+        let evaluated = eval(tests[i], "test").unwrap();
+
+        match evaluated {
+            Ok(eval) => {
+                panic!("At Test No - {} - expected error, found {:?}", i, eval);
+            }
+            Err(e) => {
+                assert_eq!(e.get_message(), expected_errors[i]);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_hash_evaluation() {
+    let tests = [
+        "{\"name\": \"bolt\", 1: true}",
+        "{}",
+        "let key = \"a\"; {key: 1 + 1, false: [1]}",
+        "{1: \"a\", 1: \"b\"}",
+        "let m = {\"a\": 1}; m[\"b\"] = 2; m[\"a\"] = 3; m",
+        "let h = {}; h[\"self\"] = h; h",
+        "let h = {}; let a = [h]; h[\"list\"] = a; h",
+    ];
+    let expected_results = vec![
+        "{name: bolt, 1: true}",
+        "{}",
+        "{a: 2, false: [1]}",
+        "{1: b}",
+        "{a: 3, b: 2}",
+        "{self: {...}}",
+        "{list: [{...}]}",
+    ];
+    let size = tests.len();
+    for i in 0..size {
+        let evaluated = eval(tests[i], "test").unwrap();
+
+        match evaluated {
+            Ok(eval) => {
+                if let Value::Hash(_hash) = &eval {
+                    assert_eq!(eval.inspect(), expected_results[i]);
+                } else {
+                    panic!("Error Downcasting {:?}", eval);
+                }
+            }
+            Err(e) => {
+                panic!("At Test No - {} - {:?}", i, e);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_hash_index_evaluation() {
+    let tests = [
+        "{\"a\": 5}[\"a\"]",
+        "let key = \"b\"; {\"b\": 5}[key]",
+        "{5: 5}[5]",
+        "{5: 5}[2 + 3]",
+        "{true: 5}[1 > 0]",
+        "{0: 5}[-0]",
+        "{\"a\": 1}[\"b\"]",
+    ];
+    let expected_results = vec!["5", "5", "5", "5", "5", "5", "null"];
+    let size = tests.len();
+    for i in 0..size {
+        let evaluated = eval(tests[i], "test").unwrap();
+
+        match evaluated {
+            Ok(eval) => {
+                assert_eq!(eval.inspect(), expected_results[i], "At Test No - {}", i);
+            }
+            Err(e) => {
+                panic!("At Test No - {} - {:?}", i, e);
+            }
+        }
+    }
+
+    let tests = [
+        "{fn(x){ x }: 1}",
+        "{\"a\": 1}[[1]]",
+        "let m = {}; m[{}] = 1",
+    ];
+    let expected_errors = vec![
+        "Unusable as hash key: fn(x)",
+        "Unusable as hash key: [1]",
+        "Unusable as hash key: {}",
+    ];
+    let size = tests.len();
+    for i in 0..size {
+        let evaluated = eval(tests[i], "test").unwrap();
+
+        match evaluated {
+            Ok(eval) => {
+                panic!("At Test No - {} - expected error, found {:?}", i, eval);
+            }
+            Err(e) => {
+                assert_eq!(e.get_message(), expected_errors[i]);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_while_evaluation() {
+    let tests = [
+        "let i = [0]; while (i[0] < 5) { i[0] = i[0] + 1 }; i[0]",
+        "let i = [0]; let sum = [0]; while (i[0] < 10) { i[0] = i[0] + 1; if (i[0] == 3) { continue }; if (i[0] == 6) { break }; sum[0] = sum[0] + i[0] }; sum[0]",
+        "let f = fn(){ let i = [0]; while (true) { i[0] = i[0] + 1; if (i[0] > 4) { return i[0] } } }; f()",
+        "let i = [0]; while (i[0] < 3) { let j = [0]; while (true) { j[0] = j[0] + 1; if (j[0] > 2) { break } }; i[0] = i[0] + j[0] }; i[0]",
+        "let i = [0]; while (false) { i[0] = 1 }; i[0]",
+    ];
+    let expected_results = vec![5, 12, 5, 3, 0];
+    let size = tests.len();
+    for i in 0..size {
+        let evaluated = eval(tests[i], "test").unwrap();
+
+        match evaluated {
+            Ok(eval) => {
+                if let Value::Integer(val) = &eval {
+                    assert_eq!(*val, expected_results[i]);
+                } else {
+                    panic!("Error Downcasting {:?}", eval);
+                }
+            }
+            Err(e) => {
+                panic!("At Test No - {} - {:?}", i, e);
+            }
+        }
+    }
+
+    let evaluated = eval("while (false) {}", "test").unwrap();
+    match evaluated {
+        Ok(eval) => {
+            assert_eq!(eval.inspect(), "null");
+        }
+        Err(e) => {
+            panic!("{:?}", e);
+        }
+    }
+}
+
+#[test]
+fn test_assignment_evaluation() {
+    let tests = [
+        "let x = 1; x = x + 1; x",
+        "let x = 1; x = 5",
+        "let a = 1; let b = 2; a = b = 5; a + b",
+        "let x = 1; if (true) { x = 10 }; x",
+        "let x = 1; let f = fn(){ x = 10 }; f(); x",
+        "let x = 1; let f = fn(x){ x = 5; x }; f(2) + x",
+        "let counter = fn(){ let count = 0; fn(){ count = count + 1; count } }; let c = counter(); c(); c(); c()",
+        "let i = 0; let sum = 0; while (i < 5) { i = i + 1; sum = sum + i }; sum",
+    ];
+    let expected_results = vec![2, 5, 10, 10, 10, 6, 3, 15];
+    let size = tests.len();
+    for i in 0..size {
+        let evaluated = eval(tests[i], "test").unwrap();
+
+        match evaluated {
+            Ok(eval) => {
+                if let Value::Integer(val) = &eval {
+                    assert_eq!(*val, expected_results[i]);
+                } else {
+                    panic!("Error Downcasting {:?}", eval);
+                }
+            }
+            Err(e) => {
+                panic!("At Test No - {} - {:?}", i, e);
+            }
+        }
+    }
+
+    let tests = ["y = 2", "let f = fn(){ z = 1 }; f()"];
+    let expected_errors = vec![
+        "Cannot assign to undeclared variable y",
+        "Cannot assign to undeclared variable z",
+    ];
+    let size = tests.len();
+    for i in 0..size {
+        let evaluated = eval(tests[i], "test").unwrap();
+
+        match evaluated {
+            Ok(eval) => {
+                panic!("At Test No - {} - expected error, found {:?}", i, eval);
+            }
+            Err(e) => {
+                assert_eq!(e.get_message(), expected_errors[i]);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_logical_evaluation() {
+    let tests = [
+        "true && true",
+        "true && false",
+        "false || true",
+        "false || false",
+        "1 < 2 && 2 < 3",
+        "1 > 2 || 2 > 3",
+        "0 || 5",
+        "3 && 5",
+        "\"\" || \"default\"",
+        "null && 1",
+        "false && undefined",
+        "true || undefined",
+        "let calls = 0; let f = fn(){ calls = calls + 1; true }; false && f(); true || f(); calls",
+        "let calls = 0; let f = fn(){ calls = calls + 1; true }; true && f(); false || f(); calls",
+    ];
+    let expected_results = vec![
+        "true", "false", "true", "false", "true", "false", "5", "5", "default", "null", "false",
+        "true", "0", "2",
+    ];
+    let size = tests.len();
+    for i in 0..size {
+        let evaluated = eval(tests[i], "test").unwrap();
+
+        match evaluated {
+            Ok(eval) => {
+                assert_eq!(eval.inspect(), expected_results[i], "At Test No - {}", i);
+            }
+            Err(e) => {
+                panic!("At Test No - {} - {:?}", i, e);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_builtin_evaluation() {
+    let tests = [
+        "len(\"hello\")",
+        "len([1, 2, 3])",
+        "len({1: 2, 3: 4})",
+        "len(\"\")",
+        "type(1)",
+        "type(\"a\")",
+        "type(true)",
+        "type(null)",
+        "type([1])",
+        "type({})",
+        "type(fn(){})",
+        "type(len)",
+        "str(12) + str(true)",
+        "num(\"3.5\") + num(true)",
+        "print(\"hello\", 1)",
+        "assert(1 < 2)",
+        "let len = fn(x){ 42 }; len(\"a\")",
+        "let apply = fn(f, x){ f(x) }; apply(len, [1, 2])",
+        "len",
+        "len(\"h\u{e9}llo \u{1F600}\")",
+    ];
+    let expected_results = vec![
+        "5",
+        "3",
+        "2",
+        "0",
+        "integer",
+        "string",
+        "boolean",
+        "null",
+        "array",
+        "hash",
+        "function",
+        "function",
+        "12true",
+        "4.5",
+        "null",
+        "null",
+        "42",
+        "2",
+        "builtin len",
+        "7",
+    ];
+    let size = tests.len();
+    for i in 0..size {
+        let evaluated = eval(tests[i], "test").unwrap();
+
+        match evaluated {
+            Ok(eval) => {
+                assert_eq!(eval.inspect(), expected_results[i], "At Test No - {}", i);
+            }
+            Err(e) => {
+                panic!("At Test No - {} - {:?}", i, e);
+            }
+        }
+    }
+
+    let tests = [
+        "len(1)",
+        "len(\"a\", \"b\")",
+        "num(\"abc\")",
+        "assert(false)",
+        "assert(1 == 2, \"math is broken\")",
+        "undefined(1)",
+    ];
+    let expected_errors = vec![
+        "Argument to len not supported, found 1",
+        "Wrong number of arguments to len: expected 1, found 2",
+        "Cannot convert \"abc\" to a number",
+        "Assertion failed",
+        "Assertion failed: math is broken",
+        "Error getting environment variable undefined",
+    ];
+    let size = tests.len();
+    for i in 0..size {
+        let evaluated = eval(tests[i], "test").unwrap();
+
+        match evaluated {
+            Ok(eval) => {
+                panic!("At Test No - {} - expected error, found {:?}", i, eval);
+            }
+            Err(e) => {
+                assert_eq!(e.get_message(), expected_errors[i]);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_vm_compiler_evaluation() {
+    //Running through the compiler backend sets up imports and tasks like the run command
+    let filename = "tests/modules/main.bolt";
+    let tests = [
+        "import \"math\" as math; let r = math.add(1, 2); r",
+        "let p = spawn(fn() { 1 }); let v = await p; v",
+    ];
+    for i in 0..tests.len() {
+        let program = Parser::new(tests[i]).parse_program().unwrap();
+        let mut compiler = Factory::new(CompilerBackend::VM, program, filename);
+        if let Err(e) = compiler.bytecode_to_jit(&String::new()) {
+            panic!("At Test No - {} - {:?}", i, e);
+        }
+    }
+
+    let tests = [
+        "let x = 1;\nlet x = 2;",
+        "let p = spawn(fn() { 1 });\nlet v = await p;\nv + \"a\"",
+    ];
+    let expected_errors = vec![
+        (
+            "ResolveError",
+            "Variable x is already declared in this scope",
+            Some(2),
+        ),
+        (
+            "RuntimeError",
+            "Invalid binary left and right operands",
+            Some(3),
+        ),
+    ];
+    for i in 0..tests.len() {
+        let program = Parser::new(tests[i]).parse_program().unwrap();
+        let mut compiler = Factory::new(CompilerBackend::VM, program, filename);
+        match compiler.bytecode_to_jit(&String::new()) {
+            Ok(_) => panic!("At Test No - {} - expected error", i),
+            Err(e) => {
+                assert_eq!(
+                    e.get_type().name(),
+                    expected_errors[i].0,
+                    "At Test No - {}",
+                    i
+                );
+                assert_eq!(e.get_message(), expected_errors[i].1, "At Test No - {}", i);
+                assert_eq!(e.get_line(), expected_errors[i].2, "At Test No - {}", i);
+            }
+        }
+    }
+
+    let program = Parser::new("let x = 1;\nlet x = 2;")
+        .parse_program()
+        .unwrap();
+    let mut compiler = Factory::new(CompilerBackend::VM, program, filename);
+    assert!(compiler.compile().is_err());
+    assert_eq!(compiler.generate_ir(), "");
+}
+
+#[test]
+fn test_import_evaluation() {
+    //Imports are resolved relative to the file name given to the evaluator
+    let filename = "tests/modules/main.bolt";
+    let tests = [
+        "import \"math\" as math; math.add(1, 2)",
+        "import \"math.bolt\"; twice(4)",
+        "import \"math\" as math; let add = 5; math.twice(add)",
+        "import \"nested/square\" as sq; sq.square(3)",
+        "import \"counter\" as c; c.increment(); c.increment(); c.count",
+        "import \"counter\" as a; import \"counter\" as b; a.increment(); b.count",
+        "import \"math\" as math; math",
+        "import \"counter\"; import \"counter\" as c; increment(); c.increment(); [c.count, count]",
+        "import \"counter\"; count = 5; import \"counter\" as c; c.count",
+        "import \"counter\"; import \"counter\"; increment()",
+    ];
+    let expected_results = vec![
+        "3",
+        "8",
+        "10",
+        "9",
+        "2",
+        "1",
+        "module math",
+        "[2, 2]",
+        "5",
+        "1",
+    ];
+    let size = tests.len();
+    for i in 0..size {
+        let evaluated = eval(tests[i], filename).unwrap();
+
+        match evaluated {
+            Ok(eval) => {
+                assert_eq!(eval.inspect(), expected_results[i], "At Test No - {}", i);
+            }
+            Err(e) => {
+                panic!("At Test No - {} - {:?}", i, e);
+            }
+        }
+    }
+
+    let tests = [
+        "import \"missing\";",
+        "import \"math\" as math; math.secret",
+        "import \"math\"; secret",
+        "import \"cycle_a\";",
+        "let x = 1; x.y",
+        "let add = 1; import \"math\";",
+        "import \"math\"; let twice = 1;",
+        "import \"counter\"; class count {}",
+    ];
+    let expected_errors = vec![
+        "Cannot find module \"missing\"",
+        "Module math has no export named secret",
+        "Error getting environment variable secret",
+        "Import cycle detected: cycle_a -> cycle_b -> cycle_a",
+        "Property access not supported for 1",
+        "Variable add is already declared in this scope",
+        "Variable twice is already declared in this scope",
+        "Variable count is already declared in this scope",
+    ];
+    let size = tests.len();
+    for i in 0..size {
+        let evaluated = eval(tests[i], filename).unwrap();
+
+        match evaluated {
+            Ok(eval) => {
+                panic!("At Test No - {} - expected error, found {:?}", i, eval);
+            }
+            Err(e) => {
+                assert_eq!(e.get_message(), expected_errors[i]);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_class_evaluation() {
+    let tests = [
+        "class Point { init(x, y) { this.x = x; this.y = y; } sum() { this.x + this.y } }; Point(1, 2).sum()",
+        "class Counter { init() { this.count = 0; } increment() { this.count = this.count + 1; this } }; let c = Counter(); c.increment().increment(); c.count",
+        "class Empty {}; let e = Empty(); e.value = 5; e.value = e.value * 2; e.value",
+        "class Greeter { init(name) { this.name = name; } greet() { \"hi \" + this.name } }; let greet = Greeter(\"bolt\").greet; greet()",
+        "class Box { init(v) { this.v = v; } getter() { fn() { this.v } } }; Box(7).getter()()",
+        "class Box { value() { 1 } }; let b = Box(); b.value = fn() { 2 }; b.value()",
+        "class Point { init(x) { this.x = x; return 42; } }; Point(3).x",
+        "class Point {}; Point",
+        "class Point {}; Point()",
+        "class Point {}; type(Point()) + type(Point)",
+    ];
+    let expected_results = vec![
+        "3",
+        "2",
+        "10",
+        "hi bolt",
+        "7",
+        "2",
+        "3",
+        "class Point",
+        "Point instance",
+        "instanceclass",
+    ];
+    let size = tests.len();
+    for i in 0..size {
+        let evaluated = eval(tests[i], "test").unwrap();
+
+        match evaluated {
+            Ok(eval) => {
+                assert_eq!(eval.inspect(), expected_results[i], "At Test No - {}", i);
+            }
+            Err(e) => {
+                panic!("At Test No - {} - {:?}", i, e);
+            }
+        }
+    }
+
+    let tests = [
+        "class Point {}; Point().x",
+        "class Point {}; Point(1)",
+        "class Point { init(x) {} }; Point()",
+        "let x = 1; x.y = 2",
+    ];
+    let expected_errors = vec![
+        "Undefined property x on Point instance",
+        "No of args in function mismatch",
+        "No of args in function mismatch",
+        "Only instances have fields, found 1",
+    ];
+    let size = tests.len();
+    for i in 0..size {
+        let evaluated = eval(tests[i], "test").unwrap();
+
+        match evaluated {
+            Ok(eval) => {
+                panic!("At Test No - {} - expected error, found {:?}", i, eval);
+            }
+            Err(e) => {
+                assert_eq!(e.get_message(), expected_errors[i]);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_class_inheritance_evaluation() {
+    let tests = [
+        "class A { name() { \"A\" } }; class B < A {}; B().name()",
+        "class A { name() { \"A\" } }; class B < A { name() { \"B\" } }; B().name()",
+        "class A { name() { \"A\" } }; class B < A { name() { \"B\" + super.name() } }; B().name()",
+        "class A { init(x) { this.x = x; } }; class B < A { init(x) { super.init(x * 2); } }; B(5).x",
+        "class A { init(x) { this.x = x; } }; class B < A {}; B(3).x",
+        "class A { name() { \"A\" } hello() { \"hello \" + this.name() } }; class B < A { name() { \"B\" } }; B().hello()",
+        "class A { m() { \"A\" } }; class B < A { m() { \"B\" + super.m() } }; class C < B { m() { \"C\" + super.m() } }; C().m()",
+        "class A { m() { this.v } }; class B < A { m() { let f = super.m; f() } }; let b = B(); b.v = 4; b.m()",
+    ];
+    let expected_results = vec!["A", "B", "BA", "10", "3", "hello B", "CBA", "4"];
+    let size = tests.len();
+    for i in 0..size {
+        let evaluated = eval(tests[i], "test").unwrap();
+
+        match evaluated {
+            Ok(eval) => {
+                assert_eq!(eval.inspect(), expected_results[i], "At Test No - {}", i);
+            }
+            Err(e) => {
+                panic!("At Test No - {} - {:?}", i, e);
+            }
+        }
+    }
+
+    let tests = [
+        "let A = 1; class B < A {}",
+        "class B < len {}",
+        "class B < Missing {}",
+        "class A {}; class B < A { m() { super.m() } }; B().m()",
+    ];
+    let expected_errors = vec![
+        "Superclass must be a class, found 1",
+        "Superclass must be a class, found builtin len",
+        "Error getting environment variable Missing",
+        "Undefined method m on superclass A",
+    ];
+    let size = tests.len();
+    for i in 0..size {
+        let evaluated = eval(tests[i], "test").unwrap();
+
+        match evaluated {
+            Ok(eval) => {
+                panic!("At Test No - {} - expected error, found {:?}", i, eval);
+            }
+            Err(e) => {
+                assert_eq!(e.get_message(), expected_errors[i]);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_match_evaluation() {
+    let describe = "let describe = fn(v) { match v { 0 => \"zero\", -1 => \"minus one\", \"a\" | \"b\" => \"letter\", true => \"yes\", null => \"nothing\", [] => \"empty\", [x] => \"one \" + str(x), [x, [y, _]] => x + y, {\"name\": name} => \"hi \" + name, n => n } };";
+    let tests = [
+        "describe(0)",
+        "describe(-1)",
+        "describe(\"b\")",
+        "describe(true)",
+        "describe(null)",
+        "describe([])",
+        "describe([7])",
+        "describe([1, [2, 3]])",
+        "describe({\"name\": \"bolt\", \"age\": 1})",
+        "describe(42)",
+        "describe(\"c\")",
+        "describe([1, 2, 3])",
+    ];
+    let expected_results = vec![
+        "zero",
+        "minus one",
+        "letter",
+        "yes",
+        "nothing",
+        "empty",
+        "one 7",
+        "3",
+        "hi bolt",
+        "42",
+        "c",
+        "[1, 2, 3]",
+    ];
+    let size = tests.len();
+    for i in 0..size {
+        let input = format!("{} {}", describe, tests[i]);
+        let evaluated = eval(&input, "test").unwrap();
+
+        match evaluated {
+            Ok(eval) => {
+                assert_eq!(eval.inspect(), expected_results[i], "At Test No - {}", i);
+            }
+            Err(e) => {
+                panic!("At Test No - {} - {:?}", i, e);
+            }
+        }
+    }
+
+    let tests = [
+        "let x = 1; match 2 { x => x }; x",
+        "let f = fn(v) { match v { 0 => { return \"early\" } _ => 1 }; \"late\" }; f(0) + f(1)",
+        "let i = 0; while (true) { i = i + 1; match i { 3 => { break }, _ => 0 } }; i",
+        "match 1 { true => \"bool\", 1 => \"number\" }",
+        "match \"1\" { 1 => \"number\", _ => \"other\" }",
+    ];
+    let expected_results = vec!["1", "earlylate", "3", "number", "other"];
+    let size = tests.len();
+    for i in 0..size {
+        let evaluated = eval(tests[i], "test").unwrap();
+
+        match evaluated {
+            Ok(eval) => {
+                assert_eq!(eval.inspect(), expected_results[i], "At Test No - {}", i);
+            }
+            Err(e) => {
+                panic!("At Test No - {} - {:?}", i, e);
+            }
+        }
+    }
+
+    let tests = ["match 3 { 1 => 1, 2 => 2 }", "match [1] { [a, b] => a }"];
+    let expected_errors = vec!["No match arm for value 3", "No match arm for value [1]"];
+    let size = tests.len();
+    for i in 0..size {
+        let evaluated = eval(tests[i], "test").unwrap();
+
+        match evaluated {
+            Ok(eval) => {
+                panic!("At Test No - {} - expected error, found {:?}", i, eval);
+            }
+            Err(e) => {
+                assert_eq!(e.get_message(), expected_errors[i]);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_exception_evaluation() {
+    let tests = [
+        "let r = 0; try { throw \"boom\" } catch (e) { r = e.message }; r",
+        "let r = 0; try { throw \"boom\" } catch (e) { r = e.kind }; r",
+        "let r = 0; try { throw 42 } catch (e) { r = e.message }; r",
+        "let r = 0; try { 1 + \"a\" } catch (e) { r = e.kind }; r",
+        "let r = 0; try { undefined } catch (e) { r = e.message }; r",
+        "let r = 0; try { [1][5] } catch (e) { r = e }; r",
+        "let r = 0; try {\nlet x = 1;\nthrow \"line\" } catch (e) { r = e.line }; r",
+        "let r = 0; try { try { throw \"inner\" } catch (e) { throw e } } catch (e) { r = e.message }; r",
+        "let f = fn() { throw \"from function\" }; let r = 0; try { f() } catch (e) { r = e.message }; r",
+        "let r = []; try { r = r } catch (e) { r = 1 } finally { r = 2 }; r",
+        "let r = 1; try { throw \"x\" } catch (e) { r = r + 1 } finally { r = r * 10 }; r",
+        "let f = fn() { try { return \"try\" } finally { print(\"cleanup\") } }; f()",
+        "let f = fn() { try { throw \"x\" } finally { return \"finally\" } }; f()",
+        "let i = 0; while (true) { try { i = i + 1; if (i > 20) { break } } finally { i = i + 10 } }; i",
+        "let r = 0; try { throw \"a\" } catch (e) { r = type(e) }; r",
+        "let r = 0; try { assert(false, \"nope\") } catch (e) { r = str(e) }; r",
+    ];
+    let expected_results = vec![
+        "boom",
+        "Error",
+        "42",
+        "RuntimeError",
+        "Error getting environment variable undefined",
+        "RuntimeError: Index 5 out of range for array of length 1",
+        "3",
+        "inner",
+        "from function",
+        "2",
+        "20",
+        "try",
+        "finally",
+        "33",
+        "error",
+        "RuntimeError: Assertion failed: nope",
+    ];
+    let size = tests.len();
+    for i in 0..size {
+        let evaluated = eval(tests[i], "test").unwrap();
+
+        match evaluated {
+            Ok(eval) => {
+                assert_eq!(eval.inspect(), expected_results[i], "At Test No - {}", i);
+            }
+            Err(e) => {
+                panic!("At Test No - {} - {:?}", i, e);
+            }
+        }
+    }
+
+    let tests = [
+        "throw \"uncaught\"",
+        "let x = 0; try { throw \"first\" } finally { x = 1 }",
+        "try { 1 } catch (e) { 2 } finally { throw \"from finally\" }",
+        "let x = 1;\nlet y = x + \"s\";",
+    ];
+    let expected_errors = vec![
+        ("uncaught", Some(1)),
+        ("first", Some(1)),
+        ("from finally", Some(1)),
+        ("Invalid binary left and right operands", Some(2)),
+    ];
+    let size = tests.len();
+    for i in 0..size {
+        let evaluated = eval(tests[i], "test").unwrap();
+
+        match evaluated {
+            Ok(eval) => {
+                panic!("At Test No - {} - expected error, found {:?}", i, eval);
+            }
+            Err(e) => {
+                assert_eq!(e.get_message(), expected_errors[i].0);
+                assert_eq!(e.get_line(), expected_errors[i].1);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_numeric_evaluation() {
+    let tests = [
+        "10 / 3",
+        "-7 / 2",
+        "10.0 / 4",
+        "10 / 4.0",
+        "1 + 2.5",
+        "2.0 * 3",
+        "10.0",
+        "0.1 + 0.2",
+        "-2.5",
+        "9007199254740993 + 1",
+        "1 == 1.0",
+        "2 < 2.5",
+        "type(1)",
+        "type(1.0)",
+        "let h = {1: \"one\"}; h[1.0]",
+        "match 2.0 { 2 => \"two\", _ => \"other\" }",
+        "num(\"42\") + 1",
+        "num(\"1.5\")",
+        "int(7.9) + int(-7.9)",
+        "float(3)",
+        "int(\"12\")",
+        "1.0 / 0",
+        "0xff + 0b1010 + 0o17",
+        "1_000 * 1e3",
+        "-9223372036854775808",
+        "-9223372036854775808 + 1",
+        "-0x8000_0000_0000_0000 == -9223372036854775807 - 1",
+        "1e20",
+        "-1.5e20",
+        "1e16 * 10",
+        "1e-7",
+    ];
+    let expected_results = vec![
+        "3",
+        "-3",
+        "2.5",
+        "2.5",
+        "3.5",
+        "6.0",
+        "10.0",
+        "0.30000000000000004",
+        "-2.5",
+        "9007199254740994",
+        "true",
+        "true",
+        "integer",
+        "float",
+        "one",
+        "two",
+        "43",
+        "1.5",
+        "0",
+        "3.0",
+        "12",
+        "inf",
+        "280",
+        "1000000.0",
+        "-9223372036854775808",
+        "-9223372036854775807",
+        "true",
+        "1e20",
+        "-1.5e20",
+        "1e17",
+        "0.0000001",
+    ];
+    let size = tests.len();
+    for i in 0..size {
+        let evaluated = eval(tests[i], "test").unwrap();
+
+        match evaluated {
+            Ok(eval) => {
+                assert_eq!(eval.inspect(), expected_results[i], "At Test No - {}", i);
+            }
+            Err(e) => {
+                panic!("At Test No - {} - {:?}", i, e);
+            }
+        }
+    }
+
+    let tests = [
+        "9223372036854775807 + 1",
+        "let min = -9223372036854775807 - 1; min - 1",
+        "4611686018427387904 * 2",
+        "let min = -9223372036854775807 - 1; min / -1",
+        "let min = -9223372036854775807 - 1; -min",
+        "1 / 0",
+        "[1, 2][1.0]",
+        "int(1.0 / 0)",
+    ];
+    let expected_errors = vec![
+        "Integer overflow in 9223372036854775807 + 1",
+        "Integer overflow in -9223372036854775808 - 1",
+        "Integer overflow in 4611686018427387904 * 2",
+        "Integer overflow in -9223372036854775808 / -1",
+        "Integer overflow negating -9223372036854775808",
+        "Division by zero",
+        "Array index must be an integer, found 1.0",
+        "Cannot convert inf to an integer",
+    ];
+    let size = tests.len();
+    for i in 0..size {
+        let evaluated = eval(tests[i], "test").unwrap();
+
+        match evaluated {
+            Ok(eval) => {
+                panic!("At Test No - {} - expected error, found {:?}", i, eval);
+            }
+            Err(e) => {
+                assert_eq!(e.get_message(), expected_errors[i], "At Test No - {}", i);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_interpolated_string_evaluation() {
+    let tests = [
+        "let name = \"bolt\"; let age = 3; \"hello ${name}, you are ${age + 1}\"",
+        "\"${[1, \"a\"]} ${null} ${true} ${2.0} ${1 / 2}\"",
+        "let h = {\"k\": 5}; \"${ {\"a\": 1}[\"a\"] + h[\"k\"] }\"",
+        "let f = fn(x) { \"<${x}>\" }; \"${f(\"in ${f(1)}\")}\"",
+        "\"cost: \\${price}\"",
+        "let p = 0; \"${ p = p + 1 }${ p = p + 1 }\"",
+        "class A { init() { this.x = 1 } }; \"${A()} ${A}\"",
+        "\"${len}\"",
+    ];
+    let expected_results = vec![
+        "hello bolt, you are 4",
+        "[1, a] null true 2.0 0",
+        "6",
+        "<in <1>>",
+        "cost: ${price}",
+        "12",
+        "A instance class A",
+        "builtin len",
+    ];
+    let size = tests.len();
+    for i in 0..size {
+        let evaluated = eval(tests[i], "test").unwrap();
+
+        match evaluated {
+            Ok(eval) => {
+                assert_eq!(eval.inspect(), expected_results[i], "At Test No - {}", i);
+            }
+            Err(e) => {
+                panic!("At Test No - {} - {:?}", i, e);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_generator_evaluation() {
+    let tests = [
+        "let count = fn(n) { let i = 0; while (i < n) { yield i; i = i + 1 } }; let g = count(2); [g.next(), g.next(), g.next(), g.next()]",
+        "let f = fn() { yield 1; return \"end\" }; let g = f(); g.next(); g.next()",
+        "let f = fn() { yield }; f().next()",
+        "let f = fn() { let total = 0; while (true) { let x = yield total; total = total + x } }; let g = f(); g.next(); g.next(5); g.next(7)[\"value\"]",
+        "let a = [0]; let f = fn() { a[0] = yield 1 }; let g = f(); g.next(); g.next(9); a",
+        "let f = fn(v) { match v { 1 => { yield \"one\" }, _ => { yield \"other\" } } if (v > 1) { yield \"big\" } else { yield \"small\" } }; let g = f(1); [g.next()[\"value\"], g.next()[\"value\"]]",
+        "let f = fn() { let i = 0; while (true) { i = i + 1; if (i == 2) { continue } if (i > 3) { break } yield i } return i }; let g = f(); [g.next()[\"value\"], g.next()[\"value\"], g.next()]",
+        "let f = fn() { try { yield 1; throw \"boom\" } catch (e) { yield e.message } finally { yield \"finally\" } }; let g = f(); [g.next()[\"value\"], g.next()[\"value\"], g.next()[\"value\"], g.next()[\"done\"]]",
+        "let log = []; let f = fn() { try { yield 1; return 2 } finally { log = [\"cleanup\"] } }; let g = f(); [g.next()[\"value\"], g.next()[\"value\"], log]",
+        "class Range { init(n) { this.n = n } items() { let i = 0; while (i < this.n) { yield i; i = i + 1 } } }; let g = Range(1).items(); [g.next(), g.next()]",
+        "let f = fn() { yield 1 }; let g = f(); [type(g), g]",
+        "let f = fn() { throw \"stop\"; yield 1 }; let g = f(); let r = 0; try { g.next() } catch (e) { r = e.message }; [r, g.next()]",
+        "let f = fn(a) { return a + (yield 1) }; let g = f(10); g.next(); g.next(5)[\"value\"]",
+        "let f = fn() { return [yield 1, yield 2] }; let g = f(); g.next(); g.next(\"a\"); g.next(\"b\")[\"value\"]",
+        "let f = fn() { let r = 0; if (yield) { r = 1 } while (r < (yield r)) { r = r + 1 } return r }; let g = f(); g.next(); g.next(true); g.next(3); g.next(3); g.next(0)[\"value\"]",
+        "let a = 1; let f = fn() { return a + (yield a) }; let g = f(); g.next(); a = 100; g.next(2)[\"value\"]",
+    ];
+    let expected_results = vec![
+        "[{value: 0, done: false}, {value: 1, done: false}, {value: null, done: true}, {value: null, done: true}]",
+        "{value: end, done: true}",
+        "{value: null, done: false}",
+        "12",
+        "[9]",
+        "[one, small]",
+        "[1, 3, {value: 4, done: true}]",
+        "[1, boom, finally, true]",
+        "[1, 2, [cleanup]]",
+        "[{value: 0, done: false}, {value: null, done: true}]",
+        "[generator, generator]",
+        "[stop, {value: null, done: true}]",
+        "15",
+        "[a, b]",
+        "3",
+        "3",
+    ];
+    let size = tests.len();
+    for i in 0..size {
+        let evaluated = eval(tests[i], "test").unwrap();
+
+        match evaluated {
+            Ok(eval) => {
+                assert_eq!(eval.inspect(), expected_results[i], "At Test No - {}", i);
+            }
+            Err(e) => {
+                panic!("At Test No - {} - {:?}", i, e);
+            }
+        }
+    }
+
+    let tests = [
+        "let f = fn() { yield 1 }; let g = f(); g.next(1, 2)",
+        "let f = fn() { yield g.next() }; let g = f(); g.next()",
+        "let f = fn() { yield 1 }; f().value",
+        "let f = fn() { yield 1;\nyield 1 + \"a\" }; let g = f(); g.next(); g.next()",
+    ];
+    let expected_errors = vec![
+        (
+            "Wrong number of arguments to next: expected at most 1, found 2",
+            Some(1),
+        ),
+        ("Generator is already running", Some(1)),
+        ("Undefined property value on generator", Some(1)),
+        ("Invalid binary left and right operands", Some(2)),
+    ];
+    let size = tests.len();
+    for i in 0..size {
+        let evaluated = eval(tests[i], "test").unwrap();
+
+        match evaluated {
+            Ok(eval) => {
+                panic!("At Test No - {} - expected error, found {:?}", i, eval);
+            }
+            Err(e) => {
+                assert_eq!(e.get_message(), expected_errors[i].0, "At Test No - {}", i);
+                assert_eq!(e.get_line(), expected_errors[i].1, "At Test No - {}", i);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_async_evaluation() {
+    let tests = [
+        "let f = async fn(x) { x * 2 }; await f(21)",
+        "let log = \"\"; let work = async fn(name, ms) { log = log + name + \" \"; await sleep(ms); log = log + name + \"! \" }; let a = work(\"a\", 20); let b = work(\"b\", 10); log = log + \"sync \"; await a; log",
+        "let f = async fn() { let x = await sleep(5); return x }; await f()",
+        "let f = async fn() { throw \"bad\" }; let g = async fn() { try { await f() } catch (e) { return \"caught \" + e.message } }; await g()",
+        "let f = async fn() { throw \"bad\" }; let r = 0; try { await f() } catch (e) { r = e.message }; r",
+        "let h = fn(x) { x + 1 }; let p = spawn(h, 1); [type(p), await p]",
+        "let f = async fn() { let i = 0; while (i < 3) { await null; i = i + 1 } return i }; await f()",
+        "class C { init(v) { this.v = v } async get() { await sleep(1); this.v } }; await C(3).get()",
+        "let f = async fn() { 1 }; f()",
+        "await 5",
+        "let f = async fn() { return await sleep(1) }; await f()",
+        "let add = fn(a, b) { a + b }; let f = async fn(p) { add(1, await p) + await p }; await f(spawn(fn() { 20 }))",
+        "let f = async fn() { let n = 1; let bump = fn() { n = n + 1; null }; [n, bump(), await sleep(1), n] }; await f()",
+        "let f = async fn(a) { let s = \"${a && await spawn(fn() { 2 })} ${a || await spawn(fn() { 3 })}\"; s }; [await f(true), await f(false)]",
+        "let f = async fn() { let n = 0; let i = 0; while (await spawn(fn(i) { i < 3 }, i)) { i = i + 1; n = n + i } n }; await f()",
+        "let f = async fn(c) { let v = if (c) { let x = await spawn(fn() { 4 }); x * 2 } else { 0 }; match v { 8 => { v + await spawn(fn() { v }) }, _ => { v } } }; [await f(true), await f(false)]",
+        "let f = async fn() { let h = {\"a\": await spawn(fn() { 1 })}; h[\"b\"] = await spawn(fn() { 2 }); -(await spawn(fn() { h[\"a\"] + h[\"b\"] })) }; await f()",
+    ];
+    let expected_results = vec![
+        "42",
+        "sync a b b! a! ",
+        "null",
+        "caught bad",
+        "bad",
+        "[promise, 2]",
+        "3",
+        "3",
+        "promise fulfilled 1",
+        "5",
+        "null",
+        "41",
+        "[1, null, null, 2]",
+        "[2 true, false 3]",
+        "6",
+        "[16, 0]",
+        "-3",
+    ];
+    let size = tests.len();
+    for i in 0..size {
+        let evaluated = eval(tests[i], "test").unwrap();
+
+        match evaluated {
+            Ok(eval) => {
+                assert_eq!(eval.inspect(), expected_results[i], "At Test No - {}", i);
+            }
+            Err(e) => {
+                panic!("At Test No - {} - {:?}", i, e);
+            }
+        }
+    }
+
+    let tests = [
+        "let f = async fn() {\nthrow \"lost\" }; f(); 1",
+        "let p = null; let f = async fn() { await p }; p = f(); await p",
+        "sleep(-1)",
+        "spawn(fn() { yield 1 })",
+        "spawn(fn(x) { x }, 1, 2)",
+    ];
+    let expected_errors = vec![
+        ("lost", Some(2)),
+        ("Awaited promise can never settle", Some(1)),
+        (
+            "Argument to sleep must be a non negative number, found -1",
+            Some(1),
+        ),
+        ("A generator can not be spawned", Some(1)),
+        (
+            "Wrong number of arguments to the spawned function: expected 1, found 2",
+            Some(1),
+        ),
+    ];
+    let size = tests.len();
+    for i in 0..size {
+        let evaluated = eval(tests[i], "test").unwrap();
+
+        match evaluated {
+            Ok(eval) => {
+                panic!("At Test No - {} - expected error, found {:?}", i, eval);
+            }
+            Err(e) => {
+                assert_eq!(e.get_message(), expected_errors[i].0, "At Test No - {}", i);
+                assert_eq!(e.get_line(), expected_errors[i].1, "At Test No - {}", i);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_resolver_evaluation() {
+    let tests = [
+        "let f = fn(a) { let b = a + 1; let g = fn() { a + b }; g() }; f(1)",
+        "let f = fn() { let g = fn(n) { if (n == 0) { 0 } else { n + g(n - 1) } }; g(4) }; f()",
+        "let f = fn() { let count = 0; let inc = fn() { count = count + 1 }; inc(); inc(); count }; f()",
+        "let f = fn() { let early = fn() { late }; let late = 7; early() }; f()",
+        "let x = 1; let f = fn() { let x = 2; x }; [f(), x]",
+        "let f = fn(v) { match v { [a, b] => { let c = a * b; c }, a => a } }; [f([2, 3]), f(4)]",
+        "let f = fn() { try { throw \"e\" } catch (e) { let m = e.message; m } }; f()",
+        "let f = fn() { g() }; let g = fn() { 5 }; f()",
+        "let f = fn(v) { let x = 1; let r = match v { x => fn() { x } }; [r(), x] }; f(5)",
+        "let f = fn() { let e = 1; let r = null; try { throw \"t\" } catch (e) { r = fn() { e.message } }; [r(), e] }; f()",
+        "let f = fn() { let n = 2; class A { get() { n } }; A().get() }; f()",
+        "class A { v() { 1 } }; let f = fn() { let n = 3; class B < A { v() { super.v() + n } }; B().v() }; f()",
+        "if (true) { let m = 1; m } else { let m = 2; m }",
+        "let f = fn(c) { if (c) { let m = 1; m } else { let m = 2; m } }; [f(true), f(false)]",
+        "let i = 0; while (i < 2) { let t = i; i = i + 1 }; let t = 5; t",
+        "let f = fn() { let i = 0; while (i < 2) { let t = i; i = i + 1 }; let t = t + 5; t }; f()",
+    ];
+    let expected_results = vec![
+        "3", "10", "2", "7", "[2, 1]", "[6, 4]", "e", "5", "[5, 1]", "[t, 1]", "2", "4", "1",
+        "[1, 2]", "5", "6",
+    ];
+    let size = tests.len();
+    for i in 0..size {
+        let evaluated = eval(tests[i], "test").unwrap();
+
+        match evaluated {
+            Ok(eval) => {
+                assert_eq!(eval.inspect(), expected_results[i], "At Test No - {}", i);
+            }
+            Err(e) => {
+                panic!("At Test No - {} - {:?}", i, e);
+            }
+        }
+    }
+
+    let tests = [
+        "print(y);\nlet y = 1;",
+        "let f = fn() {\nlet a = b; let b = 1; };",
+        "let x = 1;\nlet x = 2;",
+        "let f = fn(a, a) { a };",
+        "let f = fn(a) {\nlet a = 2; };",
+        "try { 1 } catch (e) {\nlet e = 2; }",
+        "let f = fn() {\nlet x = x; };",
+        "if (true) { let m = 1;\nlet m = 2; }",
+        "let f = fn() { while (true) { let t = 1;\nlet t = 2; } };",
+    ];
+    let expected_errors = vec![
+        ("Cannot use variable y before its declaration", Some(1)),
+        ("Cannot use variable b before its declaration", Some(2)),
+        ("Variable x is already declared in this scope", Some(2)),
+        ("Variable a is already declared in this scope", Some(1)),
+        ("Variable a is already declared in this scope", Some(2)),
+        ("Variable e is already declared in this scope", Some(2)),
+        ("Cannot use variable x before its declaration", Some(2)),
+        ("Variable m is already declared in this scope", Some(2)),
+        ("Variable t is already declared in this scope", Some(2)),
+    ];
+    let size = tests.len();
+    for i in 0..size {
+        let evaluated = eval(tests[i], "test").unwrap();
+
+        match evaluated {
+            Ok(eval) => {
+                panic!("At Test No - {} - expected error, found {:?}", i, eval);
+            }
+            Err(e) => {
+                assert_eq!(e.get_type().name(), "ResolveError", "At Test No - {}", i);
+                assert_eq!(e.get_message(), expected_errors[i].0, "At Test No - {}", i);
+                assert_eq!(e.get_line(), expected_errors[i].1, "At Test No - {}", i);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_uninitialized_local_evaluation() {
+    let tests = [
+        "let f = fn(c) { if (c) { let z = 1 }; z }; f(false)",
+        "let f = fn(c) { if (c) { let z = 1 }; z = 5; z }; f(false)",
+        "let f = fn() { let g = fn() { z }; let r = g(); let z = 1; r }; f()",
+        "let f = fn(c) { let r = 0; let i = 0; while (i < 2) { r = match i { n => { if (n == 0) { let z = 1 }; z } }; i = i + 1 }; r }; f(0)",
+    ];
+    let expected_errors = vec![
+        "Error getting environment variable z",
+        "Cannot assign to undeclared variable z",
+        "Error getting environment variable z",
+        "Error getting environment variable z",
+    ];
+    let size = tests.len();
+    for i in 0..size {
+        let evaluated = eval(tests[i], "test").unwrap();
+
+        match evaluated {
+            Ok(eval) => {
+                panic!("At Test No - {} - expected error, found {:?}", i, eval);
+            }
+            Err(e) => {
+                assert_eq!(e.get_message(), expected_errors[i], "At Test No - {}", i);
+            }
+        }
+    }
+
+    let evaluated = eval(
+        "let f = fn(c) { if (c) { let len = 1 }; len([1]) }; f(false)",
+        "test",
+    );
+    match evaluated.unwrap() {
+        Ok(eval) => assert_eq!(eval.inspect(), "1"),
+        Err(e) => panic!("{:?}", e),
+    }
+}
+
+#[test]
+fn test_call_depth_evaluation() {
+    //Recursion up to the limit needs more native stack than test threads get in debug builds
+    let thread = std::thread::Builder::new().stack_size(128 * 1024 * 1024);
+    let handle = thread
+        .spawn(|| {
+            let tests = [
+                "let f = fn(n) { if (n == 0) { return 0 }; f(n - 1) }; f(999)",
+                "let f = fn() { f() }; let r = 0; try { f() } catch (e) { r = e.message }; r",
+                "let f = fn(n) { if (n == 0) { return 0 }; f(n - 1) }; try { f(5000) } catch (e) { 0 }; f(10)",
+                "let g = fn(n) { if (n > 0) { let it = g(n - 1); let v = it.next(); } yield n; }; g(500).next()[\"value\"]",
+                "let g = fn(n) { if (n > 0) { let it = g(n - 1); let v = it.next(); } yield n; }; let r = 0; try { g(100000).next() } catch (e) { r = e.message }; r",
+            ];
+            let expected_results = vec![
+                "0",
+                "Maximum call depth exceeded",
+                "0",
+                "500",
+                "Maximum call depth exceeded",
+            ];
+            for i in 0..tests.len() {
+                match eval(tests[i], "test").unwrap() {
+                    Ok(eval) => assert_eq!(eval.inspect(), expected_results[i], "At Test No - {}", i),
+                    Err(e) => panic!("At Test No - {} - {:?}", i, e),
+                }
+            }
+
+            let tests = [
+                "let f = fn(n) { if (n == 0) { return 0 }; f(n - 1) }; f(1000)",
+                "let g = fn(n) { if (n > 0) { let it = g(n - 1); let v = it.next(); } yield n; }; g(100000).next()",
+            ];
+            for i in 0..tests.len() {
+                match eval(tests[i], "test").unwrap() {
+                    Ok(eval) => panic!("At Test No - {} - expected error, found {:?}", i, eval),
+                    Err(e) => {
+                        assert_eq!(e.get_type().name(), "RuntimeError", "At Test No - {}", i);
+                        assert_eq!(
+                            e.get_message(),
+                            "Maximum call depth exceeded",
+                            "At Test No - {}",
+                            i
+                        );
+                    }
+                }
+            }
+        })
+        .unwrap();
+    handle.join().unwrap();
+}
+
+#[test]
+fn test_gc_evaluation() {
+    let tests = [
+        "let f = fn() { let a = [0]; a[0] = a; 0 }; let before = gc()[\"collected\"]; f(); f(); gc()[\"collected\"] - before",
+        "let f = fn() { let h = {}; h[\"me\"] = h; 0 }; let before = gc()[\"collected\"]; f(); gc()[\"collected\"] - before",
+        "class Node { init() { this.me = this } }; let f = fn() { Node(); 0 }; let before = gc()[\"collected\"]; f(); gc()[\"collected\"] - before",
+        "let f = fn() { let g = fn() { g }; 0 }; let before = gc()[\"collected\"]; f(); gc()[\"collected\"] - before > 0",
+        "let keep = [0]; keep[0] = keep; gc(); len(keep[0])",
+        "let make = fn() { let n = 0; fn() { n = n + 1; n } }; let c = make(); c(); gc(); c()",
+        "let s = gc(); [type(s[\"allocated\"]), s[\"collections\"] > 0, s[\"live\"] > 0]",
+    ];
+    let expected_results = vec!["2", "1", "1", "true", "1", "2", "[integer, true, true]"];
+    let size = tests.len();
+    for i in 0..size {
+        let evaluated = eval(tests[i], "test").unwrap();
+
+        match evaluated {
+            Ok(eval) => {
+                assert_eq!(eval.inspect(), expected_results[i], "At Test No - {}", i);
+            }
+            Err(e) => {
+                panic!("At Test No - {} - {:?}", i, e);
+            }
+        }
+    }
+
+    let evaluated = eval("gc(1)", "test").unwrap();
+    match evaluated {
+        Ok(eval) => panic!("Expected error, found {:?}", eval),
+        Err(e) => assert_eq!(
+            e.get_message(),
+            "Wrong number of arguments to gc: expected 0, found 1"
+        ),
+    }
+}
@@ -1,3 +1,4 @@
+use bolt::error::BoltError;
 use bolt::lexer::{
     lexer::Lexer,
     token::{Token, TokenType},
@@ -11,7 +12,7 @@ fn next_token() {
         x + y;
         };
         let final_result = add(five, ten); 
-        !-/*5;
+        !-/ *5;
         5 < 10 > 5;
         if (5 < 10) {
             return true;
@@ -28,387 +29,387 @@ fn next_token() {
         Token {
             token_type: TokenType::LET,
             literal: String::from("let"),
-            line: 0,
+            line: 1,
         },
         Token {
             token_type: TokenType::IDENTIFIER,
             literal: String::from("five"),
-            line: 0,
+            line: 1,
         },
         Token {
             token_type: TokenType::ASSIGN,
             literal: String::from("="),
-            line: 0,
+            line: 1,
         },
         Token {
             token_type: TokenType::LPAREN,
             literal: String::from("("),
-            line: 0,
+            line: 1,
         },
         Token {
             token_type: TokenType::INT,
             literal: String::from("5"),
-            line: 0,
+            line: 1,
         },
         Token {
             token_type: TokenType::RPAREN,
             literal: String::from(")"),
-            line: 0,
+            line: 1,
         },
         Token {
             token_type: TokenType::SEMICOLON,
             literal: String::from(";"),
-            line: 0,
+            line: 1,
         },
         Token {
             token_type: TokenType::LET,
             literal: String::from("let"),
-            line: 1,
+            line: 2,
         },
         Token {
             token_type: TokenType::IDENTIFIER,
             literal: String::from("ten"),
-            line: 1,
+            line: 2,
         },
         Token {
             token_type: TokenType::ASSIGN,
             literal: String::from("="),
-            line: 1,
+            line: 2,
         },
         Token {
             token_type: TokenType::INT,
             literal: String::from("10"),
-            line: 1,
+            line: 2,
         },
         Token {
             token_type: TokenType::SEMICOLON,
             literal: String::from(";"),
-            line: 1,
+            line: 2,
         },
         Token {
             token_type: TokenType::LET,
             literal: String::from("let"),
-            line: 2,
+            line: 3,
         },
         Token {
             token_type: TokenType::IDENTIFIER,
             literal: String::from("add"),
-            line: 2,
+            line: 3,
         },
         Token {
             token_type: TokenType::ASSIGN,
             literal: String::from("="),
-            line: 2,
+            line: 3,
         },
         Token {
             token_type: TokenType::FUNCTION,
             literal: String::from("fn"),
-            line: 2,
+            line: 3,
         },
         Token {
             token_type: TokenType::LPAREN,
             literal: String::from("("),
-            line: 2,
+            line: 3,
         },
         Token {
             token_type: TokenType::IDENTIFIER,
             literal: String::from("x"),
-            line: 2,
+            line: 3,
         },
         Token {
             token_type: TokenType::COMMA,
             literal: String::from(","),
-            line: 2,
+            line: 3,
         },
         Token {
             token_type: TokenType::IDENTIFIER,
             literal: String::from("y"),
-            line: 2,
+            line: 3,
         },
         Token {
             token_type: TokenType::RPAREN,
             literal: String::from(")"),
-            line: 2,
+            line: 3,
         },
         Token {
             token_type: TokenType::LBRACE,
             literal: String::from("{"),
-            line: 2,
+            line: 3,
         },
         Token {
             token_type: TokenType::IDENTIFIER,
             literal: String::from("x"),
-            line: 3,
+            line: 4,
         },
         Token {
             token_type: TokenType::PLUS,
             literal: String::from("+"),
-            line: 3,
+            line: 4,
         },
         Token {
             token_type: TokenType::IDENTIFIER,
             literal: String::from("y"),
-            line: 3,
+            line: 4,
         },
         Token {
             token_type: TokenType::SEMICOLON,
             literal: String::from(";"),
-            line: 3,
+            line: 4,
         },
         Token {
             token_type: TokenType::RBRACE,
             literal: String::from("}"),
-            line: 4,
+            line: 5,
         },
         Token {
             token_type: TokenType::SEMICOLON,
             literal: String::from(";"),
-            line: 4,
+            line: 5,
         },
         Token {
             token_type: TokenType::LET,
             literal: String::from("let"),
-            line: 5,
+            line: 6,
         },
         Token {
             token_type: TokenType::IDENTIFIER,
             literal: String::from("final_result"),
-            line: 5,
+            line: 6,
         },
         Token {
             token_type: TokenType::ASSIGN,
             literal: String::from("="),
-            line: 5,
+            line: 6,
         },
         Token {
             token_type: TokenType::IDENTIFIER,
             literal: String::from("add"),
-            line: 5,
+            line: 6,
         },
         Token {
             token_type: TokenType::LPAREN,
             literal: String::from("("),
-            line: 5,
+            line: 6,
         },
         Token {
             token_type: TokenType::IDENTIFIER,
             literal: String::from("five"),
-            line: 5,
+            line: 6,
         },
         Token {
             token_type: TokenType::COMMA,
             literal: String::from(","),
-            line: 5,
+            line: 6,
         },
         Token {
             token_type: TokenType::IDENTIFIER,
             literal: String::from("ten"),
-            line: 5,
+            line: 6,
         },
         Token {
             token_type: TokenType::RPAREN,
             literal: String::from(")"),
-            line: 5,
+            line: 6,
         },
         Token {
             token_type: TokenType::SEMICOLON,
             literal: String::from(";"),
-            line: 5,
+            line: 6,
         },
         Token {
             token_type: TokenType::BANG,
             literal: String::from("!"),
-            line: 6,
+            line: 7,
         },
         Token {
             token_type: TokenType::MINUS,
             literal: String::from("-"),
-            line: 6,
+            line: 7,
         },
         Token {
             token_type: TokenType::SLASH,
             literal: String::from("/"),
-            line: 6,
+            line: 7,
         },
         Token {
             token_type: TokenType::ASTERISK,
             literal: String::from("*"),
-            line: 6,
+            line: 7,
         },
         Token {
             token_type: TokenType::INT,
             literal: String::from("5"),
-            line: 6,
+            line: 7,
         },
         Token {
             token_type: TokenType::SEMICOLON,
             literal: String::from(";"),
-            line: 6,
+            line: 7,
         },
         Token {
             token_type: TokenType::INT,
             literal: String::from("5"),
-            line: 7,
+            line: 8,
         },
         Token {
             token_type: TokenType::LT,
             literal: String::from("<"),
-            line: 7,
+            line: 8,
         },
         Token {
             token_type: TokenType::INT,
             literal: String::from("10"),
-            line: 7,
+            line: 8,
         },
         Token {
             token_type: TokenType::GT,
             literal: String::from(">"),
-            line: 7,
+            line: 8,
         },
         Token {
             token_type: TokenType::INT,
             literal: String::from("5"),
-            line: 7,
+            line: 8,
         },
         Token {
             token_type: TokenType::SEMICOLON,
             literal: String::from(";"),
-            line: 7,
+            line: 8,
         },
         Token {
             token_type: TokenType::IF,
             literal: String::from("if"),
-            line: 8,
+            line: 9,
         },
         Token {
             token_type: TokenType::LPAREN,
             literal: String::from("("),
-            line: 8,
+            line: 9,
         },
         Token {
             token_type: TokenType::INT,
             literal: String::from("5"),
-            line: 8,
+            line: 9,
         },
         Token {
             token_type: TokenType::LT,
             literal: String::from("<"),
-            line: 8,
+            line: 9,
         },
         Token {
             token_type: TokenType::INT,
             literal: String::from("10"),
-            line: 8,
+            line: 9,
         },
         Token {
             token_type: TokenType::RPAREN,
             literal: String::from(")"),
-            line: 8,
+            line: 9,
         },
         Token {
             token_type: TokenType::LBRACE,
             literal: String::from("{"),
-            line: 8,
+            line: 9,
         },
         Token {
             token_type: TokenType::RETURN,
             literal: String::from("return"),
-            line: 9,
+            line: 10,
         },
         Token {
             token_type: TokenType::TRUE,
             literal: String::from("true"),
-            line: 9,
+            line: 10,
         },
         Token {
             token_type: TokenType::SEMICOLON,
             literal: String::from(";"),
-            line: 9,
+            line: 10,
         },
         Token {
             token_type: TokenType::RBRACE,
             literal: String::from("}"),
-            line: 10,
+            line: 11,
         },
         Token {
             token_type: TokenType::ELSE,
             literal: String::from("else"),
-            line: 10,
+            line: 11,
         },
         Token {
             token_type: TokenType::LBRACE,
             literal: String::from("{"),
-            line: 10,
+            line: 11,
         },
         Token {
             token_type: TokenType::RETURN,
             literal: String::from("return"),
-            line: 11,
+            line: 12,
         },
         Token {
             token_type: TokenType::FALSE,
             literal: String::from("false"),
-            line: 11,
+            line: 12,
         },
         Token {
             token_type: TokenType::SEMICOLON,
             literal: String::from(";"),
-            line: 11,
+            line: 12,
         },
         Token {
             token_type: TokenType::RBRACE,
             literal: String::from("}"),
-            line: 12,
+            line: 13,
         },
         Token {
             token_type: TokenType::INT,
             literal: String::from("10"),
-            line: 13,
+            line: 14,
         },
         Token {
             token_type: TokenType::EQ,
             literal: String::from("=="),
-            line: 13,
+            line: 14,
         },
         Token {
             token_type: TokenType::INT,
             literal: String::from("10"),
-            line: 13,
+            line: 14,
         },
         Token {
             token_type: TokenType::SEMICOLON,
             literal: String::from(";"),
-            line: 13,
+            line: 14,
         },
         Token {
             token_type: TokenType::INT,
             literal: String::from("10"),
-            line: 14,
+            line: 15,
         },
         Token {
             token_type: TokenType::NOTEQ,
             literal: String::from("!="),
-            line: 14,
+            line: 15,
         },
         Token {
             token_type: TokenType::INT,
             literal: String::from("9"),
-            line: 14,
+            line: 15,
         },
         Token {
             token_type: TokenType::SEMICOLON,
             literal: String::from(";"),
-            line: 14,
+            line: 15,
         },
         Token {
             token_type: TokenType::INT,
             literal: String::from("12"),
-            line: 16,
+            line: 17,
         },
         Token {
             token_type: TokenType::SEMICOLON,
             literal: String::from(";"),
-            line: 16,
+            line: 17,
         },
     ];
     loop {
@@ -420,3 +421,503 @@ fn next_token() {
     }
     assert_eq!(tokens, test_tokens);
 }
+
+#[test]
+fn next_token_brackets() {
+    let mut lexer = Lexer::new("[1, 2][0]");
+    let expected_types = vec![
+        TokenType::LBRACKET,
+        TokenType::INT,
+        TokenType::COMMA,
+        TokenType::INT,
+        TokenType::RBRACKET,
+        TokenType::LBRACKET,
+        TokenType::INT,
+        TokenType::RBRACKET,
+        TokenType::EOF,
+    ];
+    let tokens = lexer.get_tokens();
+    let token_types: Vec<TokenType> = tokens.iter().map(|token| token.token_type).collect();
+    assert_eq!(token_types, expected_types);
+}
+
+#[test]
+fn next_token_hash() {
+    let mut lexer = Lexer::new("{\"a\": 1}");
+    let expected_types = vec![
+        TokenType::LBRACE,
+        TokenType::STRING,
+        TokenType::COLON,
+        TokenType::INT,
+        TokenType::RBRACE,
+        TokenType::EOF,
+    ];
+    let tokens = lexer.get_tokens();
+    let token_types: Vec<TokenType> = tokens.iter().map(|token| token.token_type).collect();
+    assert_eq!(token_types, expected_types);
+}
+
+#[test]
+fn next_token_loop_keywords() {
+    let mut lexer = Lexer::new("while (x) { break; continue; }");
+    let expected_types = vec![
+        TokenType::WHILE,
+        TokenType::LPAREN,
+        TokenType::IDENTIFIER,
+        TokenType::RPAREN,
+        TokenType::LBRACE,
+        TokenType::BREAK,
+        TokenType::SEMICOLON,
+        TokenType::CONTINUE,
+        TokenType::SEMICOLON,
+        TokenType::RBRACE,
+        TokenType::EOF,
+    ];
+    let tokens = lexer.get_tokens();
+    let token_types: Vec<TokenType> = tokens.iter().map(|token| token.token_type).collect();
+    assert_eq!(token_types, expected_types);
+}
+
+#[test]
+fn next_token_logical_operators() {
+    let mut lexer = Lexer::new("a && b || !c");
+    let expected_types = vec![
+        TokenType::IDENTIFIER,
+        TokenType::AND,
+        TokenType::IDENTIFIER,
+        TokenType::OR,
+        TokenType::BANG,
+        TokenType::IDENTIFIER,
+        TokenType::EOF,
+    ];
+    let tokens = lexer.get_tokens();
+    let token_types: Vec<TokenType> = tokens.iter().map(|token| token.token_type).collect();
+    assert_eq!(token_types, expected_types);
+}
+
+#[test]
+fn next_token_modules() {
+    let mut lexer = Lexer::new("import \"lib\" as lib; export let x = lib.y;");
+    let expected_types = vec![
+        TokenType::IMPORT,
+        TokenType::STRING,
+        TokenType::AS,
+        TokenType::IDENTIFIER,
+        TokenType::SEMICOLON,
+        TokenType::EXPORT,
+        TokenType::LET,
+        TokenType::IDENTIFIER,
+        TokenType::ASSIGN,
+        TokenType::IDENTIFIER,
+        TokenType::DOT,
+        TokenType::IDENTIFIER,
+        TokenType::SEMICOLON,
+        TokenType::EOF,
+    ];
+    let tokens = lexer.get_tokens();
+    let token_types: Vec<TokenType> = tokens.iter().map(|token| token.token_type).collect();
+    assert_eq!(token_types, expected_types);
+}
+
+#[test]
+fn next_token_classes() {
+    let mut lexer = Lexer::new("class Point { init(x) { this.x = x; } }");
+    let expected_types = vec![
+        TokenType::CLASS,
+        TokenType::IDENTIFIER,
+        TokenType::LBRACE,
+        TokenType::IDENTIFIER,
+        TokenType::LPAREN,
+        TokenType::IDENTIFIER,
+        TokenType::RPAREN,
+        TokenType::LBRACE,
+        TokenType::THIS,
+        TokenType::DOT,
+        TokenType::IDENTIFIER,
+        TokenType::ASSIGN,
+        TokenType::IDENTIFIER,
+        TokenType::SEMICOLON,
+        TokenType::RBRACE,
+        TokenType::RBRACE,
+        TokenType::EOF,
+    ];
+    let tokens = lexer.get_tokens();
+    let token_types: Vec<TokenType> = tokens.iter().map(|token| token.token_type).collect();
+    assert_eq!(token_types, expected_types);
+}
+
+#[test]
+fn next_token_inheritance() {
+    let mut lexer = Lexer::new("class B < A { m() { super.m() } }");
+    let expected_types = vec![
+        TokenType::CLASS,
+        TokenType::IDENTIFIER,
+        TokenType::LT,
+        TokenType::IDENTIFIER,
+        TokenType::LBRACE,
+        TokenType::IDENTIFIER,
+        TokenType::LPAREN,
+        TokenType::RPAREN,
+        TokenType::LBRACE,
+        TokenType::SUPER,
+        TokenType::DOT,
+        TokenType::IDENTIFIER,
+        TokenType::LPAREN,
+        TokenType::RPAREN,
+        TokenType::RBRACE,
+        TokenType::RBRACE,
+        TokenType::EOF,
+    ];
+    let tokens = lexer.get_tokens();
+    let token_types: Vec<TokenType> = tokens.iter().map(|token| token.token_type).collect();
+    assert_eq!(token_types, expected_types);
+}
+
+#[test]
+fn next_token_match() {
+    let mut lexer = Lexer::new("match x { 1 | 2 => a, _ => b }");
+    let expected_types = vec![
+        TokenType::MATCH,
+        TokenType::IDENTIFIER,
+        TokenType::LBRACE,
+        TokenType::INT,
+        TokenType::PIPE,
+        TokenType::INT,
+        TokenType::ARROW,
+        TokenType::IDENTIFIER,
+        TokenType::COMMA,
+        TokenType::IDENTIFIER,
+        TokenType::ARROW,
+        TokenType::IDENTIFIER,
+        TokenType::RBRACE,
+        TokenType::EOF,
+    ];
+    let tokens = lexer.get_tokens();
+    let token_types: Vec<TokenType> = tokens.iter().map(|token| token.token_type).collect();
+    assert_eq!(token_types, expected_types);
+}
+
+#[test]
+fn next_token_exceptions() {
+    let mut lexer = Lexer::new("try { throw e; } catch (e) { } finally { }");
+    let expected_types = vec![
+        TokenType::TRY,
+        TokenType::LBRACE,
+        TokenType::THROW,
+        TokenType::IDENTIFIER,
+        TokenType::SEMICOLON,
+        TokenType::RBRACE,
+        TokenType::CATCH,
+        TokenType::LPAREN,
+        TokenType::IDENTIFIER,
+        TokenType::RPAREN,
+        TokenType::LBRACE,
+        TokenType::RBRACE,
+        TokenType::FINALLY,
+        TokenType::LBRACE,
+        TokenType::RBRACE,
+        TokenType::EOF,
+    ];
+    let tokens = lexer.get_tokens();
+    let token_types: Vec<TokenType> = tokens.iter().map(|token| token.token_type).collect();
+    assert_eq!(token_types, expected_types);
+}
+
+#[test]
+fn next_token_numbers() {
+    let mut lexer = Lexer::new("10 10.0 3.25 a.b 1.x");
+    let expected = vec![
+        (TokenType::INT, "10"),
+        (TokenType::FLOAT, "10.0"),
+        (TokenType::FLOAT, "3.25"),
+        (TokenType::IDENTIFIER, "a"),
+        (TokenType::DOT, "."),
+        (TokenType::IDENTIFIER, "b"),
+        (TokenType::INT, "1"),
+        (TokenType::DOT, "."),
+        (TokenType::IDENTIFIER, "x"),
+        (TokenType::EOF, "\0"),
+    ];
+    let tokens = lexer.get_tokens();
+    let found: Vec<(TokenType, &str)> = tokens
+        .iter()
+        .map(|token| (token.token_type, token.literal.as_str()))
+        .collect();
+    assert_eq!(found, expected);
+}
+
+#[test]
+fn next_token_number_forms() {
+    let mut lexer = Lexer::new("0xFF 0b1010 0o17 1_000_000 1e3 2.5E-3 6.02e+23 1_0.2_5 0 0.5 1..2");
+    let expected = vec![
+        (TokenType::INT, "0xFF"),
+        (TokenType::INT, "0b1010"),
+        (TokenType::INT, "0o17"),
+        (TokenType::INT, "1_000_000"),
+        (TokenType::FLOAT, "1e3"),
+        (TokenType::FLOAT, "2.5E-3"),
+        (TokenType::FLOAT, "6.02e+23"),
+        (TokenType::FLOAT, "1_0.2_5"),
+        (TokenType::INT, "0"),
+        (TokenType::FLOAT, "0.5"),
+        (TokenType::INT, "1"),
+        (TokenType::DOT, "."),
+        (TokenType::DOT, "."),
+        (TokenType::INT, "2"),
+        (TokenType::EOF, "\0"),
+    ];
+    let tokens = lexer.get_tokens();
+    let found: Vec<(TokenType, &str)> = tokens
+        .iter()
+        .map(|token| (token.token_type, token.literal.as_str()))
+        .collect();
+    assert_eq!(found, expected);
+    assert!(lexer.get_errors().is_empty());
+}
+
+#[test]
+fn next_token_number_errors() {
+    let tests = [
+        "0x",
+        "0b102",
+        "0o8",
+        "0xfg",
+        "1__000",
+        "1_",
+        "007",
+        "1e",
+        "2.5e+",
+        "12ab",
+        "let a = 1;\n\nlet b = 0b2;",
+    ];
+    let expected_errors = vec![
+        ("Missing digits in hexadecimal literal 0x at line 1", 1),
+        ("Invalid digit 2 in binary literal 0b102 at line 1", 1),
+        ("Invalid digit 8 in octal literal 0o8 at line 1", 1),
+        ("Invalid digit g in hexadecimal literal 0xfg at line 1", 1),
+        ("Misplaced _ in number literal 1__000 at line 1", 1),
+        ("Misplaced _ in number literal 1_ at line 1", 1),
+        (
+            "Leading zeros are not allowed in number literal 007 at line 1",
+            1,
+        ),
+        ("Missing exponent digits in number literal 1e at line 1", 1),
+        (
+            "Missing exponent digits in number literal 2.5e+ at line 1",
+            1,
+        ),
+        ("Invalid character a in number literal 12ab at line 1", 1),
+        ("Invalid digit 2 in binary literal 0b2 at line 3", 3),
+    ];
+    for i in 0..tests.len() {
+        let mut lexer = Lexer::new(tests[i]);
+        let tokens = lexer.get_tokens();
+        let illegal: Vec<&Token> = tokens
+            .iter()
+            .filter(|token| token.token_type == TokenType::ILLIGAL)
+            .collect();
+        assert_eq!(illegal.len(), 1, "At Test No - {}", i);
+        assert_eq!(illegal[0].line, expected_errors[i].1, "At Test No - {}", i);
+        let errors = lexer.get_errors();
+        assert_eq!(errors.len(), 1, "At Test No - {}", i);
+        assert_eq!(
+            errors[0].get_message(),
+            expected_errors[i].0,
+            "At Test No - {}",
+            i
+        );
+        assert_eq!(
+            errors[0].get_line(),
+            Some(expected_errors[i].1),
+            "At Test No - {}",
+            i
+        );
+    }
+}
+
+#[test]
+fn next_token_strings() {
+    let mut lexer = Lexer::new(
+        "\"tab\\there\" \"quote \\\" and \\\\ slash\" \"\\u{1F600}\\u{e9}\\n\\r\\0\" r\"raw \\n\" r#\"say \"hi\"\"# \"two\nlines\" r\"a\nb\" x",
+    );
+    let expected = vec![
+        (TokenType::STRING, "tab\there", 1),
+        (TokenType::STRING, "quote \" and \\ slash", 1),
+        (TokenType::STRING, "\u{1F600}\u{e9}\n\r\0", 1),
+        (TokenType::STRING, "raw \\n", 1),
+        (TokenType::STRING, "say \"hi\"", 1),
+        (TokenType::STRING, "two\nlines", 1),
+        (TokenType::STRING, "a\nb", 2),
+        (TokenType::IDENTIFIER, "x", 3),
+        (TokenType::EOF, "\0", 3),
+    ];
+    let tokens = lexer.get_tokens();
+    let found: Vec<(TokenType, &str, usize)> = tokens
+        .iter()
+        .map(|token| (token.token_type, token.literal.as_str(), token.line))
+        .collect();
+    assert_eq!(found, expected);
+    assert!(lexer.get_errors().is_empty());
+}
+
+#[test]
+fn next_token_string_errors() {
+    let tests = [
+        "\"bad \\q\"",
+        "\"\\u1234\"",
+        "\"\\u{}\"",
+        "\"\\u{1234567}\"",
+        "\"\\u{D800}\"",
+        "\"\\u{zz}\"",
+        "let a = 1;\n\"open",
+        "r#\"open\"",
+        "\"one\n\\x\"",
+    ];
+    let expected_errors = vec![
+        ("Invalid escape sequence \\q in string at line 1", 1),
+        ("Invalid escape sequence \\u in string at line 1", 1),
+        ("Invalid escape sequence \\u{} in string at line 1", 1),
+        (
+            "Invalid escape sequence \\u{1234567} in string at line 1",
+            1,
+        ),
+        ("Invalid escape sequence \\u{D800} in string at line 1", 1),
+        ("Invalid escape sequence \\u{zz} in string at line 1", 1),
+        ("Unterminated string starting at line 2", 2),
+        ("Unterminated raw string starting at line 1", 1),
+        ("Invalid escape sequence \\x in string at line 2", 1),
+    ];
+    for i in 0..tests.len() {
+        let mut lexer = Lexer::new(tests[i]);
+        let tokens = lexer.get_tokens();
+        let illegal: Vec<&Token> = tokens
+            .iter()
+            .filter(|token| token.token_type == TokenType::ILLIGAL)
+            .collect();
+        assert_eq!(illegal.len(), 1, "At Test No - {}", i);
+        assert_eq!(illegal[0].line, expected_errors[i].1, "At Test No - {}", i);
+        let errors = lexer.get_errors();
+        assert_eq!(errors.len(), 1, "At Test No - {}", i);
+        assert_eq!(
+            errors[0].get_message(),
+            expected_errors[i].0,
+            "At Test No - {}",
+            i
+        );
+    }
+}
+
+#[test]
+fn next_token_unicode_identifiers() {
+    let mut lexer = Lexer::new("let café = größe + _x1 + 変数; π");
+    let expected = vec![
+        (TokenType::LET, "let"),
+        (TokenType::IDENTIFIER, "café"),
+        (TokenType::ASSIGN, "="),
+        (TokenType::IDENTIFIER, "größe"),
+        (TokenType::PLUS, "+"),
+        (TokenType::IDENTIFIER, "_x1"),
+        (TokenType::PLUS, "+"),
+        (TokenType::IDENTIFIER, "変数"),
+        (TokenType::SEMICOLON, ";"),
+        (TokenType::IDENTIFIER, "π"),
+        (TokenType::EOF, "\0"),
+    ];
+    let tokens = lexer.get_tokens();
+    let found: Vec<(TokenType, &str)> = tokens
+        .iter()
+        .map(|token| (token.token_type, token.literal.as_str()))
+        .collect();
+    assert_eq!(found, expected);
+}
+
+#[test]
+fn next_token_interpolated_strings() {
+    let mut lexer =
+        Lexer::new("\"hi ${name}, ${ {\"a\": 1}[\"a\"] + 1}!\" \"${\"in ${x}\"}\" \"\\${no}\"");
+    let expected = vec![
+        (TokenType::TEMPLATE, "hi "),
+        (TokenType::IDENTIFIER, "name"),
+        (TokenType::TEMPLATEMIDDLE, ", "),
+        (TokenType::LBRACE, "{"),
+        (TokenType::STRING, "a"),
+        (TokenType::COLON, ":"),
+        (TokenType::INT, "1"),
+        (TokenType::RBRACE, "}"),
+        (TokenType::LBRACKET, "["),
+        (TokenType::STRING, "a"),
+        (TokenType::RBRACKET, "]"),
+        (TokenType::PLUS, "+"),
+        (TokenType::INT, "1"),
+        (TokenType::TEMPLATEEND, "!"),
+        (TokenType::TEMPLATE, ""),
+        (TokenType::TEMPLATE, "in "),
+        (TokenType::IDENTIFIER, "x"),
+        (TokenType::TEMPLATEEND, ""),
+        (TokenType::TEMPLATEEND, ""),
+        (TokenType::STRING, "${no}"),
+        (TokenType::EOF, "\0"),
+    ];
+    let tokens = lexer.get_tokens();
+    let found: Vec<(TokenType, &str)> = tokens
+        .iter()
+        .map(|token| (token.token_type, token.literal.as_str()))
+        .collect();
+    assert_eq!(found, expected);
+
+    let mut lexer = Lexer::new("let a = 1;\n\"open ${a}");
+    lexer.get_tokens();
+    let errors = lexer.get_errors();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(
+        errors[0].get_message(),
+        "Unterminated string starting at line 2"
+    );
+}
+
+#[test]
+fn next_token_comments() {
+    let mut lexer = Lexer::new(
+        "1 /* one /* nested\n */ still */ + 2 // line\n/// doc for x\n////not doc\nx /**/ / 3 // end",
+    );
+    let expected = vec![
+        (TokenType::INT, "1", 1),
+        (TokenType::PLUS, "+", 2),
+        (TokenType::INT, "2", 2),
+        (TokenType::DOC, "doc for x", 3),
+        (TokenType::IDENTIFIER, "x", 5),
+        (TokenType::SLASH, "/", 5),
+        (TokenType::INT, "3", 5),
+        (TokenType::EOF, "\0", 5),
+    ];
+    let tokens = lexer.get_tokens();
+    let found: Vec<(TokenType, &str, usize)> = tokens
+        .iter()
+        .map(|token| (token.token_type, token.literal.as_str(), token.line))
+        .collect();
+    assert_eq!(found, expected);
+
+    let tests = ["1 /* open", "let a = 1;\n/* outer\n/* inner */\n"];
+    let expected_errors = vec![
+        ("Unterminated block comment starting at line 1", 1),
+        ("Unterminated block comment starting at line 2", 2),
+    ];
+    for i in 0..tests.len() {
+        let mut lexer = Lexer::new(tests[i]);
+        lexer.get_tokens();
+        let errors = lexer.get_errors();
+        assert_eq!(errors.len(), 1, "At Test No - {}", i);
+        assert_eq!(
+            errors[0].get_message(),
+            expected_errors[i].0,
+            "At Test No - {}",
+            i
+        );
+        assert_eq!(
+            errors[0].get_line(),
+            Some(expected_errors[i].1),
+            "At Test No - {}",
+            i
+        );
+    }
+}
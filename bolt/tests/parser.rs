@@ -1,6 +1,6 @@
 #![allow(dead_code, unused_imports)]
 
-use std::{rc::Rc, vec};
+use std::{cell::Cell, rc::Rc, vec};
 
 use bolt::{
     error::BoltError,
@@ -10,9 +10,13 @@ use bolt::{
     },
     parser::{
         ast::{
-            self, BinaryExpression, BlockStatement, Boolean, Expression, ExpressionStatement,
-            FunctionLiteral, Identifier, IfExpression, IntegerLiteral, LetStatement,
-            PrefixExpression, ReturnStatement, Statement,
+            self, ArrayLiteral, AssignExpression, AwaitExpression, BinaryExpression, Binding,
+            BlockStatement, Boolean, CallExpression, ClassStatement, ExportStatement, Expression,
+            ExpressionStatement, FunctionLiteral, GetExpression, HashLiteral, Identifier,
+            IfExpression, ImportStatement, IndexExpression, IntegerLiteral, InterpolatedString,
+            LetStatement, LogicalExpression, MatchExpression, Pattern, PrefixExpression,
+            ReturnStatement, Statement, StringPart, SuperExpression, ThisExpression,
+            ThrowStatement, TryStatement, WhileStatement, YieldExpression,
         },
         parser::Parser,
     },
@@ -25,20 +29,111 @@ fn test_integer_literal_xpression() {
     let p = parser.parse_program();
     match p {
         Ok(res) => {
-            let stmt: &Box<dyn Statement> = &res.stmts[0];
-            let expected_expression = Box::new(ast::IntegerLiteral {
+            let stmt: &Statement = &res.stmts[0];
+            let expected_expression = Box::new(Expression::Integer(ast::IntegerLiteral {
                 token: Token {
                     token_type: TokenType::INT,
                     literal: String::from("10"),
-                    line: 0,
+                    line: 1,
                 },
-                value: 10.0,
-            });
+                value: 10,
+            }));
             let actual_stmt = ExpressionStatement {
                 token: Token {
                     token_type: TokenType::INT,
                     literal: String::from("10"),
-                    line: 0,
+                    line: 1,
+                },
+                value: expected_expression,
+            };
+            assert_eq!(format!("{:?}", stmt), format!("{:?}", actual_stmt));
+        }
+        Err(e) => {
+            panic!("Error - {:?}", e.get_message());
+        }
+    }
+}
+
+#[test]
+fn test_float_literal_expression() {
+    let input = "2.5;";
+    let mut parser = Parser::new(&input);
+    let p = parser.parse_program();
+    match p {
+        Ok(res) => {
+            let stmt: &Statement = &res.stmts[0];
+            let expected_expression = Box::new(Expression::Float(ast::FloatLiteral {
+                token: Token {
+                    token_type: TokenType::FLOAT,
+                    literal: String::from("2.5"),
+                    line: 1,
+                },
+                value: 2.5,
+            }));
+            let actual_stmt = ExpressionStatement {
+                token: Token {
+                    token_type: TokenType::FLOAT,
+                    literal: String::from("2.5"),
+                    line: 1,
+                },
+                value: expected_expression,
+            };
+            assert_eq!(format!("{:?}", stmt), format!("{:?}", actual_stmt));
+        }
+        Err(e) => {
+            panic!("Error - {:?}", e.get_message());
+        }
+    }
+
+    let mut parser = Parser::new("9223372036854775808;");
+    match parser.parse_program() {
+        Ok(_) => panic!("Expected an error for an out of range integer"),
+        Err(e) => assert_eq!(
+            e.get_message(),
+            "Integer literal 9223372036854775808 is too large at line 1"
+        ),
+    }
+
+    let mut parser = Parser::new("1e400;");
+    match parser.parse_program() {
+        Ok(_) => panic!("Expected an error for an out of range float"),
+        Err(e) => assert_eq!(
+            e.get_message(),
+            "Float literal 1e400 is too large at line 1"
+        ),
+    }
+
+    let mut parser = Parser::new("-9223372036854775808[0];");
+    match parser.parse_program() {
+        Ok(_) => panic!("Expected an error for an out of range integer"),
+        Err(e) => assert_eq!(
+            e.get_message(),
+            "Integer literal 9223372036854775808 is too large at line 1"
+        ),
+    }
+}
+
+#[test]
+fn test_string_literal_expression() {
+    let input = "\"hello world\";";
+    let mut parser = Parser::new(&input);
+    let p = parser.parse_program();
+    match p {
+        Ok(res) => {
+            let stmt: &Statement = &res.stmts[0];
+            let expected_expression = Box::new(Expression::String(ast::StringLiteral {
+                token: Token {
+                    token_type: TokenType::STRING,
+                    literal: String::from("hello world"),
+                    line: 1,
+                },
+                value: String::from("hello world"),
+            }));
+            let actual_stmt = ExpressionStatement {
+                token: Token {
+                    token_type: TokenType::STRING,
+                    literal: String::from("hello world"),
+                    line: 1,
                 },
                 value: expected_expression,
             };
@@ -58,28 +153,28 @@ fn test_prefix_minus_expression() {
     let p = parser.parse_program();
     match p {
         Ok(res) => {
-            let stmt: &Box<dyn Statement> = &res.stmts[0];
-            let expected_expression = Box::new(ast::PrefixExpression {
+            let stmt: &Statement = &res.stmts[0];
+            let expected_expression = Box::new(Expression::Prefix(ast::PrefixExpression {
                 token: Token {
                     token_type: TokenType::MINUS,
                     literal: String::from("-"),
-                    line: 0,
+                    line: 1,
                 },
                 operator: String::from("-"),
-                right: Box::new(IntegerLiteral {
+                right: Box::new(Expression::Integer(IntegerLiteral {
                     token: Token {
                         token_type: TokenType::INT,
                         literal: String::from("5"),
-                        line: 0,
+                        line: 1,
                     },
-                    value: 5.0,
-                }),
-            });
+                    value: 5,
+                })),
+            }));
             let expected_stmt = ExpressionStatement {
                 token: Token {
                     token_type: TokenType::MINUS,
                     literal: String::from("-"),
-                    line: 0,
+                    line: 1,
                 },
                 value: expected_expression,
             };
@@ -99,28 +194,28 @@ fn test_prefix_bang_expression() {
     let p = parser.parse_program();
     match p {
         Ok(res) => {
-            let stmt: &Box<dyn Statement> = &res.stmts[0];
-            let expected_expression = Box::new(ast::PrefixExpression {
+            let stmt: &Statement = &res.stmts[0];
+            let expected_expression = Box::new(Expression::Prefix(ast::PrefixExpression {
                 token: Token {
                     token_type: TokenType::BANG,
                     literal: String::from("!"),
-                    line: 0,
+                    line: 1,
                 },
                 operator: String::from("!"),
-                right: Box::new(Boolean {
+                right: Box::new(Expression::Boolean(Boolean {
                     token: Token {
                         token_type: TokenType::TRUE,
                         literal: String::from("true"),
-                        line: 0,
+                        line: 1,
                     },
                     value: true,
-                }),
-            });
+                })),
+            }));
             let expected_stmt = ExpressionStatement {
                 token: Token {
                     token_type: TokenType::BANG,
                     literal: String::from("!"),
-                    line: 0,
+                    line: 1,
                 },
                 value: expected_expression,
             };
@@ -149,353 +244,368 @@ fn test_operator_precedence_parsing() {
             token: Token {
                 token_type: TokenType::MINUS,
                 literal: String::from("-"),
-                line: 0,
+                line: 1,
             },
-            value: Box::new(ast::BinaryExpression {
+            value: Box::new(Expression::Binary(ast::BinaryExpression {
                 token: Token {
                     token_type: TokenType::ASTERISK,
                     literal: String::from("*"),
-                    line: 0,
+                    line: 1,
                 },
                 operator: String::from("*"),
-                left: Rc::new(Box::new(PrefixExpression {
+                left: Rc::new(Expression::Prefix(PrefixExpression {
                     token: Token {
                         token_type: TokenType::MINUS,
                         literal: String::from("-"),
-                        line: 0,
+                        line: 1,
                     },
                     operator: String::from("-"),
-                    right: Box::new(Identifier {
+                    right: Box::new(Expression::Identifier(Identifier {
                         token: Token {
                             token_type: TokenType::IDENTIFIER,
                             literal: String::from("a"),
-                            line: 0,
+                            line: 1,
                         },
                         value: String::from("a"),
-                    }),
+                        binding: Cell::new(Binding::Global),
+                    })),
                 })),
-                right: Box::new(Identifier {
+                right: Box::new(Expression::Identifier(Identifier {
                     token: Token {
                         token_type: TokenType::IDENTIFIER,
                         literal: String::from("b"),
-                        line: 0,
+                        line: 1,
                     },
                     value: String::from("b"),
-                }),
-            }),
+                    binding: Cell::new(Binding::Global),
+                })),
+            })),
         },
         ExpressionStatement {
             token: Token {
                 token_type: TokenType::BANG,
                 literal: String::from("!"),
-                line: 0,
+                line: 1,
             },
-            value: Box::new(PrefixExpression {
+            value: Box::new(Expression::Prefix(PrefixExpression {
                 token: Token {
                     token_type: TokenType::BANG,
                     literal: String::from("!"),
-                    line: 0,
+                    line: 1,
                 },
                 operator: String::from("!"),
-                right: Box::new(PrefixExpression {
+                right: Box::new(Expression::Prefix(PrefixExpression {
                     token: Token {
                         token_type: TokenType::MINUS,
                         literal: String::from("-"),
-                        line: 0,
+                        line: 1,
                     },
                     operator: String::from("-"),
-                    right: Box::new(Identifier {
+                    right: Box::new(Expression::Identifier(Identifier {
                         token: Token {
                             token_type: TokenType::IDENTIFIER,
                             literal: String::from("a"),
-                            line: 0,
+                            line: 1,
                         },
                         value: String::from("a"),
-                    }),
-                }),
-            }),
+                        binding: Cell::new(Binding::Global),
+                    })),
+                })),
+            })),
         },
         ExpressionStatement {
             token: Token {
                 token_type: TokenType::IDENTIFIER,
                 literal: String::from("a"),
-                line: 0,
+                line: 1,
             },
-            value: Box::new(BinaryExpression {
+            value: Box::new(Expression::Binary(BinaryExpression {
                 token: Token {
                     token_type: TokenType::PLUS,
                     literal: String::from("+"),
-                    line: 0,
+                    line: 1,
                 },
                 operator: String::from("+"),
-                left: Rc::new(Box::new(BinaryExpression {
+                left: Rc::new(Expression::Binary(BinaryExpression {
                     token: Token {
                         token_type: TokenType::PLUS,
                         literal: String::from("+"),
-                        line: 0,
+                        line: 1,
                     },
                     operator: String::from("+"),
-                    left: Rc::new(Box::new(Identifier {
+                    left: Rc::new(Expression::Identifier(Identifier {
                         token: Token {
                             token_type: TokenType::IDENTIFIER,
                             literal: String::from("a"),
-                            line: 0,
+                            line: 1,
                         },
                         value: String::from("a"),
+                        binding: Cell::new(Binding::Global),
                     })),
-                    right: Box::new(Identifier {
+                    right: Box::new(Expression::Identifier(Identifier {
                         token: Token {
                             token_type: TokenType::IDENTIFIER,
                             literal: String::from("b"),
-                            line: 0,
+                            line: 1,
                         },
                         value: String::from("b"),
-                    }),
+                        binding: Cell::new(Binding::Global),
+                    })),
                 })),
-                right: Box::new(Identifier {
+                right: Box::new(Expression::Identifier(Identifier {
                     token: Token {
                         token_type: TokenType::IDENTIFIER,
                         literal: String::from("c"),
-                        line: 0,
+                        line: 1,
                     },
                     value: String::from("c"),
-                }),
-            }),
+                    binding: Cell::new(Binding::Global),
+                })),
+            })),
         },
         ExpressionStatement {
             token: Token {
                 token_type: TokenType::IDENTIFIER,
                 literal: String::from("a"),
-                line: 0,
+                line: 1,
             },
-            value: Box::new(BinaryExpression {
+            value: Box::new(Expression::Binary(BinaryExpression {
                 token: Token {
                     token_type: TokenType::MINUS,
                     literal: String::from("-"),
-                    line: 0,
+                    line: 1,
                 },
                 operator: String::from("-"),
-                left: Rc::new(Box::new(BinaryExpression {
+                left: Rc::new(Expression::Binary(BinaryExpression {
                     token: Token {
                         token_type: TokenType::PLUS,
                         literal: String::from("+"),
-                        line: 0,
+                        line: 1,
                     },
                     operator: String::from("+"),
-                    left: Rc::new(Box::new(Identifier {
+                    left: Rc::new(Expression::Identifier(Identifier {
                         token: Token {
                             token_type: TokenType::IDENTIFIER,
                             literal: String::from("a"),
-                            line: 0,
+                            line: 1,
                         },
                         value: String::from("a"),
+                        binding: Cell::new(Binding::Global),
                     })),
-                    right: Box::new(Identifier {
+                    right: Box::new(Expression::Identifier(Identifier {
                         token: Token {
                             token_type: TokenType::IDENTIFIER,
                             literal: String::from("b"),
-                            line: 0,
+                            line: 1,
                         },
                         value: String::from("b"),
-                    }),
+                        binding: Cell::new(Binding::Global),
+                    })),
                 })),
-                right: Box::new(Identifier {
+                right: Box::new(Expression::Identifier(Identifier {
                     token: Token {
                         token_type: TokenType::IDENTIFIER,
                         literal: String::from("c"),
-                        line: 0,
+                        line: 1,
                     },
                     value: String::from("c"),
-                }),
-            }),
+                    binding: Cell::new(Binding::Global),
+                })),
+            })),
         },
         ExpressionStatement {
             token: Token {
                 token_type: TokenType::IDENTIFIER,
                 literal: String::from("a"),
-                line: 0,
+                line: 1,
             },
-            value: Box::new(BinaryExpression {
+            value: Box::new(Expression::Binary(BinaryExpression {
                 token: Token {
                     token_type: TokenType::PLUS,
                     literal: String::from("+"),
-                    line: 0,
+                    line: 1,
                 },
                 operator: String::from("+"),
-                right: Box::new(BinaryExpression {
+                right: Box::new(Expression::Binary(BinaryExpression {
                     token: Token {
                         token_type: TokenType::ASTERISK,
                         literal: String::from("*"),
-                        line: 0,
+                        line: 1,
                     },
                     operator: String::from("*"),
-                    left: Rc::new(Box::new(Identifier {
+                    left: Rc::new(Expression::Identifier(Identifier {
                         token: Token {
                             token_type: TokenType::IDENTIFIER,
                             literal: String::from("b"),
-                            line: 0,
+                            line: 1,
                         },
                         value: String::from("b"),
+                        binding: Cell::new(Binding::Global),
                     })),
-                    right: Box::new(Identifier {
+                    right: Box::new(Expression::Identifier(Identifier {
                         token: Token {
                             token_type: TokenType::IDENTIFIER,
                             literal: String::from("c"),
-                            line: 0,
+                            line: 1,
                         },
                         value: String::from("c"),
-                    }),
-                }),
-                left: Rc::new(Box::new(Identifier {
+                        binding: Cell::new(Binding::Global),
+                    })),
+                })),
+                left: Rc::new(Expression::Identifier(Identifier {
                     token: Token {
                         token_type: TokenType::IDENTIFIER,
                         literal: String::from("a"),
-                        line: 0,
+                        line: 1,
                     },
                     value: String::from("a"),
+                    binding: Cell::new(Binding::Global),
                 })),
-            }),
+            })),
         },
         ExpressionStatement {
             token: Token {
                 token_type: TokenType::IDENTIFIER,
                 literal: String::from("a"),
-                line: 0,
+                line: 1,
             },
-            value: Box::new(BinaryExpression {
+            value: Box::new(Expression::Binary(BinaryExpression {
                 token: Token {
                     token_type: TokenType::SLASH,
                     literal: String::from("/"),
-                    line: 0,
+                    line: 1,
                 },
                 operator: String::from("/"),
-                left: Rc::new(Box::new(BinaryExpression {
+                left: Rc::new(Expression::Binary(BinaryExpression {
                     token: Token {
                         token_type: TokenType::ASTERISK,
                         literal: String::from("*"),
-                        line: 0,
+                        line: 1,
                     },
                     operator: String::from("*"),
-                    left: Rc::new(Box::new(Identifier {
+                    left: Rc::new(Expression::Identifier(Identifier {
                         token: Token {
                             token_type: TokenType::IDENTIFIER,
                             literal: String::from("a"),
-                            line: 0,
+                            line: 1,
                         },
                         value: String::from("a"),
+                        binding: Cell::new(Binding::Global),
                     })),
-                    right: Box::new(Identifier {
+                    right: Box::new(Expression::Identifier(Identifier {
                         token: Token {
                             token_type: TokenType::IDENTIFIER,
                             literal: String::from("b"),
-                            line: 0,
+                            line: 1,
                         },
                         value: String::from("b"),
-                    }),
+                        binding: Cell::new(Binding::Global),
+                    })),
                 })),
-                right: Box::new(Identifier {
+                right: Box::new(Expression::Identifier(Identifier {
                     token: Token {
                         token_type: TokenType::IDENTIFIER,
                         literal: String::from("c"),
-                        line: 0,
+                        line: 1,
                     },
                     value: String::from("c"),
-                }),
-            }),
+                    binding: Cell::new(Binding::Global),
+                })),
+            })),
         },
         ExpressionStatement {
             token: Token {
                 token_type: TokenType::INT,
                 literal: String::from("5"),
-                line: 0,
+                line: 1,
             },
-            value: Box::new(BinaryExpression {
+            value: Box::new(Expression::Binary(BinaryExpression {
                 token: Token {
                     token_type: TokenType::EQ,
                     literal: String::from("=="),
-                    line: 0,
+                    line: 1,
                 },
                 operator: String::from("=="),
-                left: Rc::new(Box::new(BinaryExpression {
+                left: Rc::new(Expression::Binary(BinaryExpression {
                     token: Token {
                         token_type: TokenType::LT,
                         literal: String::from("<"),
-                        line: 0,
+                        line: 1,
                     },
                     operator: String::from("<"),
-                    left: Rc::new(Box::new(IntegerLiteral {
+                    left: Rc::new(Expression::Integer(IntegerLiteral {
                         token: Token {
                             token_type: TokenType::INT,
                             literal: String::from("5"),
-                            line: 0,
+                            line: 1,
                         },
-                        value: 5.0,
+                        value: 5,
                     })),
-                    right: Box::new(IntegerLiteral {
+                    right: Box::new(Expression::Integer(IntegerLiteral {
                         token: Token {
                             token_type: TokenType::INT,
                             literal: String::from("4"),
-                            line: 0,
+                            line: 1,
                         },
-                        value: 4.0,
-                    }),
+                        value: 4,
+                    })),
                 })),
-                right: Box::new(BinaryExpression {
+                right: Box::new(Expression::Binary(BinaryExpression {
                     token: Token {
                         token_type: TokenType::GT,
                         literal: String::from(">"),
-                        line: 0,
+                        line: 1,
                     },
                     operator: String::from(">"),
-                    left: Rc::new(Box::new(IntegerLiteral {
+                    left: Rc::new(Expression::Integer(IntegerLiteral {
                         token: Token {
                             token_type: TokenType::INT,
                             literal: String::from("3"),
-                            line: 0,
+                            line: 1,
                         },
-                        value: 3.0,
+                        value: 3,
                     })),
-                    right: Box::new(IntegerLiteral {
+                    right: Box::new(Expression::Integer(IntegerLiteral {
                         token: Token {
                             token_type: TokenType::INT,
                             literal: String::from("4"),
-                            line: 0,
+                            line: 1,
                         },
-                        value: 4.0,
-                    }),
-                }),
-            }),
+                        value: 4,
+                    })),
+                })),
+            })),
         },
         ExpressionStatement {
             token: Token {
                 token_type: TokenType::INT,
                 literal: String::from("1"),
-                line: 0,
+                line: 1,
             },
-            value: Box::new(BinaryExpression {
+            value: Box::new(Expression::Binary(BinaryExpression {
                 token: Token {
                     token_type: TokenType::NOTEQ,
                     literal: String::from("!="),
-                    line: 0,
+                    line: 1,
                 },
                 operator: String::from("!="),
-                left: Rc::new(Box::new(IntegerLiteral {
+                left: Rc::new(Expression::Integer(IntegerLiteral {
                     token: Token {
                         token_type: TokenType::INT,
                         literal: String::from("1"),
-                        line: 0,
+                        line: 1,
                     },
-                    value: 1.0,
+                    value: 1,
                 })),
-                right: Box::new(IntegerLiteral {
+                right: Box::new(Expression::Integer(IntegerLiteral {
                     token: Token {
                         token_type: TokenType::INT,
                         literal: String::from("2"),
-                        line: 0,
+                        line: 1,
                     },
-                    value: 2.0,
-                }),
-            }),
+                    value: 2,
+                })),
+            })),
         },
     ];
     let size = tests.len();
@@ -504,7 +614,7 @@ fn test_operator_precedence_parsing() {
         let p = parser.parse_program();
         match p {
             Ok(res) => {
-                let stmt: &Box<dyn Statement> = &res.stmts[0];
+                let stmt: &Statement = &res.stmts[0];
                 assert_eq!(format!("{:?}", stmt), format!("{:?}", expected_results[i]));
             }
             Err(e) => {
@@ -522,47 +632,51 @@ fn test_boolean_statement() {
             token: Token {
                 token_type: TokenType::LET,
                 literal: String::from("let"),
-                line: 0,
+                line: 1,
             },
             identifier: Identifier {
                 token: Token {
                     token_type: TokenType::IDENTIFIER,
                     literal: String::from("a"),
-                    line: 0,
+                    line: 1,
                 },
                 value: String::from("a"),
+                binding: Cell::new(Binding::Global),
             },
-            value: Box::new(Boolean {
+            value: Box::new(Expression::Boolean(Boolean {
                 token: Token {
                     token_type: TokenType::TRUE,
                     literal: String::from("true"),
-                    line: 0,
+                    line: 1,
                 },
                 value: true,
-            }),
+            })),
+            doc: None,
         },
         LetStatement {
             token: Token {
                 token_type: TokenType::LET,
                 literal: String::from("let"),
-                line: 0,
+                line: 1,
             },
             identifier: Identifier {
                 token: Token {
                     token_type: TokenType::IDENTIFIER,
                     literal: String::from("b"),
-                    line: 0,
+                    line: 1,
                 },
                 value: String::from("b"),
+                binding: Cell::new(Binding::Global),
             },
-            value: Box::new(Boolean {
+            value: Box::new(Expression::Boolean(Boolean {
                 token: Token {
                     token_type: TokenType::FALSE,
                     literal: String::from("false"),
-                    line: 0,
+                    line: 1,
                 },
                 value: false,
-            }),
+            })),
+            doc: None,
         },
     ];
     let size = tests.len();
@@ -571,7 +685,7 @@ fn test_boolean_statement() {
         let p = parser.parse_program();
         match p {
             Ok(res) => {
-                let stmt: &Box<dyn Statement> = &res.stmts[0];
+                let stmt: &Statement = &res.stmts[0];
                 assert_eq!(format!("{:?}", stmt), format!("{:?}", expected_results[i]));
             }
             Err(e) => {
@@ -589,125 +703,125 @@ fn test_boolean_expression() {
             token: Token {
                 token_type: TokenType::TRUE,
                 literal: String::from("true"),
-                line: 0,
+                line: 1,
             },
-            value: Box::new(Boolean {
+            value: Box::new(Expression::Boolean(Boolean {
                 token: Token {
                     token_type: TokenType::TRUE,
                     literal: String::from("true"),
-                    line: 0,
+                    line: 1,
                 },
                 value: true,
-            }),
+            })),
         },
         ExpressionStatement {
             token: Token {
                 token_type: TokenType::FALSE,
                 literal: String::from("false"),
-                line: 0,
+                line: 1,
             },
-            value: Box::new(Boolean {
+            value: Box::new(Expression::Boolean(Boolean {
                 token: Token {
                     token_type: TokenType::FALSE,
                     literal: String::from("false"),
-                    line: 0,
+                    line: 1,
                 },
                 value: false,
-            }),
+            })),
         },
         ExpressionStatement {
             token: Token {
                 token_type: TokenType::INT,
                 literal: String::from("5"),
-                line: 0,
+                line: 1,
             },
-            value: Box::new(BinaryExpression {
+            value: Box::new(Expression::Binary(BinaryExpression {
                 token: Token {
                     token_type: TokenType::EQ,
                     literal: String::from("=="),
-                    line: 0,
+                    line: 1,
                 },
                 operator: String::from("=="),
-                left: Rc::new(Box::new(BinaryExpression {
+                left: Rc::new(Expression::Binary(BinaryExpression {
                     token: Token {
                         token_type: TokenType::LT,
                         literal: String::from("<"),
-                        line: 0,
+                        line: 1,
                     },
                     operator: String::from("<"),
-                    left: Rc::new(Box::new(IntegerLiteral {
+                    left: Rc::new(Expression::Integer(IntegerLiteral {
                         token: Token {
                             token_type: TokenType::INT,
                             literal: String::from("5"),
-                            line: 0,
+                            line: 1,
                         },
-                        value: 5.0,
+                        value: 5,
                     })),
-                    right: Box::new(IntegerLiteral {
+                    right: Box::new(Expression::Integer(IntegerLiteral {
                         token: Token {
                             token_type: TokenType::INT,
                             literal: String::from("8"),
-                            line: 0,
+                            line: 1,
                         },
-                        value: 8.0,
-                    }),
+                        value: 8,
+                    })),
                 })),
-                right: Box::new(Boolean {
+                right: Box::new(Expression::Boolean(Boolean {
                     token: Token {
                         token_type: TokenType::TRUE,
                         literal: String::from("true"),
-                        line: 0,
+                        line: 1,
                     },
                     value: true,
-                }),
-            }),
+                })),
+            })),
         },
         ExpressionStatement {
             token: Token {
                 token_type: TokenType::INT,
                 literal: String::from("3"),
-                line: 0,
+                line: 1,
             },
-            value: Box::new(BinaryExpression {
+            value: Box::new(Expression::Binary(BinaryExpression {
                 token: Token {
                     token_type: TokenType::EQ,
                     literal: String::from("=="),
-                    line: 0,
+                    line: 1,
                 },
                 operator: String::from("=="),
-                left: Rc::new(Box::new(BinaryExpression {
+                left: Rc::new(Expression::Binary(BinaryExpression {
                     token: Token {
                         token_type: TokenType::GT,
                         literal: String::from(">"),
-                        line: 0,
+                        line: 1,
                     },
                     operator: String::from(">"),
-                    left: Rc::new(Box::new(IntegerLiteral {
+                    left: Rc::new(Expression::Integer(IntegerLiteral {
                         token: Token {
                             token_type: TokenType::INT,
                             literal: String::from("3"),
-                            line: 0,
+                            line: 1,
                         },
-                        value: 3.0,
+                        value: 3,
                     })),
-                    right: Box::new(IntegerLiteral {
+                    right: Box::new(Expression::Integer(IntegerLiteral {
                         token: Token {
                             token_type: TokenType::INT,
                             literal: String::from("4"),
-                            line: 0,
+                            line: 1,
                         },
-                        value: 4.0,
-                    }),
+                        value: 4,
+                    })),
                 })),
-                right: Box::new(Boolean {
+                right: Box::new(Expression::Boolean(Boolean {
                     token: Token {
                         token_type: TokenType::FALSE,
                         literal: String::from("false"),
-                        line: 0,
+                        line: 1,
                     },
                     value: false,
-                }),
-            }),
+                })),
+            })),
         },
     ];
     let size = tests.len();
@@ -716,7 +830,7 @@ fn test_boolean_expression() {
         let p = parser.parse_program();
         match p {
             Ok(res) => {
-                let stmt: &Box<dyn Statement> = &res.stmts[0];
+                let stmt: &Statement = &res.stmts[0];
                 assert_eq!(format!("{:?}", stmt), format!("{:?}", expected_results[i]));
             }
             Err(e) => {
@@ -734,134 +848,134 @@ fn test_grouped_expressions() {
             token: Token {
                 token_type: TokenType::INT,
                 literal: String::from("1"),
-                line: 0,
+                line: 1,
             },
-            value: Box::new(BinaryExpression {
+            value: Box::new(Expression::Binary(BinaryExpression {
                 token: Token {
                     token_type: TokenType::PLUS,
                     literal: String::from("+"),
-                    line: 0,
+                    line: 1,
                 },
                 operator: String::from("+"),
-                left: Rc::new(Box::new(IntegerLiteral {
+                left: Rc::new(Expression::Integer(IntegerLiteral {
                     token: Token {
                         token_type: TokenType::INT,
                         literal: String::from("1"),
-                        line: 0,
+                        line: 1,
                     },
-                    value: 1.0,
+                    value: 1,
                 })),
-                right: Box::new(BinaryExpression {
+                right: Box::new(Expression::Binary(BinaryExpression {
                     token: Token {
                         token_type: TokenType::PLUS,
                         literal: String::from("+"),
-                        line: 0,
+                        line: 1,
                     },
                     operator: String::from("+"),
-                    left: Rc::new(Box::new(IntegerLiteral {
+                    left: Rc::new(Expression::Integer(IntegerLiteral {
                         token: Token {
                             token_type: TokenType::INT,
                             literal: String::from("2"),
-                            line: 0,
+                            line: 1,
                         },
-                        value: 2.0,
+                        value: 2,
                     })),
-                    right: Box::new(IntegerLiteral {
+                    right: Box::new(Expression::Integer(IntegerLiteral {
                         token: Token {
                             token_type: TokenType::INT,
                             literal: String::from("3"),
-                            line: 0,
+                            line: 1,
                         },
-                        value: 3.0,
-                    }),
-                }),
-            }),
+                        value: 3,
+                    })),
+                })),
+            })),
         },
         ExpressionStatement {
             token: Token {
                 token_type: TokenType::LPAREN,
                 literal: String::from("("),
-                line: 0,
+                line: 1,
             },
-            value: Box::new(BinaryExpression {
+            value: Box::new(Expression::Binary(BinaryExpression {
                 token: Token {
                     token_type: TokenType::ASTERISK,
                     literal: String::from("*"),
-                    line: 0,
+                    line: 1,
                 },
                 operator: String::from("*"),
-                left: Rc::new(Box::new(BinaryExpression {
+                left: Rc::new(Expression::Binary(BinaryExpression {
                     token: Token {
                         token_type: TokenType::PLUS,
                         literal: String::from("+"),
-                        line: 0,
+                        line: 1,
                     },
                     operator: String::from("+"),
-                    left: Rc::new(Box::new(IntegerLiteral {
+                    left: Rc::new(Expression::Integer(IntegerLiteral {
                         token: Token {
                             token_type: TokenType::INT,
                             literal: String::from("5"),
-                            line: 0,
+                            line: 1,
                         },
-                        value: 5.0,
+                        value: 5,
                     })),
-                    right: Box::new(IntegerLiteral {
+                    right: Box::new(Expression::Integer(IntegerLiteral {
                         token: Token {
                             token_type: TokenType::INT,
                             literal: String::from("5"),
-                            line: 0,
+                            line: 1,
                         },
-                        value: 5.0,
-                    }),
+                        value: 5,
+                    })),
                 })),
-                right: Box::new(IntegerLiteral {
+                right: Box::new(Expression::Integer(IntegerLiteral {
                     token: Token {
                         token_type: TokenType::INT,
                         literal: String::from("2"),
-                        line: 0,
+                        line: 1,
                     },
-                    value: 2.0,
-                }),
-            }),
+                    value: 2,
+                })),
+            })),
         },
         ExpressionStatement {
             token: Token {
                 token_type: TokenType::MINUS,
                 literal: String::from("-"),
-                line: 0,
+                line: 1,
             },
-            value: Box::new(PrefixExpression {
+            value: Box::new(Expression::Prefix(PrefixExpression {
                 token: Token {
                     token_type: TokenType::MINUS,
                     literal: String::from("-"),
-                    line: 0,
+                    line: 1,
                 },
                 operator: String::from("-"),
-                right: Box::new(BinaryExpression {
+                right: Box::new(Expression::Binary(BinaryExpression {
                     token: Token {
                         token_type: TokenType::PLUS,
                         literal: String::from("+"),
-                        line: 0,
+                        line: 1,
                     },
                     operator: String::from("+"),
-                    left: Rc::new(Box::new(IntegerLiteral {
+                    left: Rc::new(Expression::Integer(IntegerLiteral {
                         token: Token {
                             token_type: TokenType::INT,
                             literal: String::from("5"),
-                            line: 0,
+                            line: 1,
                         },
-                        value: 5.0,
+                        value: 5,
                     })),
-                    right: Box::new(IntegerLiteral {
+                    right: Box::new(Expression::Integer(IntegerLiteral {
                         token: Token {
                             token_type: TokenType::INT,
                             literal: String::from("5"),
-                            line: 0,
+                            line: 1,
                         },
-                        value: 5.0,
-                    }),
-                }),
-            }),
+                        value: 5,
+                    })),
+                })),
+            })),
         },
     ];
     let size = tests.len();
@@ -870,7 +984,7 @@ fn test_grouped_expressions() {
         let p = parser.parse_program();
         match p {
             Ok(res) => {
-                let stmt: &Box<dyn Statement> = &res.stmts[0];
+                let stmt: &Statement = &res.stmts[0];
                 assert_eq!(format!("{:?}", stmt), format!("{:?}", expected_results[i]));
             }
             Err(e) => {
@@ -893,353 +1007,373 @@ fn test_if_expression() {
             token: Token {
                 token_type: TokenType::IF,
                 literal: String::from("if"),
-                line: 0,
+                line: 1,
             },
-            value: Box::new(IfExpression {
+            value: Box::new(Expression::If(IfExpression {
                 token: Token {
                     token_type: TokenType::IF,
                     literal: String::from("if"),
-                    line: 0,
+                    line: 1,
                 },
-                condition: Box::new(BinaryExpression {
+                condition: Box::new(Expression::Binary(BinaryExpression {
                     token: Token {
                         token_type: TokenType::GT,
                         literal: String::from(">"),
-                        line: 0,
+                        line: 1,
                     },
                     operator: String::from(">"),
-                    left: Rc::new(Box::new(Identifier {
+                    left: Rc::new(Expression::Identifier(Identifier {
                         token: Token {
                             token_type: TokenType::IDENTIFIER,
                             literal: String::from("x"),
-                            line: 0,
+                            line: 1,
                         },
                         value: String::from("x"),
+                        binding: Cell::new(Binding::Global),
                     })),
-                    right: Box::new(Identifier {
+                    right: Box::new(Expression::Identifier(Identifier {
                         token: Token {
                             token_type: TokenType::IDENTIFIER,
                             literal: String::from("y"),
-                            line: 0,
+                            line: 1,
                         },
                         value: String::from("y"),
-                    }),
-                }),
-                consequence: Box::new(BlockStatement {
+                        binding: Cell::new(Binding::Global),
+                    })),
+                })),
+                consequence: Rc::new(Box::new(BlockStatement {
                     token: Token {
                         token_type: TokenType::IDENTIFIER,
                         literal: String::from("x"),
-                        line: 0,
+                        line: 1,
                     },
-                    statements: vec![Box::new(ExpressionStatement {
+                    statements: vec![Statement::Expression(ExpressionStatement {
                         token: Token {
                             token_type: TokenType::IDENTIFIER,
                             literal: String::from("x"),
-                            line: 0,
+                            line: 1,
                         },
-                        value: Box::new(Identifier {
+                        value: Box::new(Expression::Identifier(Identifier {
                             token: Token {
                                 token_type: TokenType::IDENTIFIER,
                                 literal: String::from("x"),
-                                line: 0,
+                                line: 1,
                             },
                             value: String::from("x"),
-                        }),
+                            binding: Cell::new(Binding::Global),
+                        })),
                     })],
-                }),
+                })),
                 alternate: None,
-            }),
+            })),
         },
         ExpressionStatement {
             token: Token {
                 token_type: TokenType::IF,
                 literal: String::from("if"),
-                line: 0,
+                line: 1,
             },
-            value: Box::new(IfExpression {
+            value: Box::new(Expression::If(IfExpression {
                 token: Token {
                     token_type: TokenType::IF,
                     literal: String::from("if"),
-                    line: 0,
+                    line: 1,
                 },
-                condition: Box::new(BinaryExpression {
+                condition: Box::new(Expression::Binary(BinaryExpression {
                     token: Token {
                         token_type: TokenType::GT,
                         literal: String::from(">"),
-                        line: 0,
+                        line: 1,
                     },
                     operator: String::from(">"),
-                    left: Rc::new(Box::new(Identifier {
+                    left: Rc::new(Expression::Identifier(Identifier {
                         token: Token {
                             token_type: TokenType::IDENTIFIER,
                             literal: String::from("x"),
-                            line: 0,
+                            line: 1,
                         },
                         value: String::from("x"),
+                        binding: Cell::new(Binding::Global),
                     })),
-                    right: Box::new(Identifier {
+                    right: Box::new(Expression::Identifier(Identifier {
                         token: Token {
                             token_type: TokenType::IDENTIFIER,
                             literal: String::from("y"),
-                            line: 0,
+                            line: 1,
                         },
                         value: String::from("y"),
-                    }),
-                }),
-                consequence: Box::new(BlockStatement {
+                        binding: Cell::new(Binding::Global),
+                    })),
+                })),
+                consequence: Rc::new(Box::new(BlockStatement {
                     token: Token {
                         token_type: TokenType::IDENTIFIER,
                         literal: String::from("x"),
-                        line: 0,
+                        line: 1,
                     },
-                    statements: vec![Box::new(ExpressionStatement {
+                    statements: vec![Statement::Expression(ExpressionStatement {
                         token: Token {
                             token_type: TokenType::IDENTIFIER,
                             literal: String::from("x"),
-                            line: 0,
+                            line: 1,
                         },
-                        value: Box::new(Identifier {
+                        value: Box::new(Expression::Identifier(Identifier {
                             token: Token {
                                 token_type: TokenType::IDENTIFIER,
                                 literal: String::from("x"),
-                                line: 0,
+                                line: 1,
                             },
                             value: String::from("x"),
-                        }),
+                            binding: Cell::new(Binding::Global),
+                        })),
                     })],
-                }),
-                alternate: Some(Box::new(BlockStatement {
+                })),
+                alternate: Some(Rc::new(Box::new(BlockStatement {
                     token: Token {
                         token_type: TokenType::IDENTIFIER,
                         literal: String::from("y"),
-                        line: 0,
+                        line: 1,
                     },
-                    statements: vec![Box::new(ExpressionStatement {
+                    statements: vec![Statement::Expression(ExpressionStatement {
                         token: Token {
                             token_type: TokenType::IDENTIFIER,
                             literal: String::from("y"),
-                            line: 0,
+                            line: 1,
                         },
-                        value: Box::new(Identifier {
+                        value: Box::new(Expression::Identifier(Identifier {
                             token: Token {
                                 token_type: TokenType::IDENTIFIER,
                                 literal: String::from("y"),
-                                line: 0,
+                                line: 1,
                             },
                             value: String::from("y"),
-                        }),
+                            binding: Cell::new(Binding::Global),
+                        })),
                     })],
-                })),
-            }),
+                }))),
+            })),
         },
         ExpressionStatement {
             token: Token {
                 token_type: TokenType::IF,
                 literal: String::from("if"),
-                line: 0,
+                line: 1,
             },
-            value: Box::new(IfExpression {
+            value: Box::new(Expression::If(IfExpression {
                 token: Token {
                     token_type: TokenType::IF,
                     literal: String::from("if"),
-                    line: 0,
+                    line: 1,
                 },
-                condition: Box::new(BinaryExpression {
+                condition: Box::new(Expression::Binary(BinaryExpression {
                     token: Token {
                         token_type: TokenType::GT,
                         literal: String::from(">"),
-                        line: 0,
+                        line: 1,
                     },
                     operator: String::from(">"),
-                    left: Rc::new(Box::new(Identifier {
+                    left: Rc::new(Expression::Identifier(Identifier {
                         token: Token {
                             token_type: TokenType::IDENTIFIER,
                             literal: String::from("x"),
-                            line: 0,
+                            line: 1,
                         },
                         value: String::from("x"),
+                        binding: Cell::new(Binding::Global),
                     })),
-                    right: Box::new(Identifier {
+                    right: Box::new(Expression::Identifier(Identifier {
                         token: Token {
                             token_type: TokenType::IDENTIFIER,
                             literal: String::from("y"),
-                            line: 0,
+                            line: 1,
                         },
                         value: String::from("y"),
-                    }),
-                }),
-                consequence: Box::new(BlockStatement {
+                        binding: Cell::new(Binding::Global),
+                    })),
+                })),
+                consequence: Rc::new(Box::new(BlockStatement {
                     token: Token {
                         token_type: TokenType::LET,
                         literal: String::from("let"),
-                        line: 0,
+                        line: 1,
                     },
                     statements: vec![
-                        Box::new(LetStatement {
+                        Statement::Let(LetStatement {
                             token: Token {
                                 token_type: TokenType::LET,
                                 literal: String::from("let"),
-                                line: 0,
+                                line: 1,
                             },
                             identifier: Identifier {
                                 token: Token {
                                     token_type: TokenType::IDENTIFIER,
                                     literal: String::from("a"),
-                                    line: 0,
+                                    line: 1,
                                 },
                                 value: String::from("a"),
+                                binding: Cell::new(Binding::Global),
                             },
-                            value: Box::new(IntegerLiteral {
+                            value: Box::new(Expression::Integer(IntegerLiteral {
                                 token: Token {
                                     token_type: TokenType::INT,
                                     literal: String::from("1"),
-                                    line: 0,
+                                    line: 1,
                                 },
-                                value: 1.0,
-                            }),
+                                value: 1,
+                            })),
+                            doc: None,
                         }),
-                        Box::new(LetStatement {
+                        Statement::Let(LetStatement {
                             token: Token {
                                 token_type: TokenType::LET,
                                 literal: String::from("let"),
-                                line: 1,
+                                line: 2,
                             },
                             identifier: Identifier {
                                 token: Token {
                                     token_type: TokenType::IDENTIFIER,
                                     literal: String::from("b"),
-                                    line: 1,
+                                    line: 2,
                                 },
                                 value: String::from("b"),
+                                binding: Cell::new(Binding::Global),
                             },
-                            value: Box::new(IntegerLiteral {
+                            value: Box::new(Expression::Integer(IntegerLiteral {
                                 token: Token {
                                     token_type: TokenType::INT,
                                     literal: String::from("2"),
-                                    line: 1,
+                                    line: 2,
                                 },
-                                value: 2.0,
-                            }),
+                                value: 2,
+                            })),
+                            doc: None,
                         }),
                     ],
-                }),
+                })),
                 alternate: None,
-            }),
+            })),
         },
         ExpressionStatement {
             token: Token {
                 token_type: TokenType::IF,
                 literal: String::from("if"),
-                line: 0,
+                line: 1,
             },
-            value: Box::new(IfExpression {
+            value: Box::new(Expression::If(IfExpression {
                 token: Token {
                     token_type: TokenType::IF,
                     literal: String::from("if"),
-                    line: 0,
+                    line: 1,
                 },
-                condition: Box::new(BinaryExpression {
+                condition: Box::new(Expression::Binary(BinaryExpression {
                     token: Token {
                         token_type: TokenType::GT,
                         literal: String::from(">"),
-                        line: 0,
+                        line: 1,
                     },
                     operator: String::from(">"),
-                    left: Rc::new(Box::new(Identifier {
+                    left: Rc::new(Expression::Identifier(Identifier {
                         token: Token {
                             token_type: TokenType::IDENTIFIER,
                             literal: String::from("x"),
-                            line: 0,
+                            line: 1,
                         },
                         value: String::from("x"),
+                        binding: Cell::new(Binding::Global),
                     })),
-                    right: Box::new(Identifier {
+                    right: Box::new(Expression::Identifier(Identifier {
                         token: Token {
                             token_type: TokenType::IDENTIFIER,
                             literal: String::from("y"),
-                            line: 0,
+                            line: 1,
                         },
                         value: String::from("y"),
-                    }),
-                }),
-                consequence: Box::new(BlockStatement {
+                        binding: Cell::new(Binding::Global),
+                    })),
+                })),
+                consequence: Rc::new(Box::new(BlockStatement {
                     token: Token {
                         token_type: TokenType::IDENTIFIER,
                         literal: String::from("x"),
-                        line: 0,
+                        line: 1,
                     },
-                    statements: vec![Box::new(ExpressionStatement {
+                    statements: vec![Statement::Expression(ExpressionStatement {
                         token: Token {
                             token_type: TokenType::IDENTIFIER,
                             literal: String::from("x"),
-                            line: 0,
+                            line: 1,
                         },
-                        value: Box::new(Identifier {
+                        value: Box::new(Expression::Identifier(Identifier {
                             token: Token {
                                 token_type: TokenType::IDENTIFIER,
                                 literal: String::from("x"),
-                                line: 0,
+                                line: 1,
                             },
                             value: String::from("x"),
-                        }),
+                            binding: Cell::new(Binding::Global),
+                        })),
                     })],
-                }),
-                alternate: Some(Box::new(BlockStatement {
+                })),
+                alternate: Some(Rc::new(Box::new(BlockStatement {
                     token: Token {
                         token_type: TokenType::LET,
                         literal: String::from("let"),
-                        line: 1,
+                        line: 2,
                     },
                     statements: vec![
-                        Box::new(LetStatement {
+                        Statement::Let(LetStatement {
                             token: Token {
                                 token_type: TokenType::LET,
                                 literal: String::from("let"),
-                                line: 1,
+                                line: 2,
                             },
                             identifier: Identifier {
                                 token: Token {
                                     token_type: TokenType::IDENTIFIER,
                                     literal: String::from("a"),
-                                    line: 1,
+                                    line: 2,
                                 },
                                 value: String::from("a"),
+                                binding: Cell::new(Binding::Global),
                             },
-                            value: Box::new(IntegerLiteral {
+                            value: Box::new(Expression::Integer(IntegerLiteral {
                                 token: Token {
                                     token_type: TokenType::INT,
                                     literal: String::from("1"),
-                                    line: 1,
+                                    line: 2,
                                 },
-                                value: 1.0,
-                            }),
+                                value: 1,
+                            })),
+                            doc: None,
                         }),
-                        Box::new(LetStatement {
+                        Statement::Let(LetStatement {
                             token: Token {
                                 token_type: TokenType::LET,
                                 literal: String::from("let"),
-                                line: 2,
+                                line: 3,
                             },
                             identifier: Identifier {
                                 token: Token {
                                     token_type: TokenType::IDENTIFIER,
                                     literal: String::from("b"),
-                                    line: 2,
+                                    line: 3,
                                 },
                                 value: String::from("b"),
+                                binding: Cell::new(Binding::Global),
                             },
-                            value: Box::new(IntegerLiteral {
+                            value: Box::new(Expression::Integer(IntegerLiteral {
                                 token: Token {
                                     token_type: TokenType::INT,
                                     literal: String::from("2"),
-                                    line: 2,
+                                    line: 3,
                                 },
-                                value: 2.0,
-                            }),
+                                value: 2,
+                            })),
+                            doc: None,
                         }),
                     ],
-                })),
-            }),
+                }))),
+            })),
         },
     ];
     let size = tests.len();
@@ -1248,7 +1382,7 @@ fn test_if_expression() {
         let p = parser.parse_program();
         match p {
             Ok(res) => {
-                let stmt: &Box<dyn Statement> = &res.stmts[0];
+                let stmt: &Statement = &res.stmts[0];
                 assert_eq!(format!("{:?}", stmt), format!("{:?}", expected_results[i]));
             }
             Err(e) => {
@@ -1262,98 +1396,106 @@ fn test_if_expression() {
 fn test_function_parameter_parsing() {
     let tests = ["fn(){}", "fn(x, y){return x + y;}"];
     let expected_results = vec![
-        Box::new(ExpressionStatement {
+        Statement::Expression(ExpressionStatement {
             token: Token {
                 token_type: TokenType::FUNCTION,
                 literal: String::from("fn"),
-                line: 0,
+                line: 1,
             },
-            value: Box::new(FunctionLiteral {
+            value: Box::new(Expression::Function(FunctionLiteral {
                 token: Token {
                     token_type: TokenType::FUNCTION,
                     literal: String::from("fn"),
-                    line: 0,
+                    line: 1,
                 },
                 parameters: Rc::new(vec![]),
                 body: Rc::new(Box::new(BlockStatement {
                     token: Token {
                         token_type: TokenType::RBRACE,
                         literal: String::from("}"),
-                        line: 0,
+                        line: 1,
                     },
                     statements: vec![],
                 })),
-            }),
+                is_generator: false,
+                is_async: false,
+            })),
         }),
-        Box::new(ExpressionStatement {
+        Statement::Expression(ExpressionStatement {
             token: Token {
                 token_type: TokenType::FUNCTION,
                 literal: String::from("fn"),
-                line: 0,
+                line: 1,
             },
-            value: Box::new(FunctionLiteral {
+            value: Box::new(Expression::Function(FunctionLiteral {
                 token: Token {
                     token_type: TokenType::FUNCTION,
                     literal: String::from("fn"),
-                    line: 0,
+                    line: 1,
                 },
                 parameters: Rc::new(vec![
                     Identifier {
                         token: Token {
                             token_type: TokenType::IDENTIFIER,
                             literal: String::from("x"),
-                            line: 0,
+                            line: 1,
                         },
                         value: String::from("x"),
+                        binding: Cell::new(Binding::Global),
                     },
                     Identifier {
                         token: Token {
                             token_type: TokenType::IDENTIFIER,
                             literal: String::from("y"),
-                            line: 0,
+                            line: 1,
                         },
                         value: String::from("y"),
+                        binding: Cell::new(Binding::Global),
                     },
                 ]),
                 body: Rc::new(Box::new(BlockStatement {
                     token: Token {
                         token_type: TokenType::RETURN,
                         literal: String::from("return"),
-                        line: 0,
+                        line: 1,
                     },
-                    statements: vec![Box::new(ReturnStatement {
+                    statements: vec![Statement::Return(ReturnStatement {
                         token: Token {
                             token_type: TokenType::RETURN,
                             literal: String::from("return"),
-                            line: 0,
+                            line: 1,
                         },
-                        value: Box::new(BinaryExpression {
+                        value: Box::new(Expression::Binary(BinaryExpression {
                             token: Token {
                                 token_type: TokenType::PLUS,
                                 literal: String::from("+"),
-                                line: 0,
+                                line: 1,
                             },
                             operator: String::from("+"),
-                            left: Rc::new(Box::new(Identifier {
+                            left: Rc::new(Expression::Identifier(Identifier {
                                 token: Token {
                                     token_type: TokenType::IDENTIFIER,
                                     literal: String::from("x"),
-                                    line: 0,
+                                    line: 1,
                                 },
                                 value: String::from("x"),
+                                binding: Cell::new(Binding::Global),
                             })),
-                            right: Box::new(Identifier {
+                            right: Box::new(Expression::Identifier(Identifier {
                                 token: Token {
                                     token_type: TokenType::IDENTIFIER,
                                     literal: String::from("y"),
-                                    line: 0,
+                                    line: 1,
                                 },
                                 value: String::from("y"),
-                            }),
-                        }),
+                                binding: Cell::new(Binding::Global),
+                            })),
+                        })),
                     })],
                 })),
-            }),
+                is_generator: false,
+                is_async: false,
+            })),
         }),
     ];
     let size = tests.len();
@@ -1362,7 +1504,7 @@ fn test_function_parameter_parsing() {
         let p = parser.parse_program();
         match p {
             Ok(res) => {
-                let stmt: &Box<dyn Statement> = &res.stmts[0];
+                let stmt: &Statement = &res.stmts[0];
                 assert_eq!(format!("{:?}", stmt), format!("{:?}", expected_results[i]));
             }
             Err(e) => {
@@ -1379,85 +1521,174 @@ fn test_call_expression_parsing() {
     let p = parser.parse_program();
     match p {
         Ok(res) => {
-            let stmt: &Box<dyn Statement> = &res.stmts[0];
-            let expected_expression = Box::new(ast::CallExpression {
+            let stmt: &Statement = &res.stmts[0];
+            let expected_expression = Box::new(Expression::Call(ast::CallExpression {
                 token: Token {
                     token_type: TokenType::LPAREN,
                     literal: String::from("("),
-                    line: 0,
+                    line: 1,
                 },
-                funtion: Rc::new(Box::new(Identifier {
+                funtion: Rc::new(Expression::Identifier(Identifier {
                     token: Token {
                         token_type: TokenType::IDENTIFIER,
                         literal: String::from("add"),
-                        line: 0,
+                        line: 1,
                     },
                     value: String::from("add"),
+                    binding: Cell::new(Binding::Global),
                 })),
                 parameters: Rc::new(vec![
-                    Box::new(IntegerLiteral {
+                    Box::new(Expression::Integer(IntegerLiteral {
                         token: Token {
                             token_type: TokenType::INT,
                             literal: String::from("1"),
-                            line: 0,
+                            line: 1,
                         },
-                        value: 1.0,
-                    }),
-                    Box::new(BinaryExpression {
+                        value: 1,
+                    })),
+                    Box::new(Expression::Binary(BinaryExpression {
                         token: Token {
                             token_type: TokenType::ASTERISK,
                             literal: String::from("*"),
-                            line: 0,
+                            line: 1,
                         },
                         operator: String::from("*"),
-                        left: Rc::new(Box::new(IntegerLiteral {
+                        left: Rc::new(Expression::Integer(IntegerLiteral {
                             token: Token {
                                 token_type: TokenType::INT,
                                 literal: String::from("2"),
-                                line: 0,
+                                line: 1,
                             },
-                            value: 2.0,
+                            value: 2,
                         })),
-                        right: Box::new(IntegerLiteral {
+                        right: Box::new(Expression::Integer(IntegerLiteral {
                             token: Token {
                                 token_type: TokenType::INT,
                                 literal: String::from("3"),
-                                line: 0,
+                                line: 1,
                             },
-                            value: 3.0,
-                        }),
-                    }),
-                    Box::new(BinaryExpression {
+                            value: 3,
+                        })),
+                    })),
+                    Box::new(Expression::Binary(BinaryExpression {
                         token: Token {
                             token_type: TokenType::PLUS,
                             literal: String::from("+"),
-                            line: 0,
+                            line: 1,
                         },
                         operator: String::from("+"),
-                        left: Rc::new(Box::new(IntegerLiteral {
+                        left: Rc::new(Expression::Integer(IntegerLiteral {
                             token: Token {
                                 token_type: TokenType::INT,
                                 literal: String::from("4"),
-                                line: 0,
+                                line: 1,
                             },
-                            value: 4.0,
+                            value: 4,
                         })),
-                        right: Box::new(IntegerLiteral {
+                        right: Box::new(Expression::Integer(IntegerLiteral {
                             token: Token {
                                 token_type: TokenType::INT,
                                 literal: String::from("5"),
-                                line: 0,
+                                line: 1,
                             },
-                            value: 5.0,
-                        }),
-                    }),
+                            value: 5,
+                        })),
+                    })),
                 ]),
-            });
+            }));
             let actual_stmt = ExpressionStatement {
                 token: Token {
                     token_type: TokenType::IDENTIFIER,
                     literal: String::from("add"),
-                    line: 0,
+                    line: 1,
+                },
+                value: expected_expression,
+            };
+            assert_eq!(format!("{:?}", stmt), format!("{:?}", actual_stmt));
+        }
+        Err(e) => {
+            panic!("Error - {:?}", e.get_message());
+        }
+    }
+}
+
+#[test]
+fn test_function_literal_argument_parsing() {
+    let tests = [
+        "apply(fn(x){ x }, 2); 3;",
+        "f((1 + 2) * 3); g(h(1, 2), 3)",
+        "1 + 2; 5",
+    ];
+    let expected_counts = vec![2, 2, 2];
+    let size = tests.len();
+    for i in 0..size {
+        let mut parser = Parser::new(tests[i]);
+        let p = parser.parse_program();
+        match p {
+            Ok(res) => {
+                assert_eq!(res.stmts.len(), expected_counts[i], "At Test No - {}", i);
+            }
+            Err(e) => {
+                panic!("Error - {:?}", e.get_message());
+            }
+        }
+    }
+}
+
+#[test]
+fn test_array_literal_parsing() {
+    let input = "[1, 2 * 3];";
+    let mut parser = Parser::new(&input);
+    let p = parser.parse_program();
+    match p {
+        Ok(res) => {
+            let stmt: &Statement = &res.stmts[0];
+            let expected_expression = Box::new(Expression::Array(ArrayLiteral {
+                token: Token {
+                    token_type: TokenType::LBRACKET,
+                    literal: String::from("["),
+                    line: 1,
+                },
+                elements: vec![
+                    Box::new(Expression::Integer(IntegerLiteral {
+                        token: Token {
+                            token_type: TokenType::INT,
+                            literal: String::from("1"),
+                            line: 1,
+                        },
+                        value: 1,
+                    })),
+                    Box::new(Expression::Binary(BinaryExpression {
+                        token: Token {
+                            token_type: TokenType::ASTERISK,
+                            literal: String::from("*"),
+                            line: 1,
+                        },
+                        operator: String::from("*"),
+                        left: Rc::new(Expression::Integer(IntegerLiteral {
+                            token: Token {
+                                token_type: TokenType::INT,
+                                literal: String::from("2"),
+                                line: 1,
+                            },
+                            value: 2,
+                        })),
+                        right: Box::new(Expression::Integer(IntegerLiteral {
+                            token: Token {
+                                token_type: TokenType::INT,
+                                literal: String::from("3"),
+                                line: 1,
+                            },
+                            value: 3,
+                        })),
+                    })),
+                ],
+            }));
+            let actual_stmt = ExpressionStatement {
+                token: Token {
+                    token_type: TokenType::LBRACKET,
+                    literal: String::from("["),
+                    line: 1,
                 },
                 value: expected_expression,
             };
@@ -1468,3 +1699,973 @@ fn test_call_expression_parsing() {
         }
     }
 }
+
+#[test]
+fn test_index_expression_parsing() {
+    let input = "a[1 + 1] = b * c[0];";
+    let identifier = |name: &str| {
+        Expression::Identifier(Identifier {
+            token: Token {
+                token_type: TokenType::IDENTIFIER,
+                literal: String::from(name),
+                line: 1,
+            },
+            value: String::from(name),
+            binding: Cell::new(Binding::Global),
+        })
+    };
+    let integer = |value: i64| {
+        Expression::Integer(IntegerLiteral {
+            token: Token {
+                token_type: TokenType::INT,
+                literal: value.to_string(),
+                line: 1,
+            },
+            value: value,
+        })
+    };
+    let mut parser = Parser::new(&input);
+    let p = parser.parse_program();
+    match p {
+        Ok(res) => {
+            let stmt: &Statement = &res.stmts[0];
+            let expected_expression = Box::new(Expression::Assign(AssignExpression {
+                token: Token {
+                    token_type: TokenType::ASSIGN,
+                    literal: String::from("="),
+                    line: 1,
+                },
+                target: Rc::new(Expression::Index(IndexExpression {
+                    token: Token {
+                        token_type: TokenType::LBRACKET,
+                        literal: String::from("["),
+                        line: 1,
+                    },
+                    left: Rc::new(identifier("a")),
+                    index: Box::new(Expression::Binary(BinaryExpression {
+                        token: Token {
+                            token_type: TokenType::PLUS,
+                            literal: String::from("+"),
+                            line: 1,
+                        },
+                        operator: String::from("+"),
+                        left: Rc::new(integer(1)),
+                        right: Box::new(integer(1)),
+                    })),
+                })),
+                value: Box::new(Expression::Binary(BinaryExpression {
+                    token: Token {
+                        token_type: TokenType::ASTERISK,
+                        literal: String::from("*"),
+                        line: 1,
+                    },
+                    operator: String::from("*"),
+                    left: Rc::new(identifier("b")),
+                    right: Box::new(Expression::Index(IndexExpression {
+                        token: Token {
+                            token_type: TokenType::LBRACKET,
+                            literal: String::from("["),
+                            line: 1,
+                        },
+                        left: Rc::new(identifier("c")),
+                        index: Box::new(integer(0)),
+                    })),
+                })),
+            }));
+            let actual_stmt = ExpressionStatement {
+                token: Token {
+                    token_type: TokenType::IDENTIFIER,
+                    literal: String::from("a"),
+                    line: 1,
+                },
+                value: expected_expression,
+            };
+            assert_eq!(format!("{:?}", stmt), format!("{:?}", actual_stmt));
+        }
+        Err(e) => {
+            panic!("Error - {:?}", e.get_message());
+        }
+    }
+
+    let mut parser = Parser::new("1 = 2;");
+    assert!(parser.parse_program().is_err());
+}
+
+#[test]
+fn test_hash_literal_parsing() {
+    let input = "{\"one\": 1, true: 2}";
+    let mut parser = Parser::new(&input);
+    let p = parser.parse_program();
+    match p {
+        Ok(res) => {
+            let stmt: &Statement = &res.stmts[0];
+            let expected_expression = Box::new(Expression::Hash(HashLiteral {
+                token: Token {
+                    token_type: TokenType::LBRACE,
+                    literal: String::from("{"),
+                    line: 1,
+                },
+                pairs: vec![
+                    (
+                        Box::new(Expression::String(ast::StringLiteral {
+                            token: Token {
+                                token_type: TokenType::STRING,
+                                literal: String::from("one"),
+                                line: 1,
+                            },
+                            value: String::from("one"),
+                        })),
+                        Box::new(Expression::Integer(IntegerLiteral {
+                            token: Token {
+                                token_type: TokenType::INT,
+                                literal: String::from("1"),
+                                line: 1,
+                            },
+                            value: 1,
+                        })),
+                    ),
+                    (
+                        Box::new(Expression::Boolean(Boolean {
+                            token: Token {
+                                token_type: TokenType::TRUE,
+                                literal: String::from("true"),
+                                line: 1,
+                            },
+                            value: true,
+                        })),
+                        Box::new(Expression::Integer(IntegerLiteral {
+                            token: Token {
+                                token_type: TokenType::INT,
+                                literal: String::from("2"),
+                                line: 1,
+                            },
+                            value: 2,
+                        })),
+                    ),
+                ],
+            }));
+            let actual_stmt = ExpressionStatement {
+                token: Token {
+                    token_type: TokenType::LBRACE,
+                    literal: String::from("{"),
+                    line: 1,
+                },
+                value: expected_expression,
+            };
+            assert_eq!(format!("{:?}", stmt), format!("{:?}", actual_stmt));
+        }
+        Err(e) => {
+            panic!("Error - {:?}", e.get_message());
+        }
+    }
+
+    let tests = ["{}", "{\"a\" 1}", "{\"a\": 1"];
+    let expected_ok = vec![true, false, false];
+    for i in 0..tests.len() {
+        let mut parser = Parser::new(tests[i]);
+        assert_eq!(
+            parser.parse_program().is_ok(),
+            expected_ok[i],
+            "At Test No - {}",
+            i
+        );
+    }
+}
+
+#[test]
+fn test_while_statement_parsing() {
+    let input = "while (x) { break; }";
+    let mut parser = Parser::new(&input);
+    let p = parser.parse_program();
+    match p {
+        Ok(res) => {
+            let stmt: &Statement = &res.stmts[0];
+            let expected_stmt = WhileStatement {
+                token: Token {
+                    token_type: TokenType::WHILE,
+                    literal: String::from("while"),
+                    line: 1,
+                },
+                condition: Box::new(Expression::Identifier(Identifier {
+                    token: Token {
+                        token_type: TokenType::IDENTIFIER,
+                        literal: String::from("x"),
+                        line: 1,
+                    },
+                    value: String::from("x"),
+                    binding: Cell::new(Binding::Global),
+                })),
+                body: Rc::new(Box::new(BlockStatement {
+                    token: Token {
+                        token_type: TokenType::BREAK,
+                        literal: String::from("break"),
+                        line: 1,
+                    },
+                    statements: vec![Statement::Break(ast::BreakStatement {
+                        token: Token {
+                            token_type: TokenType::BREAK,
+                            literal: String::from("break"),
+                            line: 1,
+                        },
+                    })],
+                })),
+            };
+            assert_eq!(format!("{:?}", stmt), format!("{:?}", expected_stmt));
+        }
+        Err(e) => {
+            panic!("Error - {:?}", e.get_message());
+        }
+    }
+
+    let tests = [
+        "break;",
+        "continue",
+        "if (true) { break }",
+        "while (true) { let f = fn(){ continue; }; }",
+        "while (true) { }; break",
+    ];
+    for i in 0..tests.len() {
+        let mut parser = Parser::new(tests[i]);
+        match parser.parse_program() {
+            Ok(program) => {
+                panic!("At Test No - {} - expected error, found {:?}", i, program);
+            }
+            Err(e) => {
+                assert!(e.get_message().contains("outside of a loop"));
+            }
+        }
+    }
+}
+
+#[test]
+fn test_assign_expression_parsing() {
+    let input = "x = y = 1;";
+    let identifier = |name: &str| {
+        Expression::Identifier(Identifier {
+            token: Token {
+                token_type: TokenType::IDENTIFIER,
+                literal: String::from(name),
+                line: 1,
+            },
+            value: String::from(name),
+            binding: Cell::new(Binding::Global),
+        })
+    };
+    let mut parser = Parser::new(&input);
+    let p = parser.parse_program();
+    match p {
+        Ok(res) => {
+            let stmt: &Statement = &res.stmts[0];
+            let expected_expression = Box::new(Expression::Assign(AssignExpression {
+                token: Token {
+                    token_type: TokenType::ASSIGN,
+                    literal: String::from("="),
+                    line: 1,
+                },
+                target: Rc::new(identifier("x")),
+                value: Box::new(Expression::Assign(AssignExpression {
+                    token: Token {
+                        token_type: TokenType::ASSIGN,
+                        literal: String::from("="),
+                        line: 1,
+                    },
+                    target: Rc::new(identifier("y")),
+                    value: Box::new(Expression::Integer(IntegerLiteral {
+                        token: Token {
+                            token_type: TokenType::INT,
+                            literal: String::from("1"),
+                            line: 1,
+                        },
+                        value: 1,
+                    })),
+                })),
+            }));
+            let actual_stmt = ExpressionStatement {
+                token: Token {
+                    token_type: TokenType::IDENTIFIER,
+                    literal: String::from("x"),
+                    line: 1,
+                },
+                value: expected_expression,
+            };
+            assert_eq!(format!("{:?}", stmt), format!("{:?}", actual_stmt));
+        }
+        Err(e) => {
+            panic!("Error - {:?}", e.get_message());
+        }
+    }
+}
+
+#[test]
+fn test_logical_expression_parsing() {
+    let input = "a || b && c == d;";
+    let token = |token_type: TokenType, literal: &str| Token {
+        token_type: token_type,
+        literal: String::from(literal),
+        line: 1,
+    };
+    let identifier = |name: &str| {
+        Expression::Identifier(Identifier {
+            token: token(TokenType::IDENTIFIER, name),
+            value: String::from(name),
+            binding: Cell::new(Binding::Global),
+        })
+    };
+    let mut parser = Parser::new(&input);
+    let p = parser.parse_program();
+    match p {
+        Ok(res) => {
+            let stmt: &Statement = &res.stmts[0];
+            let expected_expression = Box::new(Expression::Logical(LogicalExpression {
+                token: token(TokenType::OR, "||"),
+                operator: String::from("||"),
+                left: Rc::new(identifier("a")),
+                right: Box::new(Expression::Logical(LogicalExpression {
+                    token: token(TokenType::AND, "&&"),
+                    operator: String::from("&&"),
+                    left: Rc::new(identifier("b")),
+                    right: Box::new(Expression::Binary(BinaryExpression {
+                        token: token(TokenType::EQ, "=="),
+                        operator: String::from("=="),
+                        left: Rc::new(identifier("c")),
+                        right: Box::new(identifier("d")),
+                    })),
+                })),
+            }));
+            let actual_stmt = ExpressionStatement {
+                token: token(TokenType::IDENTIFIER, "a"),
+                value: expected_expression,
+            };
+            assert_eq!(format!("{:?}", stmt), format!("{:?}", actual_stmt));
+        }
+        Err(e) => {
+            panic!("Error - {:?}", e.get_message());
+        }
+    }
+}
+
+#[test]
+fn test_import_statement_parsing() {
+    let input = "import \"lib/math\" as math; import \"util\"; export let x = 1; math.add;";
+    let mut parser = Parser::new(&input);
+    let p = parser.parse_program();
+    match p {
+        Ok(res) => {
+            assert_eq!(res.stmts.len(), 4);
+            let Statement::Import(import) = &res.stmts[0] else {
+                panic!("Expected ImportStatement");
+            };
+            assert_eq!(import.path, "lib/math");
+            assert_eq!(import.alias.as_ref().unwrap().value, "math");
+            let Statement::Import(import) = &res.stmts[1] else {
+                panic!("Expected ImportStatement");
+            };
+            assert_eq!(import.path, "util");
+            assert!(import.alias.is_none());
+            let Statement::Export(export) = &res.stmts[2] else {
+                panic!("Expected ExportStatement");
+            };
+            assert!(matches!(export.statement.as_ref(), Statement::Let(_)));
+            let Statement::Expression(expression) = &res.stmts[3] else {
+                panic!("Expected ExpressionStatement");
+            };
+            let Expression::Get(get) = expression.value.as_ref() else {
+                panic!("Expected GetExpression");
+            };
+            assert_eq!(get.name.value, "add");
+        }
+        Err(e) => {
+            panic!("Error - {:?}", e.get_message());
+        }
+    }
+
+    let tests = [
+        "import math;",
+        "import \"math\" as;",
+        "if (true) { export let x = 1; }",
+        "export x = 1;",
+        "math.;",
+    ];
+    for i in 0..tests.len() {
+        let mut parser = Parser::new(tests[i]);
+        if parser.parse_program().is_ok() {
+            panic!("At Test No - {} - expected parse error", i);
+        }
+    }
+}
+
+#[test]
+fn test_class_statement_parsing() {
+    let input = "class Point { init(x, y) { this.x = x; this.y = y; } sum() { this.x + this.y } }; Point(1, 2).sum();";
+    let mut parser = Parser::new(&input);
+    let p = parser.parse_program();
+    match p {
+        Ok(res) => {
+            assert_eq!(res.stmts.len(), 2);
+            let Statement::Class(class) = &res.stmts[0] else {
+                panic!("Expected ClassStatement");
+            };
+            assert_eq!(class.name.value, "Point");
+            assert_eq!(class.methods.len(), 2);
+            assert_eq!(class.methods[0].name.value, "init");
+            assert_eq!(class.methods[0].function.parameters.len(), 2);
+            assert_eq!(class.methods[0].function.body.statements.len(), 2);
+            let Statement::Expression(expression_statement) =
+                &class.methods[0].function.body.statements[0]
+            else {
+                panic!("Expected ExpressionStatement");
+            };
+            let Expression::Assign(assign) = expression_statement.value.as_ref() else {
+                panic!("Expected AssignExpression");
+            };
+            let Expression::Get(target) = assign.target.as_ref() else {
+                panic!("Expected GetExpression");
+            };
+            assert!(matches!(target.object.as_ref(), Expression::This(_)));
+            assert_eq!(target.name.value, "x");
+            assert_eq!(class.methods[1].name.value, "sum");
+            assert_eq!(class.methods[1].function.parameters.len(), 0);
+        }
+        Err(e) => {
+            panic!("Error - {:?}", e.get_message());
+        }
+    }
+
+    let tests = [
+        "this.x",
+        "let f = fn() { this };",
+        "class { }",
+        "class A { let x = 1; }",
+        "class A { m() { 1 }",
+    ];
+    for i in 0..tests.len() {
+        let mut parser = Parser::new(tests[i]);
+        if parser.parse_program().is_ok() {
+            panic!("At Test No - {} - expected parse error", i);
+        }
+    }
+}
+
+#[test]
+fn test_class_inheritance_parsing() {
+    let input = "class B < A { m() { super.m() } }";
+    let mut parser = Parser::new(&input);
+    let p = parser.parse_program();
+    match p {
+        Ok(res) => {
+            let Statement::Class(class) = &res.stmts[0] else {
+                panic!("Expected ClassStatement");
+            };
+            assert_eq!(class.name.value, "B");
+            assert_eq!(class.superclass.as_ref().unwrap().value, "A");
+            let Statement::Expression(expression_statement) =
+                &class.methods[0].function.body.statements[0]
+            else {
+                panic!("Expected ExpressionStatement");
+            };
+            let Expression::Call(call) = expression_statement.value.as_ref() else {
+                panic!("Expected CallExpression");
+            };
+            let Expression::Super(super_expression) = call.funtion.as_ref() else {
+                panic!("Expected SuperExpression");
+            };
+            assert_eq!(super_expression.method.value, "m");
+        }
+        Err(e) => {
+            panic!("Error - {:?}", e.get_message());
+        }
+    }
+
+    let tests = [
+        "class A < A {}",
+        "class B < {}",
+        "super.m()",
+        "class A { m() { super.m() } }",
+        "class B < A { m() { class C { n() { super.n() } } } }",
+        "class B < A { m() { super } }",
+    ];
+    for i in 0..tests.len() {
+        let mut parser = Parser::new(tests[i]);
+        if parser.parse_program().is_ok() {
+            panic!("At Test No - {} - expected parse error", i);
+        }
+    }
+}
+
+#[test]
+fn test_match_expression_parsing() {
+    let input = "match value { 1 | -1 => \"one\", [x, _] => x, {\"k\": v} => { v }, _ => null };";
+    let mut parser = Parser::new(&input);
+    let p = parser.parse_program();
+    match p {
+        Ok(res) => {
+            assert_eq!(res.stmts.len(), 1);
+            let Statement::Expression(expression_statement) = &res.stmts[0] else {
+                panic!("Expected ExpressionStatement");
+            };
+            let Expression::Match(match_expression) = expression_statement.value.as_ref() else {
+                panic!("Expected MatchExpression");
+            };
+            assert_eq!(match_expression.arms.len(), 4);
+            match &match_expression.arms[0].pattern {
+                Pattern::Alternative(patterns) => {
+                    assert_eq!(patterns.len(), 2);
+                    assert!(matches!(patterns[0], Pattern::Literal(_)));
+                    assert!(matches!(patterns[1], Pattern::Literal(_)));
+                }
+                pattern => panic!("Expected alternative pattern, found {:?}", pattern),
+            }
+            match &match_expression.arms[1].pattern {
+                Pattern::Array(patterns) => {
+                    assert!(matches!(&patterns[0], Pattern::Binding(name) if name.value == "x"));
+                    assert!(matches!(patterns[1], Pattern::Wildcard));
+                }
+                pattern => panic!("Expected array pattern, found {:?}", pattern),
+            }
+            match &match_expression.arms[2].pattern {
+                Pattern::Hash(entries) => {
+                    assert_eq!(entries.len(), 1);
+                    assert!(matches!(&entries[0].1, Pattern::Binding(name) if name.value == "v"));
+                }
+                pattern => panic!("Expected hash pattern, found {:?}", pattern),
+            }
+            assert!(matches!(
+                match_expression.arms[3].pattern,
+                Pattern::Wildcard
+            ));
+            for arm in &match_expression.arms {
+                assert_eq!(arm.body.statements.len(), 1);
+            }
+        }
+        Err(e) => {
+            panic!("Error - {:?}", e.get_message());
+        }
+    }
+
+    let tests = [
+        "match x { 1 => 2 3 => 4 }",
+        "match x { 1 2 }",
+        "match x { [a] | b => 1 }",
+        "match x { a + 1 => 1 }",
+        "match x { {y: 1} => 1 }",
+        "match x { - a => 1 }",
+        "match x { 1 => 1",
+        "match x { [a, a] => 1 }",
+        "match x { {\"k\": a, \"j\": [a]} => 1 }",
+    ];
+    for i in 0..tests.len() {
+        let mut parser = Parser::new(tests[i]);
+        if parser.parse_program().is_ok() {
+            panic!("At Test No - {} - expected parse error", i);
+        }
+    }
+
+    let mut parser = Parser::new("match x { [a, _] => a, [_, a] => a, [b, [c, b]] => b }");
+    match parser.parse_program() {
+        Ok(_) => panic!("Expected an error for a name bound twice in a pattern"),
+        Err(e) => assert_eq!(
+            e.get_message(),
+            "Variable b is already declared in this scope at line 1"
+        ),
+    }
+}
+
+#[test]
+fn test_try_statement_parsing() {
+    let input =
+        "try { throw \"a\"; } catch (e) { e } finally { 1 }; try { 1 } finally { 2 } throw 1";
+    let mut parser = Parser::new(&input);
+    let p = parser.parse_program();
+    match p {
+        Ok(res) => {
+            assert_eq!(res.stmts.len(), 3);
+            let Statement::Try(try_statement) = &res.stmts[0] else {
+                panic!("Expected TryStatement");
+            };
+            assert!(matches!(
+                &try_statement.body.statements[0],
+                Statement::Throw(_)
+            ));
+            assert_eq!(try_statement.catch_parameter.as_ref().unwrap().value, "e");
+            assert_eq!(
+                try_statement.catch_body.as_ref().unwrap().statements.len(),
+                1
+            );
+            assert_eq!(
+                try_statement
+                    .finally_body
+                    .as_ref()
+                    .unwrap()
+                    .statements
+                    .len(),
+                1
+            );
+            let Statement::Try(try_statement) = &res.stmts[1] else {
+                panic!("Expected TryStatement");
+            };
+            assert!(try_statement.catch_parameter.is_none());
+            assert!(try_statement.catch_body.is_none());
+            assert!(try_statement.finally_body.is_some());
+            assert!(matches!(&res.stmts[2], Statement::Throw(_)));
+        }
+        Err(e) => {
+            panic!("Error - {:?}", e.get_message());
+        }
+    }
+
+    let tests = [
+        "try { 1 }",
+        "try { 1 } catch { 2 }",
+        "try { 1 } catch (e { 2 }",
+        "try 1 catch (e) { 2 }",
+        "throw;",
+    ];
+    for i in 0..tests.len() {
+        let mut parser = Parser::new(tests[i]);
+        if parser.parse_program().is_ok() {
+            panic!("At Test No - {} - expected parse error", i);
+        }
+    }
+}
+
+#[test]
+fn test_interpolated_string_parsing() {
+    let input = "\"hello ${name}, you are ${age + 1}\"";
+    let mut parser = Parser::new(&input);
+    let p = parser.parse_program();
+    match p {
+        Ok(res) => {
+            let Statement::Expression(stmt) = &res.stmts[0] else {
+                panic!("Expected ExpressionStatement");
+            };
+            let Expression::InterpolatedString(interpolated) = stmt.value.as_ref() else {
+                panic!("Expected InterpolatedString");
+            };
+            assert_eq!(interpolated.parts.len(), 4);
+            match &interpolated.parts[0] {
+                StringPart::Literal(literal) => assert_eq!(literal, "hello "),
+                part => panic!("Expected a literal, found {:?}", part),
+            }
+            match &interpolated.parts[1] {
+                StringPart::Expression(expression) => {
+                    let Expression::Identifier(identifier) = expression.as_ref() else {
+                        panic!("Expected Identifier");
+                    };
+                    assert_eq!(identifier.value, "name");
+                }
+                part => panic!("Expected an expression, found {:?}", part),
+            }
+            match &interpolated.parts[2] {
+                StringPart::Literal(literal) => assert_eq!(literal, ", you are "),
+                part => panic!("Expected a literal, found {:?}", part),
+            }
+            match &interpolated.parts[3] {
+                StringPart::Expression(expression) => {
+                    assert!(matches!(expression.as_ref(), Expression::Binary(_)));
+                }
+                part => panic!("Expected an expression, found {:?}", part),
+            }
+        }
+        Err(e) => {
+            panic!("Error - {:?}", e.get_message());
+        }
+    }
+
+    let tests = ["\"a ${}\"", "\"a ${1 2}\"", "\"a ${1\"", "\"a ${1} b"];
+    let expected_errors = vec![
+        "Expected an expression inside ${} at line 1",
+        "Expected } to close the interpolation at line 1",
+        "Unterminated string starting at line 1",
+        "Unterminated string starting at line 1",
+    ];
+    for i in 0..tests.len() {
+        let mut parser = Parser::new(tests[i]);
+        match parser.parse_program() {
+            Ok(_) => panic!("At Test No - {} - expected parse error", i),
+            Err(e) => assert_eq!(e.get_message(), expected_errors[i], "At Test No - {}", i),
+        }
+    }
+}
+
+#[test]
+fn test_doc_comment_parsing() {
+    let input = "/// Adds one
+/// to its argument
+let inc = fn(x) { x + 1 };
+let plain = 1;
+/// Not attached
+plain;
+let after = 2;
+/// Exported
+export let two = 2;
+class A {
+    /// Builds an A
+    init() { }
+    other() { }
+}";
+    let mut parser = Parser::new(&input);
+    let p = parser.parse_program();
+    match p {
+        Ok(res) => {
+            let docs: Vec<Option<String>> = res
+                .stmts
+                .iter()
+                .filter_map(|stmt| {
+                    let stmt = match stmt {
+                        Statement::Export(export) => export.statement.as_ref(),
+                        stmt => stmt,
+                    };
+                    match stmt {
+                        Statement::Let(let_statement) => return Some(let_statement.doc.clone()),
+                        _ => return None,
+                    }
+                })
+                .collect();
+            assert_eq!(
+                docs,
+                vec![
+                    Some(String::from("Adds one\nto its argument")),
+                    None,
+                    None,
+                    Some(String::from("Exported")),
+                ]
+            );
+            let Statement::Class(class) = &res.stmts[5] else {
+                panic!("Expected ClassStatement");
+            };
+            assert_eq!(class.methods[0].doc, Some(String::from("Builds an A")));
+            assert_eq!(class.methods[1].doc, None);
+        }
+        Err(e) => {
+            panic!("Error - {:?}", e.get_message());
+        }
+    }
+}
+
+#[test]
+fn test_yield_expression_parsing() {
+    let input =
+        "let g = fn() { yield 1; let x = yield; x = yield x + 1; while (true) { yield 2 } };
+let f = fn() { let inner = fn() { yield 1 }; inner };";
+    let mut parser = Parser::new(&input);
+    let p = parser.parse_program();
+    match p {
+        Ok(res) => {
+            let Statement::Let(let_statement) = &res.stmts[0] else {
+                panic!("Expected LetStatement");
+            };
+            let value = &let_statement.value;
+            let Expression::Function(function) = value.as_ref() else {
+                panic!("Expected FunctionLiteral");
+            };
+            assert!(function.is_generator);
+            let statements = &function.body.statements;
+            let Statement::Expression(yield_statement) = &statements[0] else {
+                panic!("Expected ExpressionStatement");
+            };
+            let Expression::Yield(yield_expression) = yield_statement.value.as_ref() else {
+                panic!("Expected YieldExpression");
+            };
+            assert!(yield_expression.value.is_some());
+            let Statement::Let(let_statement) = &statements[1] else {
+                panic!("Expected LetStatement");
+            };
+            let Expression::Yield(yield_expression) = let_statement.value.as_ref() else {
+                panic!("Expected YieldExpression");
+            };
+            assert!(yield_expression.value.is_none());
+            let Statement::Expression(expression_statement) = &statements[2] else {
+                panic!("Expected ExpressionStatement");
+            };
+            let Expression::Assign(assign) = expression_statement.value.as_ref() else {
+                panic!("Expected AssignExpression");
+            };
+            assert!(matches!(assign.value.as_ref(), Expression::Yield(_)));
+            assert!(matches!(&statements[3], Statement::While(_)));
+
+            //Only the function containing the yield is a generator
+            let Statement::Let(let_statement) = &res.stmts[1] else {
+                panic!("Expected LetStatement");
+            };
+            let value = &let_statement.value;
+            let Expression::Function(function) = value.as_ref() else {
+                panic!("Expected FunctionLiteral");
+            };
+            assert!(!function.is_generator);
+        }
+        Err(e) => {
+            panic!("Error - {:?}", e.get_message());
+        }
+    }
+
+    //A yield in an expression is moved into a let before its statement, like an await
+    let input = "let f = fn() { let a = 1 + (yield 2); return yield a; };";
+    let mut parser = Parser::new(input);
+    match parser.parse_program() {
+        Ok(res) => {
+            let Statement::Let(let_statement) = &res.stmts[0] else {
+                panic!("Expected LetStatement");
+            };
+            let Expression::Function(function) = let_statement.value.as_ref() else {
+                panic!("Expected FunctionLiteral");
+            };
+            assert!(function.is_generator);
+            let statements = &function.body.statements;
+            assert_eq!(statements.len(), 4);
+            let Statement::Let(hoisted) = &statements[0] else {
+                panic!("Expected LetStatement");
+            };
+            assert!(matches!(hoisted.value.as_ref(), Expression::Yield(_)));
+            let Statement::Let(sum) = &statements[1] else {
+                panic!("Expected LetStatement");
+            };
+            let Expression::Binary(binary) = sum.value.as_ref() else {
+                panic!("Expected BinaryExpression");
+            };
+            let Expression::Identifier(temporary) = binary.right.as_ref() else {
+                panic!("Expected Identifier");
+            };
+            assert_eq!(temporary.value, hoisted.identifier.value);
+            assert!(matches!(statements[2], Statement::Let(_)));
+            assert!(matches!(statements[3], Statement::Return(_)));
+        }
+        Err(e) => {
+            panic!("Error - {:?}", e.get_message());
+        }
+    }
+
+    let tests = [
+        "let f = fn() { print(yield 1, yield); };",
+        "let f = fn() { if (yield) { yield [yield 1]; } };",
+        "let f = fn() { while ((yield 1) < 2) { } };",
+        "let f = fn() { let a = if (true) { yield 1; }; };",
+        "let f = fn() { match 1 { _ => yield 2 } + 1; };",
+    ];
+    for i in 0..tests.len() {
+        let mut parser = Parser::new(tests[i]);
+        if let Err(e) = parser.parse_program() {
+            panic!("At Test No - {} - {:?}", i, e.get_message());
+        }
+    }
+
+    let tests = ["yield 1;", "class A { init() { yield 1; } }"];
+    let expected_errors = [
+        "yield outside of a function at line 1",
+        "init can not be a generator at line 1",
+    ];
+    for i in 0..tests.len() {
+        let mut parser = Parser::new(tests[i]);
+        match parser.parse_program() {
+            Ok(_) => panic!("At Test No - {} - expected parse error", i),
+            Err(e) => assert_eq!(e.get_message(), expected_errors[i], "At Test No - {}", i),
+        }
+    }
+}
+
+#[test]
+fn test_await_expression_parsing() {
+    let input = "let f = async fn() { await sleep(1); let x = await g(); x = await x; };
+class A { async get() { await 1; } }
+await f();";
+    let mut parser = Parser::new(&input);
+    let p = parser.parse_program();
+    match p {
+        Ok(res) => {
+            let Statement::Let(let_statement) = &res.stmts[0] else {
+                panic!("Expected LetStatement");
+            };
+            let value = &let_statement.value;
+            let Expression::Function(function) = value.as_ref() else {
+                panic!("Expected FunctionLiteral");
+            };
+            assert!(function.is_async);
+            assert!(!function.is_generator);
+            let statements = &function.body.statements;
+            let Statement::Expression(expression_statement) = &statements[0] else {
+                panic!("Expected ExpressionStatement");
+            };
+            let Expression::Await(await_expression) = expression_statement.value.as_ref() else {
+                panic!("Expected AwaitExpression");
+            };
+            assert!(matches!(
+                await_expression.value.as_ref(),
+                Expression::Call(_)
+            ));
+            let Statement::Let(let_statement) = &statements[1] else {
+                panic!("Expected LetStatement");
+            };
+            assert!(matches!(let_statement.value.as_ref(), Expression::Await(_)));
+
+            let Statement::Class(class) = &res.stmts[1] else {
+                panic!("Expected ClassStatement");
+            };
+            assert!(class.methods[0].function.is_async);
+
+            //Outside of functions await can be used anywhere
+            let Statement::Expression(statement) = &res.stmts[2] else {
+                panic!("Expected ExpressionStatement");
+            };
+            assert!(matches!(statement.value.as_ref(), Expression::Await(_)));
+        }
+        Err(e) => {
+            panic!("Error - {:?}", e.get_message());
+        }
+    }
+
+    //Inside async functions an await in an expression is moved into a let before its statement
+    let input = "let f = async fn() { let a = 1 + await 2; return await a; };";
+    let mut parser = Parser::new(input);
+    match parser.parse_program() {
+        Ok(res) => {
+            let Statement::Let(let_statement) = &res.stmts[0] else {
+                panic!("Expected LetStatement");
+            };
+            let Expression::Function(function) = let_statement.value.as_ref() else {
+                panic!("Expected FunctionLiteral");
+            };
+            let statements = &function.body.statements;
+            assert_eq!(statements.len(), 4);
+            let Statement::Let(hoisted) = &statements[0] else {
+                panic!("Expected LetStatement");
+            };
+            assert!(matches!(hoisted.value.as_ref(), Expression::Await(_)));
+            let Statement::Let(sum) = &statements[1] else {
+                panic!("Expected LetStatement");
+            };
+            let Expression::Binary(binary) = sum.value.as_ref() else {
+                panic!("Expected BinaryExpression");
+            };
+            let Expression::Identifier(temporary) = binary.right.as_ref() else {
+                panic!("Expected Identifier");
+            };
+            assert_eq!(temporary.value, hoisted.identifier.value);
+            assert!(matches!(statements[2], Statement::Let(_)));
+            assert!(matches!(statements[3], Statement::Return(_)));
+        }
+        Err(e) => {
+            panic!("Error - {:?}", e.get_message());
+        }
+    }
+
+    let tests = [
+        "let f = fn() { await 1; };",
+        "let f = async fn() { yield 1; };",
+        "let f = async 1;",
+        "let f = async fn() { await; };",
+        "class A { async init() { } }",
+    ];
+    let expected_errors = [
+        "await outside of an async function at line 1",
+        "yield can not be used in an async function at line 1",
+        "Expected fn after async at line 1",
+        "Expected an expression after await at line 1",
+        "init can not be async at line 1",
+    ];
+    for i in 0..tests.len() {
+        let mut parser = Parser::new(tests[i]);
+        match parser.parse_program() {
+            Ok(_) => panic!("At Test No - {} - expected parse error", i),
+            Err(e) => assert_eq!(e.get_message(), expected_errors[i], "At Test No - {}", i),
+        }
+    }
+}
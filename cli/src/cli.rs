@@ -23,7 +23,7 @@ impl Cli {
             Some(types::Commands::Start {}) => {
                 commands::start();
             }
-            Some(types::Commands::Run { path }) => commands::run(path),
+            Some(types::Commands::Run { path, vm }) => commands::run(path, *vm),
             Some(types::Commands::Jit { path, backend }) => {
                 let compiler_backend: CompilerBackend = CompilerBackend::from(backend);
                 commands::jit(path, &compiler_backend)
@@ -1,9 +1,11 @@
 use crate::repl;
 use bolt::{
-    error::BoltError, evaluator::evaluator::Evaluator, parser::parser::Parser, Compiler,
+    error::{BoltError, BoltErrorType},
+    evaluator::evaluator::Evaluator,
+    parser::parser::Parser,
     CompilerBackend, Factory,
 };
-use std::fs;
+use std::{fs, process};
 
 use regex::Regex;
 
@@ -19,25 +21,45 @@ fn extract_filename(filepath: &str) -> Option<String> {
     }
 }
 
+//Errors that reach the top level are reported with their kind and line
+pub fn format_error(error: &impl BoltError) -> String {
+    let kind = error.get_type();
+    match (kind.clone(), error.get_line()) {
+        //Parse errors already mention the line in their message
+        (BoltErrorType::PARSE, _) | (_, None) => {
+            format!("Uncaught {}: {}", kind.name(), error.get_message())
+        }
+        (_, Some(line)) => format!(
+            "Uncaught {}: {} at line {}",
+            kind.name(),
+            error.get_message(),
+            line
+        ),
+    }
+}
+
 pub fn start() {
     repl::start_repl().unwrap();
 }
 
-pub fn run(path: &String) {
+pub fn run(path: &String, vm: bool) {
     let contents = fs::read_to_string(path).expect("Should have been able to read the file");
-    let evaluator = Evaluator::new(&contents, "evaluator", None, false, None, "");
+    let backend = match vm {
+        true => Some(CompilerBackend::VM),
+        false => None,
+    };
+    let evaluator = Evaluator::new(&contents, path, None, false, backend, "");
     match evaluator.eval() {
         Some(evaluated) => match evaluated {
             Ok(result) => {
                 println!("{}", result.inspect());
             }
             Err(e) => {
-                panic!("{}", e.get_message());
+                eprintln!("{}", format_error(&e));
+                process::exit(1);
             }
         },
-        None => {
-            panic!("Something went wrong!");
-        }
+        None => {}
     }
 }
 
@@ -56,7 +78,8 @@ pub fn jit(path: &String, backend: &CompilerBackend) {
         Some(evaluated) => match evaluated {
             Ok(_) => {}
             Err(e) => {
-                panic!("{}", e.get_message());
+                eprintln!("{}", format_error(&e));
+                process::exit(1);
             }
         },
         None => {}
@@ -76,7 +99,10 @@ pub fn compile(
     match parser.parse_program() {
         Ok(program) => {
             let mut compiler = Factory::new(*backend, program, &filename);
-            compiler.compile();
+            if let Err(e) = compiler.compile() {
+                eprintln!("{}", format_error(&e));
+                process::exit(1);
+            }
             if bytecode {
                 println!("Compiling to bytecode");
                 compiler.bytecode_to_file(out, target)
@@ -3,6 +3,11 @@ pub mod commands;
 pub mod repl;
 pub mod types;
 
+use std::{process, thread};
+
+//Native stack for the interpreter, enough for the deepest recursion the call depth limit allows
+const STACK_SIZE: usize = 128 * 1024 * 1024;
+
 fn main() {
     println!(
         r###"
@@ -16,5 +21,11 @@ fn main() {
     Welcome to Bolt! Language built for learning and educational purpose.
     "###
     );
-    cli::Cli::init();
+    let interpreter = thread::Builder::new()
+        .stack_size(STACK_SIZE)
+        .spawn(cli::Cli::init)
+        .expect("Error starting the interpreter thread");
+    if interpreter.join().is_err() {
+        process::exit(101);
+    }
 }
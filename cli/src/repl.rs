@@ -1,7 +1,5 @@
-use bolt::{
-    error::BoltError,
-    evaluator::{environment::Environment, evaluator::Evaluator},
-};
+use crate::commands::format_error;
+use bolt::evaluator::{environment::Environment, evaluator::Evaluator};
 use std::io::{self, Write};
 
 pub fn start_repl() -> io::Result<()> {
@@ -33,12 +31,10 @@ pub fn start_repl() -> io::Result<()> {
                     println!("{}", result.inspect());
                 }
                 Err(e) => {
-                    panic!("{}", e.get_message());
+                    eprintln!("{}", format_error(&e));
                 }
             },
-            None => {
-                panic!("Something went wrong!");
-            }
+            None => {}
         }
         user_input.clear();
     }
@@ -4,12 +4,14 @@ use clap::{Subcommand, ValueEnum};
 #[derive(ValueEnum, Debug, Clone, Copy)]
 pub enum Backend {
     LLVM,
+    VM,
 }
 
 impl From<&Backend> for CompilerBackend {
     fn from(value: &Backend) -> Self {
         match value {
             Backend::LLVM => CompilerBackend::LLVM,
+            Backend::VM => CompilerBackend::VM,
         }
     }
 }
@@ -20,6 +22,9 @@ pub enum Commands {
     Run {
         #[clap(short, long)]
         path: String,
+        //Run on the bytecode virtual machine instead of the tree walking evaluator
+        #[clap(long, default_value = "false")]
+        vm: bool,
     },
     Jit {
         #[clap(short, long)]